@@ -4,7 +4,16 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use cw_erc20::{
-    AllowanceResponse, BalanceResponse, Constants, ExecuteMsg, InstantiateMsg, QueryMsg,
+    AllowanceResponse, ApproveResponse, AttestMintResponse, BalanceResponse, BatchMintCW20Response,
+    BatchSendToEvmResponse, BatchTransferResponse, BridgeAllowlistModeResponse,
+    BridgeAllowlistResponse, BridgeConfigResponse, BridgeStatsResponse, BridgeTransfer,
+    BridgeTransfersResponse, BurnResponse, ClaimResponse, ClaimableResponse, Constants, Escrow,
+    EscrowedResponse, ExecuteMsg, FeeExemptionsResponse, FeeSchedule, FeeTierResponse,
+    FreezeAccountResponse, FrozenAccountsResponse, GuardianSet, HistoryResponse, InstantiateMsg,
+    MigrateMsg, MintAttestation, MintCW20Response, MintOutcome, NonceResponse, PendingMint, PendingMintsResponse,
+    QueryMsg, Refund, RefundsResponse, ReleaseEscrowResponse, ReportReservesResponse,
+    ReserveDiscrepanciesResponse, ReserveStatusResponse, RoleResponse, SendToEvmResponse, SignDoc,
+    TransferResponse, UpdateBridgeAllowlistResponse, UpdateFeeExemptionsResponse,
 };
 
 fn main() {
@@ -16,7 +25,50 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
     export_schema(&schema_for!(BalanceResponse), &out_dir);
     export_schema(&schema_for!(AllowanceResponse), &out_dir);
+    export_schema(&schema_for!(BridgeConfigResponse), &out_dir);
+    export_schema(&schema_for!(FrozenAccountsResponse), &out_dir);
+    export_schema(&schema_for!(BridgeAllowlistResponse), &out_dir);
+    export_schema(&schema_for!(HistoryResponse), &out_dir);
+    export_schema(&schema_for!(BridgeTransfer), &out_dir);
+    export_schema(&schema_for!(BridgeTransfersResponse), &out_dir);
+    export_schema(&schema_for!(BridgeStatsResponse), &out_dir);
+    export_schema(&schema_for!(ReserveStatusResponse), &out_dir);
+    export_schema(&schema_for!(ReserveDiscrepanciesResponse), &out_dir);
+    export_schema(&schema_for!(NonceResponse), &out_dir);
+    export_schema(&schema_for!(ClaimableResponse), &out_dir);
+    export_schema(&schema_for!(Escrow), &out_dir);
+    export_schema(&schema_for!(EscrowedResponse), &out_dir);
+    export_schema(&schema_for!(Refund), &out_dir);
+    export_schema(&schema_for!(GuardianSet), &out_dir);
+    export_schema(&schema_for!(MintAttestation), &out_dir);
+    export_schema(&schema_for!(PendingMint), &out_dir);
+    export_schema(&schema_for!(FeeSchedule), &out_dir);
+    export_schema(&schema_for!(RefundsResponse), &out_dir);
+    export_schema(&schema_for!(SignDoc), &out_dir);
     export_schema(&schema_for!(Constants), &out_dir);
+    // Execute response data
+    export_schema(&schema_for!(TransferResponse), &out_dir);
+    export_schema(&schema_for!(BatchTransferResponse), &out_dir);
+    export_schema(&schema_for!(ApproveResponse), &out_dir);
+    export_schema(&schema_for!(BurnResponse), &out_dir);
+    export_schema(&schema_for!(MintCW20Response), &out_dir);
+    export_schema(&schema_for!(MintOutcome), &out_dir);
+    export_schema(&schema_for!(BatchMintCW20Response), &out_dir);
+    export_schema(&schema_for!(ClaimResponse), &out_dir);
+    export_schema(&schema_for!(AttestMintResponse), &out_dir);
+    export_schema(&schema_for!(PendingMintsResponse), &out_dir);
+    export_schema(&schema_for!(UpdateFeeExemptionsResponse), &out_dir);
+    export_schema(&schema_for!(FeeExemptionsResponse), &out_dir);
+    export_schema(&schema_for!(FeeTierResponse), &out_dir);
+    export_schema(&schema_for!(ReleaseEscrowResponse), &out_dir);
+    export_schema(&schema_for!(SendToEvmResponse), &out_dir);
+    export_schema(&schema_for!(BatchSendToEvmResponse), &out_dir);
+    export_schema(&schema_for!(ReportReservesResponse), &out_dir);
+    export_schema(&schema_for!(RoleResponse), &out_dir);
+    export_schema(&schema_for!(FreezeAccountResponse), &out_dir);
+    export_schema(&schema_for!(BridgeAllowlistModeResponse), &out_dir);
+    export_schema(&schema_for!(UpdateBridgeAllowlistResponse), &out_dir);
 }
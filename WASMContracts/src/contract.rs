@@ -1,33 +1,107 @@
 use cosmwasm_std::{
-    entry_point, from_slice, to_binary, to_vec, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Storage, Uint128, CosmosMsg
+    entry_point, from_slice, to_binary, to_vec, Addr, Binary, BlockInfo, Deps, DepsMut, Env,
+    MessageInfo, Order, Response, StdError, StdResult, Storage, Timestamp, Uint128, Uint64, CosmosMsg
 };
 use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
 use std::convert::TryInto;
-use bech32::{self, FromBase32};
+use bech32::{self, FromBase32, ToBase32, Variant};
 
 
+use crate::cw20_base::{self, CW20_BASE_CONTRACT};
 use crate::error::ContractError;
-use crate::msg::{AllowanceResponse, BalanceResponse, ExecuteMsg, InstantiateMsg, QueryMsg,SendToEvmMsg};
-use crate::state::Constants;
+use crate::events;
+use crate::msg::{
+    AllowanceResponse, ApproveResponse, AttestMintResponse, BalanceResponse, BatchMintCW20Response,
+    BatchSendToEvmResponse, BatchTransferResponse, BridgeAllowlistModeResponse,
+    BridgeAllowlistResponse, BridgeConfigResponse, BridgeStatsResponse, BridgeTransfersResponse,
+    BurnResponse, ClaimResponse, ClaimableResponse, DailyBridgeVolume, EscrowedResponse, ExecuteMsg,
+    FeeExemptionsResponse, FeeTierResponse, FreezeAccountResponse, FrozenAccountsResponse,
+    HistoryResponse, InstantiateMsg, MigrateMsg, MintCW20Response, MintItem, MintItemResult,
+    MintItemStatus, MintOutcome, NonceResponse, PendingMintsResponse, QueryMsg, RefundsResponse,
+    ReleaseEscrowResponse, ReportReservesResponse, ReserveDiscrepanciesResponse,
+    ReserveStatusResponse, RoleResponse, SendToEvmMsg, SendToEvmResponse, SignDoc, SignedAction,
+    SignedPayload, TransferItem, TransferResponse, UpdateBridgeAllowlistResponse,
+    UpdateFeeExemptionsResponse,
+};
+use crate::signature;
+use crate::state::{
+    BridgeDirection, BridgeDirections, BridgeTransfer, BridgeTransferStatus, BridgeVolume,
+    Constants, Escrow, FeeSchedule, FeeTier, GuardianSet, GuardianVote, HistoryEntry, HistoryKind,
+    MintAttestation, PendingMint, PendingMintStatus, Refund, RefundReason, ReserveReport, Role,
+};
 
 pub const PREFIX_CONFIG: &[u8] = b"config";
 pub const PREFIX_BALANCES: &[u8] = b"balances";
 pub const PREFIX_ALLOWANCES: &[u8] = b"allowances";
+pub const PREFIX_ROLES: &[u8] = b"roles";
+pub const PREFIX_FROZEN: &[u8] = b"frozen";
+pub const PREFIX_BRIDGE_ALLOWLIST: &[u8] = b"bridge_allowlist";
+pub const PREFIX_HISTORY: &[u8] = b"history";
+pub const PREFIX_HISTORY_SEQ: &[u8] = b"history_seq";
+pub const PREFIX_BRIDGE_TRANSFERS: &[u8] = b"bridge_transfers";
+pub const PREFIX_BRIDGE_TRANSFERS_BY_ACCOUNT: &[u8] = b"bridge_transfers_by_account";
+pub const PREFIX_PENDING_OUTBOUND: &[u8] = b"pending_outbound";
+pub const PREFIX_QUEUED_OUTBOUND: &[u8] = b"queued_outbound";
+pub const PREFIX_PENDING_MINTS: &[u8] = b"pending_mints";
+pub const PREFIX_PENDING_MINT_EVENTS: &[u8] = b"pending_mint_events";
+pub const PREFIX_FEE_EXEMPT: &[u8] = b"fee_exempt";
+pub const PREFIX_ACCOUNT_VOLUME: &[u8] = b"account_volume";
+pub const PREFIX_DAILY_STATS: &[u8] = b"daily_stats";
+pub const PREFIX_RESERVE_DISCREPANCIES: &[u8] = b"reserve_discrepancies";
+pub const PREFIX_PROCESSED_MINTS: &[u8] = b"processed_mints";
+pub const PREFIX_NONCES: &[u8] = b"nonces";
+pub const PREFIX_CLAIMABLE: &[u8] = b"claimable";
+pub const PREFIX_ESCROWS: &[u8] = b"escrows";
+pub const PREFIX_REFUNDS: &[u8] = b"refunds";
+pub const PREFIX_MINT_ATTESTATIONS: &[u8] = b"mint_attestations";
 
 pub const KEY_CONSTANTS: &[u8] = b"constants";
 pub const KEY_TOTAL_SUPPLY: &[u8] = b"total_supply";
+pub const KEY_DIRECTIONS: &[u8] = b"directions";
+pub const KEY_OWNER: &[u8] = b"owner";
+pub const KEY_CAP: &[u8] = b"cap";
+pub const KEY_ALLOWLIST_ENABLED: &[u8] = b"allowlist_enabled";
+pub const KEY_HISTORY_LIMIT: &[u8] = b"history_limit";
+pub const KEY_BRIDGE_TRANSFER_SEQ: &[u8] = b"bridge_transfer_seq";
+pub const KEY_BRIDGE_STATS: &[u8] = b"bridge_stats";
+pub const KEY_OUTBOUND_IN_FLIGHT: &[u8] = b"outbound_in_flight";
+pub const KEY_PENDING_INBOUND: &[u8] = b"pending_inbound";
+pub const KEY_LAST_RESERVE_REPORT: &[u8] = b"last_reserve_report";
+pub const KEY_RESERVE_AUTO_PAUSE: &[u8] = b"reserve_auto_pause";
+pub const KEY_GUARDIANS: &[u8] = b"guardians";
+pub const KEY_TIMELOCK_THRESHOLD: &[u8] = b"timelock_threshold";
+pub const KEY_TIMELOCK_DELAY: &[u8] = b"timelock_delay";
+pub const KEY_OPTIMISTIC_THRESHOLD: &[u8] = b"optimistic_threshold";
+pub const KEY_CHALLENGE_PERIOD: &[u8] = b"challenge_period";
+pub const KEY_SENDER_CANCEL_ENABLED: &[u8] = b"sender_cancel_enabled";
+pub const KEY_CLAIM_BY_SENDER: &[u8] = b"claim_by_sender";
+pub const KEY_PENDING_MINT_SEQ: &[u8] = b"pending_mint_seq";
+pub const KEY_FEE_SCHEDULE: &[u8] = b"fee_schedule";
+
+// Pagination bounds for list queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+const SECONDS_PER_DAY: u64 = 86_400;
+const MAX_FEE_BPS: u16 = 10_000;
+// Upper bound on the daily buckets returned by a single BridgeStats query
+const MAX_STATS_DAYS: usize = 366;
+
+pub const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Bech32 prefix of OKC accounts, used to derive the module caller from the EVM contract
+pub const ADDRESS_PREFIX: &str = "ex";
 
 
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
 
-    let total_supply: u128 = 0;
+    let mut total_supply: u128 = 0;
 
     // Check name, symbol, decimals
     if !is_valid_name(&msg.name) {
@@ -39,6 +113,41 @@ pub fn instantiate(
     if msg.decimals > 18 {
         return Err(ContractError::DecimalsExceeded {});
     }
+    evm_address_bytes(&msg.evm_contract)?;
+
+    let owner = match msg.owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => info.sender,
+    };
+
+    // Seed initial balances
+    let mut seeded: Vec<Addr> = Vec::with_capacity(msg.initial_balances.len());
+    for row in msg.initial_balances {
+        let address = deps.api.addr_validate(&row.address)?;
+        if seeded.contains(&address) {
+            return Err(ContractError::DuplicateInitialBalanceAddresses {});
+        }
+        let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
+        balances_store.set(address.as_str().as_bytes(), &row.amount.u128().to_be_bytes());
+        total_supply = Uint128::from(total_supply)
+            .checked_add(row.amount)
+            .map_err(StdError::from)?
+            .u128();
+        seeded.push(address);
+    }
+    if let Some(cap) = msg.cap {
+        if total_supply > cap.u128() {
+            return Err(ContractError::CapExceeded {
+                cap: cap.u128(),
+                required: total_supply,
+            });
+        }
+    }
+
+    for caller in msg.bridge_callers {
+        let caller = deps.api.addr_validate(&caller)?;
+        grant_role(deps.storage, Role::BridgeCaller, &caller);
+    }
 
     let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
     let constants = to_vec(&Constants {
@@ -49,6 +158,17 @@ pub fn instantiate(
     })?;
     config_store.set(KEY_CONSTANTS, &constants);
     config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+    config_store.set(
+        KEY_DIRECTIONS,
+        &to_vec(&BridgeDirections {
+            inbound: true,
+            outbound: true,
+        })?,
+    );
+    config_store.set(KEY_OWNER, owner.as_str().as_bytes());
+    if let Some(cap) = msg.cap {
+        config_store.set(KEY_CAP, &cap.u128().to_be_bytes());
+    }
     
     Ok(Response::default())
 }
@@ -63,8 +183,15 @@ pub fn execute(
     match msg {
         ExecuteMsg::Approve { spender, amount } => try_approve(deps, env, info, spender, &amount),
         ExecuteMsg::Transfer { recipient, amount } => try_transfer(deps, env, info, recipient, &amount),
+        ExecuteMsg::BatchTransfer { transfers } => try_batch_transfer(deps, env, info, transfers),
         ExecuteMsg::Burn { amount } => try_burn(deps, env, info, &amount),
-        ExecuteMsg::MintCW20 { recipient, amount } => try_mint_cw20(deps, env, info, recipient, amount),
+        ExecuteMsg::MintCW20 {
+            recipient,
+            amount,
+            event_id,
+            evm_sender,
+        } => try_mint_cw20(deps, env, info, recipient, amount, event_id, evm_sender),
+        ExecuteMsg::BatchMintCW20 { mints } => try_batch_mint_cw20(deps, env, info, mints),
         ExecuteMsg::TransferFrom {
             owner,
             recipient,
@@ -73,12 +200,289 @@ pub fn execute(
         ExecuteMsg::SendToEvm {
             recipient,
             amount,
-        } => try_send_to_erc20(deps, env, info, recipient, amount),
+            deadline,
+        } => try_send_to_erc20(deps, env, info, recipient, amount, deadline),
+        ExecuteMsg::BatchSendToEvm { transfers } => try_batch_send_to_evm(deps, env, info, transfers),
+        ExecuteMsg::UpdateConfig {
+            evm_contract,
+            name,
+            symbol,
+            inbound_enabled,
+            outbound_enabled,
+            history_limit,
+            reserve_auto_pause,
+            timelock_threshold,
+            timelock_delay,
+            optimistic_threshold,
+            challenge_period,
+            sender_cancel_enabled,
+            claim_by_sender,
+        } => try_update_config(
+            deps,
+            env,
+            info,
+            evm_contract,
+            name,
+            symbol,
+            inbound_enabled,
+            outbound_enabled,
+            history_limit,
+            reserve_auto_pause,
+            timelock_threshold,
+            timelock_delay,
+            optimistic_threshold,
+            challenge_period,
+            sender_cancel_enabled,
+            claim_by_sender,
+        ),
+        ExecuteMsg::GrantRole { role, address } => try_grant_role(deps, env, info, role, address),
+        ExecuteMsg::RevokeRole { role, address } => try_revoke_role(deps, env, info, role, address),
+        ExecuteMsg::FreezeAccount { address } => try_freeze_account(deps, env, info, address),
+        ExecuteMsg::UnfreezeAccount { address } => try_unfreeze_account(deps, env, info, address),
+        ExecuteMsg::SetBridgeAllowlistMode { enabled } => {
+            try_set_bridge_allowlist_mode(deps, env, info, enabled)
+        }
+        ExecuteMsg::UpdateBridgeAllowlist { add, remove } => {
+            try_update_bridge_allowlist(deps, env, info, add, remove)
+        }
+        ExecuteMsg::UpdateFeeSchedule {
+            collector,
+            base_fee_bps,
+            tiers,
+            window_days,
+        } => try_update_fee_schedule(deps, env, info, collector, base_fee_bps, tiers, window_days),
+        ExecuteMsg::UpdateFeeExemptions { add, remove } => {
+            try_update_fee_exemptions(deps, env, info, add, remove)
+        }
+        ExecuteMsg::UpdateGuardians {
+            guardians,
+            threshold,
+        } => try_update_guardians(deps, env, info, guardians, threshold),
+        ExecuteMsg::AttestMint {
+            event_id,
+            recipient,
+            amount,
+            evm_sender,
+        } => try_attest_mint(deps, env, info, event_id, recipient, amount, evm_sender),
+        ExecuteMsg::FinalizeMint { id } => try_finalize_mint(deps, env, info, id),
+        ExecuteMsg::Challenge { id } => try_challenge(deps, env, info, id),
+        ExecuteMsg::ResolveMint { id, approve } => try_resolve_mint(deps, env, info, id, approve),
+        ExecuteMsg::ExecuteQueued { id } => try_execute_queued(deps, env, info, id),
+        ExecuteMsg::CancelQueued { id } => try_cancel_queued(deps, env, info, id),
+        ExecuteMsg::CancelSendToEvm { id } => try_cancel_send_to_evm(deps, env, info, id),
+        ExecuteMsg::ConfirmSendToEvm { id } => try_confirm_send_to_evm(deps, env, info, id),
+        ExecuteMsg::ReportReserves {
+            reserves,
+            evm_block,
+        } => try_report_reserves(deps, env, info, reserves, evm_block),
+        ExecuteMsg::Permit {
+            owner_pubkey,
+            spender,
+            amount,
+            expiration,
+            nonce,
+            signature,
+        } => try_permit(
+            deps,
+            env,
+            info,
+            owner_pubkey,
+            spender,
+            amount,
+            expiration,
+            nonce,
+            signature,
+        ),
+        ExecuteMsg::ExecuteSigned {
+            payload,
+            pubkey,
+            signature,
+        } => try_execute_signed(deps, env, info, payload, pubkey, signature),
+        ExecuteMsg::Claim {
+            evm_address,
+            recipient,
+            signature,
+        } => try_claim(deps, env, info, evm_address, recipient, signature),
+        ExecuteMsg::ReleaseEscrow {
+            event_id,
+            recipient,
+            signature,
+        } => try_release_escrow(deps, env, info, event_id, recipient, signature),
+    }
+}
+
+#[entry_point]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    match msg {
+        MigrateMsg::FromCw20Base {
+            evm_contract,
+            owner,
+            bridge_callers,
+        } => migrate_from_cw20_base(deps, env, evm_contract, owner, bridge_callers),
+        MigrateMsg::Upgrade {} => migrate_upgrade(deps),
+    }
+}
+
+// Accepts new code for a deployment of this contract, either instantiated
+// directly or already converted from cw20-base, and records the new version
+fn migrate_upgrade(deps: DepsMut) -> Result<Response, ContractError> {
+    let previous = match cw20_base::read_contract_version(deps.storage)? {
+        Some(version) if version.contract == CONTRACT_NAME => version.version,
+        None if ReadonlyPrefixedStorage::new(deps.storage, PREFIX_CONFIG)
+            .get(KEY_CONSTANTS)
+            .is_some() =>
+        {
+            String::new()
+        }
+        other => {
+            return Err(ContractError::UnsupportedMigration {
+                contract: other.map(|version| version.contract).unwrap_or_default(),
+            })
+        }
+    };
+    deps.storage.set(
+        cw20_base::KEY_CONTRACT_INFO,
+        &to_vec(&cw20_base::ContractVersion {
+            contract: CONTRACT_NAME.to_string(),
+            version: CONTRACT_VERSION.to_string(),
+        })?,
+    );
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from", CONTRACT_NAME)
+        .add_attribute("previous_version", previous)
+        .add_attribute("version", CONTRACT_VERSION))
+}
+
+/**
+ * Converts a cw20-base deployment in place: holders keep their balances and
+ * unexpired allowances, and the token is paired with `evm_contract`.
+ * The owner defaults to the cw20-base minter and the minter cap becomes the supply cap.
+ */
+fn migrate_from_cw20_base(
+    deps: DepsMut,
+    env: Env,
+    evm_contract: String,
+    owner: Option<String>,
+    bridge_callers: Vec<String>,
+) -> Result<Response, ContractError> {
+    let version = cw20_base::read_contract_version(deps.storage)?;
+    match version {
+        Some(version) if version.contract == CW20_BASE_CONTRACT => {}
+        other => {
+            return Err(ContractError::UnsupportedMigration {
+                contract: other.map(|version| version.contract).unwrap_or_default(),
+            })
+        }
+    }
+    let token_info = cw20_base::read_token_info(deps.storage)?
+        .ok_or_else(|| StdError::not_found("cw20-base token info"))?;
+    if !is_valid_name(&token_info.name) {
+        return Err(ContractError::NameWrongFormat {});
+    }
+    if !is_valid_symbol(&token_info.symbol) {
+        return Err(ContractError::TickerWrongSymbolFormat {});
+    }
+
+    evm_address_bytes(&evm_contract)?;
+    // Without an owner every admin path would be unreachable after the migration
+    let owner = match owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => match &token_info.mint {
+            Some(mint) => mint.minter.clone(),
+            None => return Err(ContractError::MigrationOwnerRequired {}),
+        },
+    };
+
+    // Move balances
+    let balances = cw20_base::read_balances(deps.storage)?;
+    for (holder, balance) in balances.iter() {
+        let mut old_store = PrefixedStorage::new(deps.storage, cw20_base::NAMESPACE_BALANCES);
+        old_store.remove(holder);
+        let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
+        balances_store.set(holder, &balance.u128().to_be_bytes());
+    }
+
+    // Move allowances, dropping the ones that already expired
+    let allowances = cw20_base::read_allowances(deps.storage)?;
+    let mut migrated_allowances = 0usize;
+    for (owner_key, spender_key, allowance) in allowances {
+        let mut old_store = PrefixedStorage::multilevel(
+            deps.storage,
+            &[cw20_base::NAMESPACE_ALLOWANCES, &owner_key],
+        );
+        old_store.remove(&spender_key);
+        if allowance.expires.is_expired(&env.block) {
+            continue;
+        }
+        let (owner_address, spender_address) =
+            match (String::from_utf8(owner_key), String::from_utf8(spender_key)) {
+                (Ok(owner_address), Ok(spender_address)) => (owner_address, spender_address),
+                _ => return Err(ContractError::CorruptedDataFound {}),
+            };
+        write_allowance(
+            deps.storage,
+            &Addr::unchecked(owner_address),
+            &Addr::unchecked(spender_address),
+            allowance.allowance.u128(),
+        )?;
+        migrated_allowances += 1;
+    }
+
+    // cw20-base also indexes allowances by spender, nothing here reads it
+    for key in cw20_base::read_keys(deps.storage, cw20_base::NAMESPACE_ALLOWANCES_SPENDER) {
+        let mut old_store =
+            PrefixedStorage::new(deps.storage, cw20_base::NAMESPACE_ALLOWANCES_SPENDER);
+        old_store.remove(&key);
+    }
+
+    for caller in bridge_callers {
+        let caller = deps.api.addr_validate(&caller)?;
+        grant_role(deps.storage, Role::BridgeCaller, &caller);
+    }
+
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    let constants = to_vec(&Constants {
+        name: token_info.name,
+        symbol: token_info.symbol,
+        decimals: token_info.decimals,
+        contract: evm_contract,
+    })?;
+    config_store.set(KEY_CONSTANTS, &constants);
+    config_store.set(KEY_TOTAL_SUPPLY, &token_info.total_supply.u128().to_be_bytes());
+    config_store.set(
+        KEY_DIRECTIONS,
+        &to_vec(&BridgeDirections {
+            inbound: true,
+            outbound: true,
+        })?,
+    );
+    config_store.set(KEY_OWNER, owner.as_str().as_bytes());
+    if let Some(cap) = token_info.mint.and_then(|mint| mint.cap) {
+        config_store.set(KEY_CAP, &cap.u128().to_be_bytes());
     }
+
+    deps.storage.remove(cw20_base::KEY_TOKEN_INFO);
+    deps.storage.remove(cw20_base::KEY_MARKETING_INFO);
+    deps.storage.remove(cw20_base::KEY_LOGO);
+    deps.storage.set(
+        cw20_base::KEY_CONTRACT_INFO,
+        &to_vec(&cw20_base::ContractVersion {
+            contract: CONTRACT_NAME.to_string(),
+            version: CONTRACT_VERSION.to_string(),
+        })?,
+    );
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from", CW20_BASE_CONTRACT)
+        .add_attribute("balances", balances.len().to_string())
+        .add_attribute("allowances", migrated_allowances.to_string()))
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::Balance { address } => {
             let address_key = deps.api.addr_validate(&address)?;
@@ -97,6 +501,242 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             })?;
             Ok(out)
         }
+        QueryMsg::BridgeConfig {} => {
+            let out = to_binary(&read_bridge_config(deps.storage)?)?;
+            Ok(out)
+        }
+        QueryMsg::FrozenAccounts { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = calc_range_start(start_after.as_ref().map(|s| s.as_bytes()));
+            let frozen_store = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_FROZEN);
+            let accounts = frozen_store
+                .range(start.as_deref(), None, Order::Ascending)
+                .take(limit)
+                .map(|(key, _)| {
+                    String::from_utf8(key).map_err(|_| ContractError::CorruptedDataFound {})
+                })
+                .collect::<Result<Vec<String>, ContractError>>()?;
+            let out = to_binary(&FrozenAccountsResponse { accounts })?;
+            Ok(out)
+        }
+        QueryMsg::BridgeAllowlist { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = calc_range_start(start_after.as_ref().map(|s| s.as_bytes()));
+            let allowlist_store = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_BRIDGE_ALLOWLIST);
+            let addresses = allowlist_store
+                .range(start.as_deref(), None, Order::Ascending)
+                .take(limit)
+                .map(|(key, _)| {
+                    String::from_utf8(key).map_err(|_| ContractError::CorruptedDataFound {})
+                })
+                .collect::<Result<Vec<String>, ContractError>>()?;
+            let out = to_binary(&BridgeAllowlistResponse {
+                enabled: read_allowlist_enabled(deps.storage),
+                addresses,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::History {
+            address,
+            start_after,
+            limit,
+        } => {
+            let address = deps.api.addr_validate(&address)?;
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            // Newest first, so the page ends right before start_after
+            let end = start_after.map(|id| id.to_be_bytes());
+            let history_store = ReadonlyPrefixedStorage::multilevel(
+                deps.storage,
+                &[PREFIX_HISTORY, address.as_str().as_bytes()],
+            );
+            let entries = history_store
+                .range(None, end.as_ref().map(|id| &id[..]), Order::Descending)
+                .take(limit)
+                .map(|(_, data)| Ok(from_slice(&data)?))
+                .collect::<Result<Vec<HistoryEntry>, ContractError>>()?;
+            let out = to_binary(&HistoryResponse { entries })?;
+            Ok(out)
+        }
+        QueryMsg::BridgeTransfer { id } => {
+            let out = to_binary(&read_bridge_transfer(deps.storage, id)?)?;
+            Ok(out)
+        }
+        QueryMsg::BridgeTransfersByAccount {
+            address,
+            direction,
+            start_after,
+            limit,
+        } => {
+            let address = deps.api.addr_validate(&address)?;
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let index_store = ReadonlyPrefixedStorage::multilevel(
+                deps.storage,
+                &[PREFIX_BRIDGE_TRANSFERS_BY_ACCOUNT, address.as_str().as_bytes()],
+            );
+            // The index value holds the direction, so records are only loaded for the page
+            let transfers = range_after_id(&index_store, start_after)
+                .filter(|(_, value)| match direction {
+                    Some(direction) => value == direction.key(),
+                    None => true,
+                })
+                .take(limit)
+                .map(|(key, _)| read_bridge_transfer(deps.storage, bytes_to_u64(&key)?))
+                .collect::<Result<Vec<BridgeTransfer>, ContractError>>()?;
+            let out = to_binary(&BridgeTransfersResponse { transfers })?;
+            Ok(out)
+        }
+        QueryMsg::BridgeStats { from_day, to_day } => {
+            let totals = read_bridge_volume(deps.storage, None)?;
+            let start = from_day.map(|day| day.to_be_bytes());
+            // The end bound is exclusive, the last possible day leaves it open
+            let end = to_day.and_then(|day| day.checked_add(1)).map(|day| day.to_be_bytes());
+            let daily_store = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_DAILY_STATS);
+            let days = daily_store
+                .range(
+                    start.as_ref().map(|day| &day[..]),
+                    end.as_ref().map(|day| &day[..]),
+                    Order::Ascending,
+                )
+                .take(MAX_STATS_DAYS)
+                .map(|(key, value)| {
+                    Ok(DailyBridgeVolume {
+                        day: bytes_to_u64(&key)?,
+                        volume: from_slice(&value)?,
+                    })
+                })
+                .collect::<Result<Vec<DailyBridgeVolume>, ContractError>>()?;
+            let out = to_binary(&BridgeStatsResponse { totals, days })?;
+            Ok(out)
+        }
+        QueryMsg::ReserveStatus {} => {
+            let config_store = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+            let last_report = match config_store.get(KEY_LAST_RESERVE_REPORT) {
+                Some(data) => Some(from_slice(&data)?),
+                None => None,
+            };
+            let out = to_binary(&ReserveStatusResponse {
+                total_supply: Uint128::from(read_total_supply(deps.storage)?),
+                outbound_in_flight: Uint128::from(read_outbound_in_flight(deps.storage)?),
+                pending_inbound: Uint128::from(read_pending_inbound(deps.storage)?),
+                last_report,
+                auto_pause: read_reserve_auto_pause(deps.storage),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::ReserveDiscrepancies { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let discrepancies_store =
+                ReadonlyPrefixedStorage::new(deps.storage, PREFIX_RESERVE_DISCREPANCIES);
+            let reports = range_after_id(&discrepancies_store, start_after)
+                .take(limit)
+                .map(|(_, value)| Ok(from_slice(&value)?))
+                .collect::<Result<Vec<ReserveReport>, ContractError>>()?;
+            let out = to_binary(&ReserveDiscrepanciesResponse { reports })?;
+            Ok(out)
+        }
+        QueryMsg::PendingOutbound { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let pending_store = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_PENDING_OUTBOUND);
+            let transfers = range_after_id(&pending_store, start_after)
+                .take(limit)
+                .map(|(key, _)| read_bridge_transfer(deps.storage, bytes_to_u64(&key)?))
+                .collect::<Result<Vec<BridgeTransfer>, ContractError>>()?;
+            let out = to_binary(&BridgeTransfersResponse { transfers })?;
+            Ok(out)
+        }
+        QueryMsg::PendingMints { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let pending_store = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_PENDING_MINTS);
+            let mints = range_after_id(&pending_store, start_after)
+                .take(limit)
+                .map(|(_, value)| Ok(from_slice(&value)?))
+                .collect::<Result<Vec<PendingMint>, ContractError>>()?;
+            let out = to_binary(&PendingMintsResponse { mints })?;
+            Ok(out)
+        }
+        QueryMsg::QueuedOutbound { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let queued_store = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_QUEUED_OUTBOUND);
+            let transfers = range_after_id(&queued_store, start_after)
+                .take(limit)
+                .map(|(key, _)| read_bridge_transfer(deps.storage, bytes_to_u64(&key)?))
+                .collect::<Result<Vec<BridgeTransfer>, ContractError>>()?;
+            let out = to_binary(&BridgeTransfersResponse { transfers })?;
+            Ok(out)
+        }
+        QueryMsg::Nonce { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let out = to_binary(&NonceResponse {
+                nonce: read_nonce(deps.storage, address.as_str())?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Escrowed { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = calc_range_start(start_after.as_ref().map(|s| s.as_bytes()));
+            let escrow_store = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_ESCROWS);
+            let escrows = escrow_store
+                .range(start.as_deref(), None, Order::Ascending)
+                .take(limit)
+                .map(|(_, value)| Ok(from_slice(&value)?))
+                .collect::<Result<Vec<Escrow>, ContractError>>()?;
+            let out = to_binary(&EscrowedResponse { escrows })?;
+            Ok(out)
+        }
+        QueryMsg::FeeSchedule {} => {
+            let out = to_binary(&read_fee_schedule(deps.storage)?)?;
+            Ok(out)
+        }
+        QueryMsg::FeeExemptions { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = calc_range_start(start_after.as_ref().map(|s| s.as_bytes()));
+            let exempt_store = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_FEE_EXEMPT);
+            let accounts = exempt_store
+                .range(start.as_deref(), None, Order::Ascending)
+                .take(limit)
+                .map(|(key, _)| {
+                    String::from_utf8(key).map_err(|_| ContractError::CorruptedDataFound {})
+                })
+                .collect::<Result<Vec<String>, ContractError>>()?;
+            let out = to_binary(&FeeExemptionsResponse { accounts })?;
+            Ok(out)
+        }
+        QueryMsg::FeeTier { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let schedule = read_fee_schedule(deps.storage)?;
+            let out = to_binary(&fee_tier(deps.storage, &env.block, &schedule, address.as_str())?)?;
+            Ok(out)
+        }
+        QueryMsg::Guardians {} => {
+            let out = to_binary(&read_guardian_set(deps.storage)?)?;
+            Ok(out)
+        }
+        QueryMsg::MintAttestation { event_id } => {
+            let attestation = read_mint_attestation(deps.storage, &event_id)?
+                .ok_or_else(|| StdError::not_found("mint attestation"))?;
+            let out = to_binary(&attestation)?;
+            Ok(out)
+        }
+        QueryMsg::Refunds { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let refund_store = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_REFUNDS);
+            let refunds = range_after_id(&refund_store, start_after)
+                .take(limit)
+                .map(|(_, value)| Ok(from_slice(&value)?))
+                .collect::<Result<Vec<Refund>, ContractError>>()?;
+            let out = to_binary(&RefundsResponse { refunds })?;
+            Ok(out)
+        }
+        QueryMsg::Claimable { evm_address } => {
+            evm_address_bytes(&evm_address)?;
+            let evm_address = evm_address.to_lowercase();
+            let out = to_binary(&ClaimableResponse {
+                amount: Uint128::from(read_claimable(deps.storage, &evm_address)?),
+                nonce: read_nonce(deps.storage, &evm_address)?,
+                evm_address,
+            })?;
+            Ok(out)
+        }
     }
 }
 
@@ -105,1493 +745,8993 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
  * 
  * @ recipient must be "ex" address,check by blockchain
  */
+#[allow(clippy::too_many_arguments)]
 fn try_mint_cw20(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     recipient: String,
     amount: Uint128,
+    event_id: Option<String>,
+    evm_sender: Option<String>,
 ) -> Result<Response<SendToEvmMsg>, ContractError> {
-
-    //read evm contract with [u8]
-    let config_storage = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_CONFIG);
-    let data = config_storage.get(KEY_CONSTANTS);
-
-    let const_data: Constants = from_slice(&data.unwrap()).unwrap();
-    let evm_contract_address = hex::decode(&const_data.contract[2..]).unwrap();
-
-    //read sender address with [u8]
-    let (_,data,_) = bech32::decode(&info.sender.as_str()).unwrap();
-    let sender = Vec::<u8>::from_base32(&data).unwrap();
-
-    //check tx sender is specified address
-    if sender != evm_contract_address {
-        return Err(ContractError::InvalidSender {
-           address:info.sender.to_string()
-        });
+    // Refunds emit outbound messages, so only the bridge caller gets that far
+    assert_bridge_caller(deps.storage, &info.sender)?;
+    assert_no_guardians(deps.storage)?;
+    if let Some(event_id) = &event_id {
+        assert_mint_not_processed(deps.storage, event_id)?;
     }
+    let request = MintRequest {
+        recipient,
+        amount,
+        event_id,
+        evm_sender: normalize_evm_sender(evm_sender)?,
+    };
+    let (response, outcome) = dispatch_mint(deps, env, info, request, true)?;
+    Ok(response.set_data(to_binary(&outcome)?))
+}
 
-    let amount_raw = amount.u128();
-    //check recipient is validate
-    let recipient_address = deps.api.addr_validate(recipient.as_str())?;
-    let mut account_balance = read_balance(deps.storage, &recipient_address)?;
-
-    account_balance += amount_raw;
-
-    let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
-    balances_store.set(
-        &recipient_address.as_str().as_bytes(),
-        &account_balance.to_be_bytes(),
-    );
-
-    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
-    let data = config_store
-        .get(KEY_TOTAL_SUPPLY)
-        .expect("no total supply data stored");
-    let mut total_supply = bytes_to_u128(&data).unwrap();
-
-    total_supply += amount_raw;
-
-    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+fn normalize_evm_sender(evm_sender: Option<String>) -> Result<Option<String>, ContractError> {
+    match evm_sender {
+        Some(evm_sender) => {
+            evm_address_bytes(&evm_sender)?;
+            Ok(Some(evm_sender.to_lowercase()))
+        }
+        None => Ok(None),
+    }
+}
 
-    Ok(Response::new()
-        .add_attribute("action", "MINT")
-        .add_attribute("account", recipient_address)
-        .add_attribute("sender", info.sender.to_string())
-        .add_attribute("amount", amount.to_string()))
+// A mint whose caller was already authorized, however it reached the contract
+struct MintRequest {
+    recipient: String,
+    amount: Uint128,
+    event_id: Option<String>,
+    evm_sender: Option<String>,
 }
 
-fn try_send_to_erc20(
+// Credits, holds, escrows or refunds the mint. `hold` is false for mints that
+// already went through a challenge period
+fn dispatch_mint(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    recipient: String,
-    amount: Uint128,
-) -> Result<Response<SendToEvmMsg>, ContractError> {
+    request: MintRequest,
+    hold: bool,
+) -> Result<(Response<SendToEvmMsg>, MintOutcome), ContractError> {
+    let total_supply = supply_after_mint(deps.storage, request.amount)?;
+    let route = route_mint(deps.as_ref(), &env, request, hold, total_supply)?;
+    apply_mint(deps, env, info, route, total_supply)
+}
 
+// Where a mint goes, decided before anything is written
+enum MintRoute {
+    Hold(PendingMint),
+    Mint(MintTarget, MintRequest),
+    Escrow(Escrow),
+    Refund(Refund),
+}
 
-    //check recipient address should a ETH address
-    if !is_valid_eth_address(&recipient) {
-        return Err(ContractError::InvalidRecipient {address: recipient});
+// Runs every check of dispatch_mint without writing anything. An error leaves
+// the mint where it was, so it can be retried
+fn route_mint(
+    deps: Deps,
+    env: &Env,
+    request: MintRequest,
+    hold: bool,
+    total_supply: u128,
+) -> Result<MintRoute, ContractError> {
+    let inbound = read_directions(deps.storage)?.inbound;
+    let cap = read_cap(deps.storage)?;
+    let threshold = read_optimistic_threshold(deps.storage)?;
+    let optimistic = hold && threshold != 0 && request.amount.u128() > threshold;
+    let MintRequest {
+        recipient,
+        amount,
+        event_id,
+        evm_sender,
+    } = request;
+    // The EVM sender can then claim it to whichever account it chooses, a
+    // wrong but valid recipient cannot take it
+    let credited_to = match &evm_sender {
+        Some(evm_sender) if read_claim_by_sender(deps.storage) => evm_sender,
+        _ => &recipient,
+    };
+    match check_mint(deps, credited_to, inbound, cap, total_supply) {
+        // Checked again when finalized, the checks above only decide between holding and refunding
+        Ok(_) if optimistic => {
+            let finalize_height = Uint64::from(env.block.height)
+                .checked_add(Uint64::from(read_challenge_period(deps.storage)?))
+                .map_err(StdError::from)?;
+            Ok(MintRoute::Hold(PendingMint {
+                id: 0,
+                recipient,
+                amount,
+                event_id,
+                evm_sender,
+                height: env.block.height,
+                time: env.block.time,
+                finalize_height: finalize_height.u64(),
+                status: PendingMintStatus::Pending,
+                challenger: None,
+            }))
+        }
+        Ok(target) => Ok(MintRoute::Mint(
+            target,
+            MintRequest {
+                recipient,
+                amount,
+                event_id,
+                evm_sender,
+            },
+        )),
+        Err((RefundReason::InvalidRecipient, _)) if event_id.is_some() && evm_sender.is_some() => {
+            let event_id = event_id.unwrap();
+            if read_escrow(deps.storage, &event_id)?.is_some() {
+                return Err(ContractError::EscrowExists { event_id });
+            }
+            Ok(MintRoute::Escrow(Escrow {
+                event_id,
+                evm_sender: evm_sender.unwrap(),
+                recipient,
+                amount,
+                height: env.block.height,
+                time: env.block.time,
+            }))
+        }
+        Err((reason, err)) => match evm_sender {
+            Some(evm_sender) => Ok(MintRoute::Refund(Refund {
+                transfer_id: 0,
+                event_id,
+                evm_sender,
+                recipient,
+                amount,
+                reason,
+                height: env.block.height,
+                time: env.block.time,
+            })),
+            None => Err(err),
+        },
     }
+}
 
-    let from = info.sender;
-    let amount_raw = amount.u128();
-    let mut account_balance = read_balance(deps.storage, &from)?;
-
-    if account_balance < amount_raw {
-        return Err(ContractError::InsufficientFunds {
-            balance: account_balance,
-            required: amount_raw,
-        });
+// Applies a routed mint and writes the total supply, which already includes
+// the amount when the mint is credited or escrowed
+fn apply_mint(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    route: MintRoute,
+    total_supply: u128,
+) -> Result<(Response<SendToEvmMsg>, MintOutcome), ContractError> {
+    let (response, outcome) = apply_route(deps.branch(), env, info, route, total_supply)?;
+    if let MintOutcome::Minted(_) | MintOutcome::Escrowed(_) = outcome {
+        let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+        config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
     }
-    account_balance -= amount_raw;
+    Ok((response, outcome))
+}
 
-    let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
-    balances_store.set(
-        from.as_str().as_bytes(),
-        &account_balance.to_be_bytes(),
-    );
+// Like apply_mint, leaving the total supply to the caller
+fn apply_route(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    route: MintRoute,
+    total_supply: u128,
+) -> Result<(Response<SendToEvmMsg>, MintOutcome), ContractError> {
+    match route {
+        MintRoute::Hold(pending) => hold_mint(deps, info, pending),
+        MintRoute::Mint(target, request) => {
+            let minted = mint_to_target(
+                deps.storage,
+                &env,
+                target,
+                request.amount,
+                total_supply,
+                request.event_id.as_deref(),
+            )?;
+            let event = events::bridge_in(
+                info.sender.as_str(),
+                &minted.recipient,
+                minted.amount - minted.fee,
+                minted.fee,
+                minted.transfer_id,
+            );
+            Ok((Response::new().add_event(event), MintOutcome::Minted(minted)))
+        }
+        MintRoute::Escrow(escrow) => escrow_mint(deps, env, info, escrow),
+        MintRoute::Refund(refund) => refund_mint(deps, env, info, refund),
+    }
+}
 
+// Stores an optimistic mint until it is finalized or resolved
+fn hold_mint(
+    deps: DepsMut,
+    info: MessageInfo,
+    mut pending: PendingMint,
+) -> Result<(Response<SendToEvmMsg>, MintOutcome), ContractError> {
+    let pending_inbound = Uint128::from(read_pending_inbound(deps.storage)?)
+        .checked_add(pending.amount)
+        .map_err(StdError::from)?;
     let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    pending.id = match config_store.get(KEY_PENDING_MINT_SEQ) {
+        Some(data) => bytes_to_u64(&data)?,
+        None => 0,
+    };
+    config_store.set(KEY_PENDING_MINT_SEQ, &(pending.id + 1).to_be_bytes());
+    write_pending_inbound(deps.storage, pending_inbound.u128());
+    write_pending_mint(deps.storage, &pending)?;
+    if let Some(event_id) = &pending.event_id {
+        let mut events_store = PrefixedStorage::new(deps.storage, PREFIX_PENDING_MINT_EVENTS);
+        events_store.set(event_id.as_bytes(), &pending.id.to_be_bytes());
+    }
 
-    //read evm contract address
-    let constants_data = config_store.get(KEY_CONSTANTS);
-    let const_data: Constants = from_slice(&constants_data.unwrap()).unwrap();
-
-    //read total supply
-    let total_supply_data = config_store.get(KEY_TOTAL_SUPPLY).expect("no total supply data stored");
-    let mut total_supply = bytes_to_u128(&total_supply_data).unwrap();
-
-    total_supply -= amount_raw;
-
-    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
-
-    //make MSG
-    let message = CosmosMsg::Custom(SendToEvmMsg {
-        sender: _env.contract.address.to_string(),
-        contract: const_data.contract.to_string(),
-        recipient,
-        amount,
-    });
-
-    Ok(Response::new()
-           .add_message(message)
-           .add_attribute("action", "call evm")
-           .add_attribute("amount", amount.to_string())
-           .set_data(b"the result data"))
+    let event = events::mint_pending(
+        info.sender.as_str(),
+        &pending.recipient,
+        pending.amount,
+        pending.id,
+        pending.finalize_height,
+    );
+    Ok((Response::new().add_event(event), MintOutcome::Pending(pending)))
 }
 
-fn try_transfer(
+fn try_finalize_mint(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    recipient: String,
-    amount: &Uint128,
+    id: u64,
 ) -> Result<Response<SendToEvmMsg>, ContractError> {
-    perform_transfer(
-        deps.storage,
-        &info.sender,
-        &deps.api.addr_validate(recipient.as_str())?,
-        amount.u128(),
-    )?;
-    Ok(Response::new()
-        .add_attribute("action", "transfer")
-        .add_attribute("sender", info.sender)
-        .add_attribute("recipient", recipient))
+    let pending = read_pending_mint(deps.storage, id)?;
+    if pending.status == PendingMintStatus::Challenged {
+        return Err(ContractError::MintChallenged { id });
+    }
+    if env.block.height < pending.finalize_height {
+        return Err(ContractError::ChallengePeriodActive {
+            id,
+            finalize_height: pending.finalize_height,
+        });
+    }
+    let (response, outcome) = complete_pending_mint(deps, env, info, pending)?;
+    Ok(response.set_data(to_binary(&outcome)?))
 }
 
-fn try_transfer_from(
+fn try_challenge(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    owner: String,
-    recipient: String,
-    amount: &Uint128,
+    id: u64,
 ) -> Result<Response<SendToEvmMsg>, ContractError> {
-    let owner_address = deps.api.addr_validate(owner.as_str())?;
-    let recipient_address = deps.api.addr_validate(recipient.as_str())?;
-    let amount_raw = amount.u128();
-
-    let mut allowance = read_allowance(deps.storage, &owner_address, &info.sender)?;
-    if allowance < amount_raw {
-        return Err(ContractError::InsufficientAllowance {
-            allowance,
-            required: amount_raw,
+    assert_owner_or_role(deps.storage, &info.sender, Role::Watcher)?;
+    let mut pending = read_pending_mint(deps.storage, id)?;
+    if pending.status == PendingMintStatus::Challenged {
+        return Err(ContractError::MintChallenged { id });
+    }
+    if env.block.height >= pending.finalize_height {
+        return Err(ContractError::ChallengePeriodEnded {
+            id,
+            finalize_height: pending.finalize_height,
         });
     }
-    allowance -= amount_raw;
-    write_allowance(deps.storage, &owner_address, &info.sender, allowance)?;
-    perform_transfer(deps.storage, &owner_address, &recipient_address, amount_raw)?;
+    pending.status = PendingMintStatus::Challenged;
+    pending.challenger = Some(info.sender.clone());
+    write_pending_mint(deps.storage, &pending)?;
 
+    let data = to_binary(&pending)?;
     Ok(Response::new()
-        .add_attribute("action", "transfer_from")
-        .add_attribute("spender", &info.sender)
-        .add_attribute("sender", owner)
-        .add_attribute("recipient", recipient))
+        .add_event(events::mint_challenged(info.sender.as_str(), id))
+        .set_data(data))
 }
 
-fn try_approve(
+// A rejected mint is returned to its EVM sender when known, dropped otherwise
+fn try_resolve_mint(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    spender: String,
-    amount: &Uint128,
+    id: u64,
+    approve: bool,
 ) -> Result<Response<SendToEvmMsg>, ContractError> {
-    let spender_address = deps.api.addr_validate(spender.as_str())?;
-    write_allowance(deps.storage, &info.sender, &spender_address, amount.u128())?;
-    Ok(Response::new()
-        .add_attribute("action", "approve")
-        .add_attribute("owner", info.sender)
-        .add_attribute("spender", spender))
+    assert_owner(deps.storage, &info.sender)?;
+    let pending = read_pending_mint(deps.storage, id)?;
+    if pending.status != PendingMintStatus::Challenged {
+        return Err(ContractError::MintNotChallenged { id });
+    }
+    if approve {
+        let (response, outcome) = complete_pending_mint(deps, env, info, pending)?;
+        return Ok(response.set_data(to_binary(&outcome)?));
+    }
+
+    remove_pending_mint(deps.storage, &pending)?;
+    let response = Response::new().add_event(events::mint_rejected(info.sender.as_str(), id, pending.amount));
+    let evm_sender = match pending.evm_sender.clone() {
+        Some(evm_sender) => evm_sender,
+        None => return Ok(response.set_data(to_binary(&MintOutcome::Rejected(pending))?)),
+    };
+    let refund = Refund {
+        transfer_id: 0,
+        event_id: pending.event_id,
+        evm_sender,
+        recipient: pending.recipient,
+        amount: pending.amount,
+        reason: RefundReason::Rejected,
+        height: env.block.height,
+        time: env.block.time,
+    };
+    let (refunded, outcome) = refund_mint(deps, env, info, refund)?;
+    let response = response
+        .add_submessages(refunded.messages)
+        .add_events(refunded.events);
+    Ok(response.set_data(to_binary(&outcome)?))
 }
 
-fn try_burn(
+// Runs the MintCW20 checks again, they may have changed during the challenge
+// period. A mint that fails them now is escrowed or refunded like a MintCW20.
+// Without an EVM sender a failed check has nowhere to go, the mint stays pending
+fn complete_pending_mint(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    amount: &Uint128,
-) -> Result<Response<SendToEvmMsg>, ContractError> {
-    let amount_raw = amount.u128();
+    pending: PendingMint,
+) -> Result<(Response<SendToEvmMsg>, MintOutcome), ContractError> {
+    let request = MintRequest {
+        recipient: pending.recipient.clone(),
+        amount: pending.amount,
+        event_id: pending.event_id.clone(),
+        evm_sender: pending.evm_sender.clone(),
+    };
+    let total_supply = supply_after_mint(deps.storage, pending.amount)?;
+    let route = route_mint(deps.as_ref(), &env, request, false, total_supply)?;
+    // The event index goes first, mint_to_target would see it as still pending
+    remove_pending_mint(deps.storage, &pending)?;
+    apply_mint(deps, env, info, route, total_supply)
+}
 
-    let mut account_balance = read_balance(deps.storage, &info.sender)?;
+fn read_pending_mint(store: &dyn Storage, id: u64) -> Result<PendingMint, ContractError> {
+    let pending_store = ReadonlyPrefixedStorage::new(store, PREFIX_PENDING_MINTS);
+    let data = pending_store
+        .get(&id.to_be_bytes())
+        .ok_or_else(|| StdError::not_found("PendingMint"))?;
+    Ok(from_slice(&data)?)
+}
 
-    if account_balance < amount_raw {
-        return Err(ContractError::InsufficientFunds {
-            balance: account_balance,
-            required: amount_raw,
-        });
+fn write_pending_mint(store: &mut dyn Storage, pending: &PendingMint) -> Result<(), ContractError> {
+    let mut pending_store = PrefixedStorage::new(store, PREFIX_PENDING_MINTS);
+    pending_store.set(&pending.id.to_be_bytes(), &to_vec(pending)?);
+    Ok(())
+}
+
+fn remove_pending_mint(store: &mut dyn Storage, pending: &PendingMint) -> Result<(), ContractError> {
+    let pending_inbound = read_pending_inbound(store)?.saturating_sub(pending.amount.u128());
+    let mut pending_store = PrefixedStorage::new(store, PREFIX_PENDING_MINTS);
+    pending_store.remove(&pending.id.to_be_bytes());
+    if let Some(event_id) = &pending.event_id {
+        let mut events_store = PrefixedStorage::new(store, PREFIX_PENDING_MINT_EVENTS);
+        events_store.remove(event_id.as_bytes());
     }
-    account_balance -= amount_raw;
+    write_pending_inbound(store, pending_inbound);
+    Ok(())
+}
 
-    let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
-    balances_store.set(
-        info.sender.as_str().as_bytes(),
-        &account_balance.to_be_bytes(),
-    );
+#[allow(clippy::too_many_arguments)]
+fn try_update_fee_schedule(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collector: String,
+    base_fee_bps: u16,
+    tiers: Vec<FeeTier>,
+    window_days: u32,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let collector = deps.api.addr_validate(&collector)?;
+    assert_not_frozen(deps.storage, &collector)?;
+    // A higher tier never costs more than the one below it
+    let rates_valid = base_fee_bps <= MAX_FEE_BPS
+        && tiers.iter().all(|tier| tier.fee_bps <= base_fee_bps);
+    let tiers_ordered = tiers
+        .windows(2)
+        .all(|pair| pair[0].min_volume < pair[1].min_volume && pair[1].fee_bps <= pair[0].fee_bps);
+    if !rates_valid || !tiers_ordered || (!tiers.is_empty() && window_days == 0) {
+        return Err(ContractError::InvalidFeeSchedule {});
+    }
 
+    let schedule = FeeSchedule {
+        collector: Some(collector),
+        base_fee_bps,
+        tiers,
+        window_days,
+    };
     let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
-    let data = config_store
-        .get(KEY_TOTAL_SUPPLY)
-        .expect("no total supply data stored");
-    let mut total_supply = bytes_to_u128(&data).unwrap();
-
-    total_supply -= amount_raw;
-
-    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+    config_store.set(KEY_FEE_SCHEDULE, &to_vec(&schedule)?);
 
-    Ok(Response::new()
-        .add_attribute("action", "burn")
-        .add_attribute("account", info.sender)
-        .add_attribute("amount", amount.to_string()))
+    let event = events::update_fee_schedule(info.sender.as_str(), base_fee_bps, schedule.tiers.len());
+    let data = to_binary(&schedule)?;
+    Ok(Response::new().add_event(event).set_data(data))
 }
 
-fn perform_transfer(
-    store: &mut dyn Storage,
-    from: &Addr,
-    to: &Addr,
-    amount: u128,
-) -> Result<(), ContractError> {
-    let mut balances_store = PrefixedStorage::new(store, PREFIX_BALANCES);
-
-    let mut from_balance = match balances_store.get(from.as_str().as_bytes()) {
-        Some(data) => bytes_to_u128(&data),
-        None => Ok(0u128),
-    }?;
+fn try_update_fee_exemptions(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let add = add
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<StdResult<Vec<Addr>>>()?;
+    let remove = remove
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<StdResult<Vec<Addr>>>()?;
+
+    let mut exempt_store = PrefixedStorage::new(deps.storage, PREFIX_FEE_EXEMPT);
+    for address in add.iter() {
+        exempt_store.set(address.as_str().as_bytes(), &[1]);
+    }
+    for address in remove.iter() {
+        exempt_store.remove(address.as_str().as_bytes());
+    }
+    let event = events::fee_exemption_update(info.sender.as_str(), add.len(), remove.len());
+    let data = to_binary(&UpdateFeeExemptionsResponse {
+        added: add.into_iter().map(String::from).collect(),
+        removed: remove.into_iter().map(String::from).collect(),
+    })?;
+    Ok(Response::new().add_event(event).set_data(data))
+}
 
-    if from_balance < amount {
-        return Err(ContractError::InsufficientFunds {
-            balance: from_balance,
-            required: amount,
-        });
+fn read_fee_schedule(store: &dyn Storage) -> Result<FeeSchedule, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_FEE_SCHEDULE) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Ok(FeeSchedule::default()),
     }
-    from_balance -= amount;
-    balances_store.set(from.as_str().as_bytes(), &from_balance.to_be_bytes());
+}
 
-    let mut to_balance = match balances_store.get(to.as_str().as_bytes()) {
-        Some(data) => bytes_to_u128(&data),
-        None => Ok(0u128),
-    }?;
-    to_balance += amount;
-    balances_store.set(to.as_str().as_bytes(), &to_balance.to_be_bytes());
+// The rate an account pays for its next bridge transfer, in either direction
+fn fee_tier(
+    store: &dyn Storage,
+    block: &BlockInfo,
+    schedule: &FeeSchedule,
+    account: &str,
+) -> Result<FeeTierResponse, ContractError> {
+    let exempt = ReadonlyPrefixedStorage::new(store, PREFIX_FEE_EXEMPT)
+        .get(account.as_bytes())
+        .is_some();
+    let volume = read_account_volume(store, block, account, schedule.window_days)?;
+    let tier = schedule
+        .tiers
+        .iter()
+        .rposition(|tier| volume >= tier.min_volume.u128());
+    let fee_bps = match tier {
+        _ if exempt => 0,
+        Some(index) => schedule.tiers[index].fee_bps,
+        None => schedule.base_fee_bps,
+    };
+    Ok(FeeTierResponse {
+        address: account.to_string(),
+        exempt,
+        volume: Uint128::from(volume),
+        tier: tier.map(|index| index as u32),
+        fee_bps,
+    })
+}
 
-    Ok(())
+// Computes the fee on a bridged amount and adds the amount to the account's
+// volume. Returns the fee and the collector it is owed to
+fn assess_bridge_fee(
+    store: &mut dyn Storage,
+    block: &BlockInfo,
+    account: &str,
+    amount: u128,
+) -> Result<(u128, Option<Addr>), ContractError> {
+    let schedule = read_fee_schedule(store)?;
+    let fee_bps = fee_tier(store, block, &schedule, account)?.fee_bps;
+    let fee = Uint128::from(amount).multiply_ratio(fee_bps, MAX_FEE_BPS).u128();
+    record_account_volume(store, block, account, amount, schedule.window_days)?;
+    // Rates are only non-zero once a schedule, and so a collector, is set
+    Ok((fee, schedule.collector.filter(|_| fee > 0)))
 }
 
-// Converts 16 bytes value into u128
-// Errors if data found that is not 16 bytes
-pub fn bytes_to_u128(data: &[u8]) -> Result<u128, ContractError> {
-    match data[0..16].try_into() {
-        Ok(bytes) => Ok(u128::from_be_bytes(bytes)),
-        Err(_) => Err(ContractError::CorruptedDataFound {}),
+// Like assess_bridge_fee, crediting the fee to the collector right away
+fn collect_bridge_fee(
+    store: &mut dyn Storage,
+    block: &BlockInfo,
+    account: &str,
+    amount: u128,
+) -> Result<u128, ContractError> {
+    let (fee, collector) = assess_bridge_fee(store, block, account, amount)?;
+    if let Some(collector) = collector {
+        pay_bridge_fee(store, block, &collector, account, fee)?;
     }
+    Ok(fee)
 }
 
-// Reads 16 byte storage value into u128
-// Returns zero if key does not exist. Errors if data found that is not 16 bytes
-pub fn read_u128(store: &ReadonlyPrefixedStorage, key: &Addr) -> Result<u128, ContractError> {
-    let result = store.get(key.as_str().as_bytes());
-    match result {
-        Some(data) => bytes_to_u128(&data),
-        None => Ok(0u128),
-    }
+fn pay_bridge_fee(
+    store: &mut dyn Storage,
+    block: &BlockInfo,
+    collector: &Addr,
+    account: &str,
+    fee: u128,
+) -> Result<(), ContractError> {
+    credit_balance(store, collector, fee)?;
+    record_history(
+        store,
+        block,
+        collector,
+        HistoryKind::BridgeFee,
+        Some(account.to_string()),
+        fee,
+    )
 }
 
-fn read_balance(store: &dyn Storage, owner: &Addr) -> Result<u128, ContractError> {
-    let balance_store = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES);
-    read_u128(&balance_store, owner)
+// An outbound fee is held by the contract until the transfer is confirmed or cancelled
+fn hold_bridge_fee(
+    store: &mut dyn Storage,
+    env: &Env,
+    account: &str,
+    amount: u128,
+) -> Result<(u128, Option<Addr>), ContractError> {
+    let (fee, collector) = assess_bridge_fee(store, &env.block, account, amount)?;
+    if collector.is_some() {
+        credit_balance(store, &env.contract.address, fee)?;
+    }
+    Ok((fee, collector))
 }
 
-fn read_allowance(
+// Bridged volume of an account over the last window_days days, today included
+fn read_account_volume(
     store: &dyn Storage,
-    owner: &Addr,
-    spender: &Addr,
+    block: &BlockInfo,
+    account: &str,
+    window_days: u32,
 ) -> Result<u128, ContractError> {
-    let owner_store =
-        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()]);
-    read_u128(&owner_store, spender)
+    let today = block.time.seconds() / SECONDS_PER_DAY;
+    let start = (today + 1).saturating_sub(window_days as u64);
+    let volume_store =
+        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_ACCOUNT_VOLUME, account.as_bytes()]);
+    let mut volume = Uint128::zero();
+    for (_, value) in volume_store.range(Some(&start.to_be_bytes()), None, Order::Ascending) {
+        volume = volume
+            .checked_add(Uint128::from(bytes_to_u128(&value)?))
+            .map_err(StdError::from)?;
+    }
+    Ok(volume.u128())
 }
 
-#[allow(clippy::unnecessary_wraps)]
-fn write_allowance(
+// Adds the amount to today's bucket and drops the buckets that left the window
+fn record_account_volume(
     store: &mut dyn Storage,
-    owner: &Addr,
-    spender: &Addr,
+    block: &BlockInfo,
+    account: &str,
     amount: u128,
-) -> StdResult<()> {
-    let mut owner_store =
-        PrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()]);
-    owner_store.set(spender.as_str().as_bytes(), &amount.to_be_bytes());
+    window_days: u32,
+) -> Result<(), ContractError> {
+    if window_days == 0 {
+        return Ok(());
+    }
+    let today = block.time.seconds() / SECONDS_PER_DAY;
+    let start = (today + 1).saturating_sub(window_days as u64);
+    let mut volume_store =
+        PrefixedStorage::multilevel(store, &[PREFIX_ACCOUNT_VOLUME, account.as_bytes()]);
+    let expired: Vec<Vec<u8>> = volume_store
+        .range(None, Some(&start.to_be_bytes()), Order::Ascending)
+        .map(|(key, _)| key)
+        .collect();
+    for key in expired {
+        volume_store.remove(&key);
+    }
+    let volume = match volume_store.get(&today.to_be_bytes()) {
+        Some(data) => bytes_to_u128(&data)?,
+        None => 0,
+    };
+    let volume = Uint128::from(volume)
+        .checked_add(Uint128::from(amount))
+        .map_err(StdError::from)?;
+    volume_store.set(&today.to_be_bytes(), &volume.u128().to_be_bytes());
     Ok(())
 }
 
-fn is_valid_name(name: &str) -> bool {
-    let bytes = name.as_bytes();
-    if bytes.len() < 3 || bytes.len() > 30 {
-        return false;
+// Takes a cancelled amount back out of the bucket of the day it was bridged,
+// nothing to do once that bucket left the window
+fn remove_account_volume(
+    store: &mut dyn Storage,
+    account: &str,
+    amount: u128,
+    time: Timestamp,
+) -> Result<(), ContractError> {
+    let day = time.seconds() / SECONDS_PER_DAY;
+    let mut volume_store =
+        PrefixedStorage::multilevel(store, &[PREFIX_ACCOUNT_VOLUME, account.as_bytes()]);
+    if let Some(data) = volume_store.get(&day.to_be_bytes()) {
+        let volume = bytes_to_u128(&data)?.saturating_sub(amount);
+        volume_store.set(&day.to_be_bytes(), &volume.to_be_bytes());
     }
-    true
+    Ok(())
 }
 
-fn is_valid_symbol(symbol: &str) -> bool {
-    let bytes = symbol.as_bytes();
-    if bytes.len() < 3 || bytes.len() > 6 {
-        return false;
-    }
-    for byte in bytes.iter() {
-        if *byte < 65 || *byte > 90 {
-            return false;
+fn try_update_guardians(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    guardians: Vec<String>,
+    threshold: u32,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let guardians = guardians
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<StdResult<Vec<Addr>>>()?;
+    for (i, guardian) in guardians.iter().enumerate() {
+        if guardians[..i].contains(guardian) {
+            return Err(ContractError::InvalidGuardianSet {});
         }
     }
-    true
+    let threshold = if guardians.is_empty() { 0 } else { threshold };
+    if !guardians.is_empty() && (threshold == 0 || threshold as usize > guardians.len()) {
+        return Err(ContractError::InvalidGuardianSet {});
+    }
+
+    let set = GuardianSet {
+        set_id: read_guardian_set(deps.storage)?.set_id + 1,
+        guardians,
+        threshold,
+    };
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_GUARDIANS, &to_vec(&set)?);
+
+    let event = events::update_guardians(info.sender.as_str(), set.guardians.len(), threshold);
+    let data = to_binary(&set)?;
+    Ok(Response::new().add_event(event).set_data(data))
 }
 
-fn is_valid_eth_address(input: &str) -> bool {
-    
-    if input.len() != 42 {
-        return false;
+// Votes are kept per guardian, so conflicting attestations never add up. The
+// vote is stored before the mint is dispatched, a mint that fails the checks
+// of route_mint keeps its votes and is retried by any guardian that voted for it
+#[allow(clippy::too_many_arguments)]
+fn try_attest_mint(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event_id: String,
+    recipient: String,
+    amount: Uint128,
+    evm_sender: Option<String>,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    let set = read_guardian_set(deps.storage)?;
+    if !set.guardians.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
     }
-    if !input.starts_with("0x") {
-        return false;
+    assert_mint_not_processed(deps.storage, &event_id)?;
+    let vote = GuardianVote {
+        guardian: info.sender.clone(),
+        recipient: recipient.clone(),
+        amount,
+        evm_sender: normalize_evm_sender(evm_sender)?,
+    };
+
+    let mut attestation = read_mint_attestation(deps.storage, &event_id)?
+        .filter(|attestation| attestation.set_id == set.set_id)
+        .unwrap_or_else(|| MintAttestation {
+            event_id: event_id.clone(),
+            set_id: set.set_id,
+            votes: vec![],
+        });
+    let count_matching = |votes: &[GuardianVote]| {
+        votes
+            .iter()
+            .filter(|other| {
+                other.recipient == vote.recipient
+                    && other.amount == vote.amount
+                    && other.evm_sender == vote.evm_sender
+            })
+            .count() as u32
+    };
+    match attestation.votes.iter().find(|other| other.guardian == info.sender) {
+        Some(previous) if previous == &vote && count_matching(&attestation.votes) >= set.threshold => {}
+        Some(_) => return Err(ContractError::AlreadyAttested { event_id }),
+        None => attestation.votes.push(vote.clone()),
     }
-    true
+    let attestations = count_matching(&attestation.votes);
+    let mut attestation_store = PrefixedStorage::new(deps.storage, PREFIX_MINT_ATTESTATIONS);
+    attestation_store.set(event_id.as_bytes(), &to_vec(&attestation)?);
+
+    let mut response = Response::new().add_event(events::attest_mint(
+        info.sender.as_str(),
+        &event_id,
+        &recipient,
+        amount,
+        attestations,
+    ));
+    let (result, error) = if attestations < set.threshold {
+        (None, None)
+    } else {
+        let request = MintRequest {
+            recipient,
+            amount,
+            event_id: Some(event_id.clone()),
+            evm_sender: vote.evm_sender,
+        };
+        // Nothing is written for a mint that fails its checks, an error once
+        // the mint is applied fails the whole attestation
+        let routed = supply_after_mint(deps.storage, amount).and_then(|total_supply| {
+            let route = route_mint(deps.as_ref(), &env, request, true, total_supply)?;
+            Ok((route, total_supply))
+        });
+        match routed {
+            Ok((route, total_supply)) => {
+                let (dispatched, outcome) = apply_mint(deps.branch(), env, info, route, total_supply)?;
+                let mut attestation_store = PrefixedStorage::new(deps.storage, PREFIX_MINT_ATTESTATIONS);
+                attestation_store.remove(event_id.as_bytes());
+                response = response
+                    .add_submessages(dispatched.messages)
+                    .add_events(dispatched.events);
+                (Some(outcome), None)
+            }
+            Err(err) => (None, Some(err.to_string())),
+        }
+    };
+
+    let data = to_binary(&AttestMintResponse {
+        event_id,
+        attestations,
+        threshold: set.threshold,
+        result,
+        error,
+    })?;
+    Ok(response.set_data(data))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{from_slice, Addr, Env, MessageInfo, Storage, Timestamp, Uint128};
-    use cosmwasm_storage::ReadonlyPrefixedStorage;
-
-    fn mock_env_height(signer: &str, height: u64, time: u64) -> (Env, MessageInfo) {
-        let mut env = mock_env();
-        let info = mock_info(signer, &[]);
-        env.block.height = height;
-        env.block.time = Timestamp::from_seconds(time);
-        (env, info)
+fn read_guardian_set(store: &dyn Storage) -> Result<GuardianSet, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_GUARDIANS) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Ok(GuardianSet::default()),
     }
+}
 
-    fn get_constants(storage: &dyn Storage) -> Constants {
-        let config_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_CONFIG);
-        let data = config_storage
-            .get(KEY_CONSTANTS)
-            .expect("no config data stored");
-        from_slice(&data).expect("invalid data")
+fn assert_no_guardians(store: &dyn Storage) -> Result<(), ContractError> {
+    if read_guardian_set(store)?.guardians.is_empty() {
+        Ok(())
+    } else {
+        Err(ContractError::GuardianAttestationRequired {})
     }
+}
 
-    fn get_total_supply(storage: &dyn Storage) -> u128 {
-        let config_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_CONFIG);
-        let data = config_storage
-            .get(KEY_TOTAL_SUPPLY)
-            .expect("no decimals data stored");
-        return bytes_to_u128(&data).unwrap();
+fn read_mint_attestation(
+    store: &dyn Storage,
+    event_id: &str,
+) -> Result<Option<MintAttestation>, ContractError> {
+    let attestation_store = ReadonlyPrefixedStorage::new(store, PREFIX_MINT_ATTESTATIONS);
+    match attestation_store.get(event_id.as_bytes()) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
     }
+}
 
-    fn get_balance(storage: &dyn Storage, address: &Addr) -> u128 {
-        let balances_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_BALANCES);
-        return read_u128(&balances_storage, address).unwrap();
+enum MintTarget {
+    Account(Addr),
+    Claimable(String),
+}
+
+// Runs every MintCW20 check that can be answered with a refund
+fn check_mint(
+    deps: Deps,
+    recipient: &str,
+    inbound: bool,
+    cap: Option<u128>,
+    total_supply: u128,
+) -> Result<MintTarget, (RefundReason, ContractError)> {
+    if !inbound {
+        return Err((
+            RefundReason::InboundPaused,
+            ContractError::BridgeDirectionDisabled {
+                direction: "inbound".to_string(),
+            },
+        ));
+    }
+    if let Some(cap) = cap {
+        if total_supply > cap {
+            return Err((
+                RefundReason::CapExceeded,
+                ContractError::CapExceeded {
+                    cap,
+                    required: total_supply,
+                },
+            ));
+        }
+    }
+    if is_valid_eth_address(recipient) {
+        evm_address_bytes(recipient).map_err(|err| (RefundReason::InvalidRecipient, err))?;
+        return Ok(MintTarget::Claimable(recipient.to_lowercase()));
     }
 
-    fn get_allowance(storage: &dyn Storage, owner: &Addr, spender: &Addr) -> u128 {
-        let owner_storage = ReadonlyPrefixedStorage::multilevel(
-            storage,
-            &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()],
-        );
-        return read_u128(&owner_storage, spender).unwrap();
+    //check recipient is validate
+    let recipient_address = deps
+        .api
+        .addr_validate(recipient)
+        .map_err(|err| (RefundReason::InvalidRecipient, err.into()))?;
+    assert_not_frozen(deps.storage, &recipient_address)
+        .map_err(|err| (RefundReason::RecipientFrozen, err))?;
+    assert_allowlisted(deps.storage, &recipient_address)
+        .map_err(|err| (RefundReason::NotAllowlisted, err))?;
+    Ok(MintTarget::Account(recipient_address))
+}
+
+// Sends the amount back to the EVM sender without minting anything
+fn refund_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut refund: Refund,
+) -> Result<(Response<SendToEvmMsg>, MintOutcome), ContractError> {
+    let constants = read_constants(deps.storage)?;
+    let (transfer_id, message) = queue_send_to_evm(
+        deps.storage,
+        &env,
+        &constants.contract,
+        &env.contract.address,
+        refund.evm_sender.clone(),
+        refund.amount,
+    )?;
+    refund.transfer_id = transfer_id;
+    let mut refund_store = PrefixedStorage::new(deps.storage, PREFIX_REFUNDS);
+    refund_store.set(&transfer_id.to_be_bytes(), &to_vec(&refund)?);
+    // A refunded event must not be minted by a later retry
+    if let Some(event_id) = &refund.event_id {
+        record_processed_mint(deps.storage, event_id, transfer_id);
     }
 
-    mod instantiate {
-        use super::*;
-        use crate::error::ContractError;
+    let event = events::bridge_refund(
+        info.sender.as_str(),
+        &refund.evm_sender,
+        refund.amount,
+        transfer_id,
+        refund.reason.code(),
+    );
+    Ok((Response::new().add_message(message).add_event(event), MintOutcome::Refunded(refund)))
+}
 
-        #[test]
-        fn works() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                evm_contract: "abc".to_string(),
-            };
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            assert_eq!(
-                get_constants(&deps.storage),
-                Constants {
-                    name: "Cash Token".to_string(),
-                    symbol: "CASH".to_string(),
-                    decimals: 9,
-                    contract: "abc".to_string(),
-                }
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                0
-            );
-            assert_eq!(get_total_supply(&deps.storage), 0);
+// Credits a checked mint, the total supply already includes the amount and is
+// written by the caller. Every mint path ends here, so an event is recorded
+// once whichever message carried it
+fn mint_to_target(
+    store: &mut dyn Storage,
+    env: &Env,
+    target: MintTarget,
+    amount: Uint128,
+    total_supply: u128,
+    event_id: Option<&str>,
+) -> Result<MintCW20Response, ContractError> {
+    if let Some(event_id) = event_id {
+        assert_mint_not_processed(store, event_id)?;
+    }
+    let (transfer_id, recipient, fee, balance) = match target {
+        MintTarget::Account(address) => {
+            let fee = collect_bridge_fee(store, &env.block, address.as_str(), amount.u128())?;
+            let credited = Uint128::from(amount.u128() - fee);
+            let (transfer_id, balance) = credit_mint(store, &env.block, &address, credited)?;
+            record_bridge_volume(store, transfer_id)?;
+            (transfer_id, address.to_string(), fee, balance)
         }
+        // Held for the EVM address until it is claimed, frozen and allow-list
+        // checks apply to the claim recipient instead
+        MintTarget::Claimable(evm_address) => {
+            let fee = collect_bridge_fee(store, &env.block, &evm_address, amount.u128())?;
+            let credited = Uint128::from(amount.u128() - fee);
+            let claimable = read_claimable(store, &evm_address)? + credited.u128();
+            let mut claimable_store = PrefixedStorage::new(store, PREFIX_CLAIMABLE);
+            claimable_store.set(evm_address.as_bytes(), &claimable.to_be_bytes());
+            let transfer_id = create_bridge_transfer(
+                store,
+                &env.block,
+                BridgeDirection::In,
+                &env.contract.address,
+                Some(evm_address.clone()),
+                credited,
+                BridgeTransferStatus::Completed,
+            )?;
+            record_bridge_volume(store, transfer_id)?;
+            (transfer_id, evm_address, fee, claimable)
+        }
+    };
+    if let Some(event_id) = event_id {
+        record_processed_mint(store, event_id, transfer_id);
+    }
 
-        #[test]
-        fn works_with_empty_balance() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                evm_contract: "abc".to_string(),
-            };
-            let (env, info) = mock_env_height("creator", 450, 550);
+    Ok(MintCW20Response {
+        transfer_id,
+        recipient,
+        amount,
+        fee: Uint128::from(fee),
+        balance: Uint128::from(balance),
+        total_supply: Uint128::from(total_supply),
+    })
+}
+
+// The tokens count towards the total supply until the escrow is released
+fn escrow_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    escrow: Escrow,
+) -> Result<(Response<SendToEvmMsg>, MintOutcome), ContractError> {
+    let mut escrow_store = PrefixedStorage::new(deps.storage, PREFIX_ESCROWS);
+    escrow_store.set(escrow.event_id.as_bytes(), &to_vec(&escrow)?);
+
+    let transfer_id = create_bridge_transfer(
+        deps.storage,
+        &env.block,
+        BridgeDirection::In,
+        &env.contract.address,
+        Some(escrow.evm_sender.clone()),
+        escrow.amount,
+        BridgeTransferStatus::Completed,
+    )?;
+    // The event stays processed after the release, so it can never be escrowed again
+    record_processed_mint(deps.storage, &escrow.event_id, transfer_id);
+
+    let event = events::escrow(
+        info.sender.as_str(),
+        &escrow.event_id,
+        &escrow.evm_sender,
+        &escrow.recipient,
+        escrow.amount,
+    );
+    Ok((Response::new().add_event(event), MintOutcome::Escrowed(escrow)))
+}
+
+fn try_release_escrow(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event_id: String,
+    recipient: Option<String>,
+    signature: Binary,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    let escrow = read_escrow(deps.storage, &event_id)?
+        .ok_or_else(|| StdError::not_found("escrow"))?;
+    let recipient_address = recipient
+        .map(|recipient| deps.api.addr_validate(&recipient))
+        .transpose()?;
+    // Shares the Claim nonce of the EVM sender, a used signature never verifies again
+    let nonce = read_nonce(deps.storage, &escrow.evm_sender)?;
+    let doc = SignDoc::ReleaseEscrow {
+        chain_id: env.block.chain_id.clone(),
+        contract: env.contract.address.to_string(),
+        event_id: event_id.clone(),
+        recipient: recipient_address.as_ref().map(|address| address.to_string()),
+        nonce,
+    };
+    if signature::recover_personal_signer(deps.api, &doc, &signature)?
+        != evm_address_bytes(&escrow.evm_sender)?
+    {
+        return Err(ContractError::InvalidSignature {});
+    }
+    // Releasing finishes the mint, so the MintCW20 checks apply to the new recipient.
+    // The escrowed amount is already part of the total supply
+    let total_supply = read_total_supply(deps.storage)?;
+    if let Some(recipient_address) = &recipient_address {
+        let inbound = read_directions(deps.storage)?.inbound;
+        let cap = read_cap(deps.storage)?;
+        check_mint(deps.as_ref(), recipient_address.as_str(), inbound, cap, total_supply)
+            .map_err(|(_, err)| err)?;
+    }
+    let returned_supply = Uint128::from(total_supply)
+        .checked_sub(escrow.amount)
+        .map_err(StdError::from)?;
+    use_nonce(deps.storage, &escrow.evm_sender, nonce)?;
+
+    let mut escrow_store = PrefixedStorage::new(deps.storage, PREFIX_ESCROWS);
+    escrow_store.remove(event_id.as_bytes());
+
+    let mut response = Response::new();
+    let (recipient, fee, transfer_id) = match recipient_address {
+        Some(recipient_address) => {
+            let fee = collect_bridge_fee(
+                deps.storage,
+                &env.block,
+                recipient_address.as_str(),
+                escrow.amount.u128(),
+            )?;
+            let credited = escrow.amount.u128() - fee;
+            credit_balance(deps.storage, &recipient_address, credited)?;
+            record_history(
+                deps.storage,
+                &env.block,
+                &recipient_address,
+                HistoryKind::BridgeIn,
+                Some(escrow.evm_sender.clone()),
+                credited,
+            )?;
+            // Only now has the escrowed mint reached an account
+            update_bridge_volume(
+                deps.storage,
+                BridgeDirection::In,
+                Uint128::from(credited),
+                escrow.time,
+                BridgeVolume::add,
+            )?;
+            (recipient_address.to_string(), fee, None)
+        }
+        // Returning the tokens reverses the mint
+        None => {
+            let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+            config_store.set(KEY_TOTAL_SUPPLY, &returned_supply.u128().to_be_bytes());
+            let constants = read_constants(deps.storage)?;
+            let (transfer_id, message) = queue_send_to_evm(
+                deps.storage,
+                &env,
+                &constants.contract,
+                &env.contract.address,
+                escrow.evm_sender.clone(),
+                escrow.amount,
+            )?;
+            response = response.add_message(message);
+            (escrow.evm_sender.clone(), 0, Some(transfer_id))
+        }
+    };
+
+    let event = events::escrow_release(
+        info.sender.as_str(),
+        &event_id,
+        &recipient,
+        escrow.amount,
+        Uint128::from(fee),
+    );
+    let data = to_binary(&ReleaseEscrowResponse {
+        event_id,
+        recipient,
+        amount: escrow.amount,
+        fee: Uint128::from(fee),
+        transfer_id,
+    })?;
+    Ok(response.add_event(event).set_data(data))
+}
+
+fn read_escrow(store: &dyn Storage, event_id: &str) -> Result<Option<Escrow>, ContractError> {
+    let escrow_store = ReadonlyPrefixedStorage::new(store, PREFIX_ESCROWS);
+    match escrow_store.get(event_id.as_bytes()) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+fn try_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    evm_address: String,
+    recipient: String,
+    signature: Binary,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    let evm_bytes = evm_address_bytes(&evm_address)?;
+    let evm_address = evm_address.to_lowercase();
+    let recipient_address = deps.api.addr_validate(&recipient)?;
+    let nonce = read_nonce(deps.storage, &evm_address)?;
+    let doc = SignDoc::Claim {
+        chain_id: env.block.chain_id.clone(),
+        contract: env.contract.address.to_string(),
+        evm_address: evm_address.clone(),
+        recipient: recipient_address.to_string(),
+        nonce,
+    };
+    if signature::recover_personal_signer(deps.api, &doc, &signature)? != evm_bytes {
+        return Err(ContractError::InvalidSignature {});
+    }
+
+    let amount = read_claimable(deps.storage, &evm_address)?;
+    if amount == 0 {
+        return Err(ContractError::NothingToClaim { evm_address });
+    }
+    assert_not_frozen(deps.storage, &recipient_address)?;
+    assert_allowlisted(deps.storage, &recipient_address)?;
+    use_nonce(deps.storage, &evm_address, nonce)?;
+
+    let mut claimable_store = PrefixedStorage::new(deps.storage, PREFIX_CLAIMABLE);
+    claimable_store.remove(evm_address.as_bytes());
+    let balance = read_balance(deps.storage, &recipient_address)? + amount;
+    let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
+    balances_store.set(recipient_address.as_str().as_bytes(), &balance.to_be_bytes());
+    record_history(
+        deps.storage,
+        &env.block,
+        &recipient_address,
+        HistoryKind::BridgeIn,
+        Some(evm_address.clone()),
+        amount,
+    )?;
+
+    let event = events::claim(
+        info.sender.as_str(),
+        &evm_address,
+        recipient_address.as_str(),
+        Uint128::from(amount),
+    );
+    let data = to_binary(&ClaimResponse {
+        evm_address,
+        recipient: recipient_address.to_string(),
+        amount: Uint128::from(amount),
+        balance: Uint128::from(balance),
+    })?;
+    Ok(Response::new().add_event(event).set_data(data))
+}
+
+fn read_claimable(store: &dyn Storage, evm_address: &str) -> Result<u128, ContractError> {
+    let claimable_store = ReadonlyPrefixedStorage::new(store, PREFIX_CLAIMABLE);
+    match claimable_store.get(evm_address.as_bytes()) {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0),
+    }
+}
+
+// Every item is routed on its own, so one bad recipient never reverts the others.
+// The total supply is written once, after the last item
+fn try_batch_mint_cw20(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mints: Vec<MintItem>,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_bridge_caller(deps.storage, &info.sender)?;
+    assert_no_guardians(deps.storage)?;
+    if mints.is_empty() {
+        return Err(ContractError::EmptyBatch {});
+    }
+
+    let mut total_supply = read_total_supply(deps.storage)?;
+    let mut total_minted = 0u128;
+    let mut results = Vec::with_capacity(mints.len());
+    let mut response = Response::new();
+
+    for item in mints {
+        let routed = match read_processed_mint(deps.storage, &item.replay_key)? {
+            Some(transfer_id) => Err(MintItemStatus::AlreadyProcessed { transfer_id }),
+            None => route_mint_item(deps.as_ref(), &env, &item, total_supply),
+        };
+        let status = match routed {
+            // Only an error that is not about the item itself reverts the batch
+            Ok((route, supply)) => {
+                let (applied, outcome) = apply_route(deps.branch(), env.clone(), info.clone(), route, supply)?;
+                response = response
+                    .add_submessages(applied.messages)
+                    .add_events(applied.events);
+                match outcome {
+                    MintOutcome::Minted(minted) => {
+                        total_supply = supply;
+                        total_minted += item.amount.u128();
+                        MintItemStatus::Minted {
+                            transfer_id: minted.transfer_id,
+                            fee: minted.fee,
+                        }
+                    }
+                    MintOutcome::Escrowed(_) => {
+                        total_supply = supply;
+                        total_minted += item.amount.u128();
+                        MintItemStatus::Escrowed {}
+                    }
+                    MintOutcome::Pending(pending) => MintItemStatus::Pending { id: pending.id },
+                    MintOutcome::Refunded(refund) => MintItemStatus::Refunded {
+                        transfer_id: refund.transfer_id,
+                    },
+                    // Only ResolveMint rejects a mint
+                    MintOutcome::Rejected(_) => return Err(ContractError::CorruptedDataFound {}),
+                }
+            }
+            Err(status) => {
+                let reason = match &status {
+                    MintItemStatus::Failed { error } => error.as_str(),
+                    _ => "already processed",
+                };
+                response = response.add_event(events::bridge_in_skipped(
+                    info.sender.as_str(),
+                    &item.replay_key,
+                    &item.recipient,
+                    item.amount,
+                    reason,
+                ));
+                status
+            }
+        };
+        results.push(MintItemResult {
+            replay_key: item.replay_key,
+            recipient: item.recipient,
+            amount: item.amount,
+            status,
+        });
+    }
+
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+
+    let data = to_binary(&BatchMintCW20Response {
+        results,
+        total_minted: Uint128::from(total_minted),
+        total_supply: Uint128::from(total_supply),
+    })?;
+    Ok(response.set_data(data))
+}
+
+// Routes an item like a MintCW20 against the running batch supply. Returns the
+// route and the supply once the item is minted, or the status of a failed item
+fn route_mint_item(
+    deps: Deps,
+    env: &Env,
+    item: &MintItem,
+    total_supply: u128,
+) -> Result<(MintRoute, u128), MintItemStatus> {
+    let routed = || -> Result<(MintRoute, u128), ContractError> {
+        assert_mint_not_processed(deps.storage, &item.replay_key)?;
+        let supply = Uint128::from(total_supply)
+            .checked_add(item.amount)
+            .map_err(StdError::from)?
+            .u128();
+        let request = MintRequest {
+            recipient: item.recipient.clone(),
+            amount: item.amount,
+            event_id: Some(item.replay_key.clone()),
+            evm_sender: normalize_evm_sender(item.evm_sender.clone())?,
+        };
+        Ok((route_mint(deps, env, request, true, supply)?, supply))
+    };
+    routed().map_err(|err| MintItemStatus::Failed {
+        error: err.to_string(),
+    })
+}
+
+// The total supply once amount is minted, an overflow is an error rather than a panic
+fn supply_after_mint(store: &dyn Storage, amount: Uint128) -> Result<u128, ContractError> {
+    let total_supply = Uint128::from(read_total_supply(store)?);
+    Ok(total_supply.checked_add(amount).map_err(StdError::from)?.u128())
+}
+
+// Returns the transfer id of an event that was already minted or refunded
+fn read_processed_mint(store: &dyn Storage, replay_key: &str) -> Result<Option<u64>, ContractError> {
+    let processed_store = ReadonlyPrefixedStorage::new(store, PREFIX_PROCESSED_MINTS);
+    match processed_store.get(replay_key.as_bytes()) {
+        Some(data) => Ok(Some(bytes_to_u64(&data)?)),
+        None => Ok(None),
+    }
+}
+
+fn record_processed_mint(store: &mut dyn Storage, replay_key: &str, transfer_id: u64) {
+    let mut processed_store = PrefixedStorage::new(store, PREFIX_PROCESSED_MINTS);
+    processed_store.set(replay_key.as_bytes(), &transfer_id.to_be_bytes());
+}
+
+// Rejects an event that was processed already or is held as a pending mint
+fn assert_mint_not_processed(store: &dyn Storage, event_id: &str) -> Result<(), ContractError> {
+    if event_id.is_empty() {
+        return Err(ContractError::EmptyReplayKey {});
+    }
+    if let Some(transfer_id) = read_processed_mint(store, event_id)? {
+        return Err(ContractError::MintAlreadyProcessed {
+            event_id: event_id.to_string(),
+            transfer_id,
+        });
+    }
+    let events_store = ReadonlyPrefixedStorage::new(store, PREFIX_PENDING_MINT_EVENTS);
+    match events_store.get(event_id.as_bytes()) {
+        Some(data) => Err(ContractError::MintPending {
+            event_id: event_id.to_string(),
+            id: bytes_to_u64(&data)?,
+        }),
+        None => Ok(()),
+    }
+}
+
+// Credits a bridged-in amount and records it, leaving the total supply to the caller
+// Returns the bridge transfer id and the new balance
+fn credit_mint(
+    store: &mut dyn Storage,
+    block: &BlockInfo,
+    recipient: &Addr,
+    amount: Uint128,
+) -> Result<(u64, u128), ContractError> {
+    let mut account_balance = read_balance(store, recipient)?;
+    account_balance += amount.u128();
+
+    let mut balances_store = PrefixedStorage::new(store, PREFIX_BALANCES);
+    balances_store.set(recipient.as_str().as_bytes(), &account_balance.to_be_bytes());
+
+    record_history(
+        store,
+        block,
+        recipient,
+        HistoryKind::BridgeIn,
+        None,
+        amount.u128(),
+    )?;
+    let transfer_id = create_bridge_transfer(
+        store,
+        block,
+        BridgeDirection::In,
+        recipient,
+        None,
+        amount,
+        BridgeTransferStatus::Completed,
+    )?;
+    Ok((transfer_id, account_balance))
+}
+
+fn try_send_to_erc20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+    deadline: Option<u64>,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+
+
+    if !read_directions(deps.storage)?.outbound {
+        return Err(ContractError::BridgeDirectionDisabled {
+            direction: "outbound".to_string(),
+        });
+    }
+
+    //check recipient address should a ETH address
+    if !is_valid_eth_address(&recipient) {
+        return Err(ContractError::InvalidRecipient {address: recipient});
+    }
+    if let Some(deadline) = deadline {
+        if deadline <= env.block.time.seconds() {
+            return Err(ContractError::InvalidDeadline { deadline });
+        }
+    }
+
+    let from = info.sender;
+    assert_not_frozen(deps.storage, &from)?;
+    assert_allowlisted(deps.storage, &from)?;
+    let amount_raw = amount.u128();
+    let mut account_balance = read_balance(deps.storage, &from)?;
+
+    if account_balance < amount_raw {
+        return Err(ContractError::InsufficientFunds {
+            balance: account_balance,
+            required: amount_raw,
+        });
+    }
+    account_balance -= amount_raw;
+    let const_data = read_constants(deps.storage)?;
+    let total_supply = read_total_supply(deps.storage)?;
+    // Large transfers wait for the release time, guardians may cancel them meanwhile
+    let release_time = match read_timelock_threshold(deps.storage)? {
+        threshold if threshold != 0 && amount_raw > threshold => {
+            let release_time = Uint64::from(env.block.time.seconds())
+                .checked_add(Uint64::from(read_timelock_delay(deps.storage)?))
+                .map_err(StdError::from)?;
+            Some(release_time.u64())
+        }
+        _ => None,
+    };
+    if let (Some(deadline), Some(release_time)) = (deadline, release_time) {
+        if deadline <= release_time {
+            return Err(ContractError::DeadlineBeforeRelease { deadline, release_time });
+        }
+    }
+
+    let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
+    balances_store.set(
+        from.as_str().as_bytes(),
+        &account_balance.to_be_bytes(),
+    );
+    // The fee stays on this side, only the rest leaves the supply
+    let (fee, fee_collector) = hold_bridge_fee(deps.storage, &env, from.as_str(), amount_raw)?;
+    let sent = Uint128::from(amount_raw - fee);
+
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_TOTAL_SUPPLY, &(total_supply - sent.u128()).to_be_bytes());
+
+    let (transfer_id, message) = match release_time {
+        Some(_) => {
+            let transfer_id = queue_timelocked_send(deps.storage, &env, &from, recipient.clone(), sent)?;
+            (transfer_id, None)
+        }
+        None => {
+            let (transfer_id, message) = queue_send_to_evm(
+                deps.storage,
+                &env,
+                &const_data.contract,
+                &from,
+                recipient.clone(),
+                sent,
+            )?;
+            record_bridge_volume(deps.storage, transfer_id)?;
+            (transfer_id, Some(message))
+        }
+    };
+    if fee > 0 || deadline.is_some() || release_time.is_some() {
+        let mut transfer = read_bridge_transfer(deps.storage, transfer_id)?;
+        transfer.deadline = deadline;
+        transfer.release_time = release_time;
+        transfer.fee = Uint128::from(fee);
+        transfer.fee_collector = fee_collector;
+        write_bridge_transfer(deps.storage, &transfer)?;
+    }
+
+    let event = match release_time {
+        Some(release_time) => {
+            events::bridge_out_queued(from.as_str(), &recipient, sent, Uint128::from(fee), transfer_id, release_time)
+        }
+        None => events::bridge_out(from.as_str(), &recipient, sent, Uint128::from(fee), transfer_id),
+    };
+    let data = to_binary(&SendToEvmResponse {
+        transfer_id,
+        recipient,
+        amount,
+        fee: Uint128::from(fee),
+        balance: Uint128::from(read_balance(deps.storage, &from)?),
+    })?;
+
+    Ok(Response::new()
+           .add_messages(message)
+           .add_event(event)
+           .set_data(data))
+}
+
+// Debits the sender once and emits one SendToEvm message per recipient, each with its own transfer id
+fn try_batch_send_to_evm(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    transfers: Vec<TransferItem>,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    if !read_directions(deps.storage)?.outbound {
+        return Err(ContractError::BridgeDirectionDisabled {
+            direction: "outbound".to_string(),
+        });
+    }
+    if transfers.is_empty() {
+        return Err(ContractError::EmptyBatch {});
+    }
+    if let Some(item) = transfers.iter().find(|item| !is_valid_eth_address(&item.recipient)) {
+        return Err(ContractError::InvalidRecipient {
+            address: item.recipient.clone(),
+        });
+    }
+    let threshold = read_timelock_threshold(deps.storage)?;
+    if let Some(item) = transfers
+        .iter()
+        .find(|item| threshold != 0 && item.amount.u128() > threshold)
+    {
+        return Err(ContractError::TimelockRequired {
+            amount: item.amount.u128(),
+            threshold,
+        });
+    }
+    let total = transfers
+        .iter()
+        .try_fold(Uint128::zero(), |total, item| total.checked_add(item.amount))
+        .map_err(StdError::from)?;
+
+    assert_not_frozen(deps.storage, &info.sender)?;
+    assert_allowlisted(deps.storage, &info.sender)?;
+    debit_balance(deps.storage, &info.sender, total.u128())?;
+
+    let evm_contract = read_constants(deps.storage)?.contract;
+    let mut transfer_ids = Vec::with_capacity(transfers.len());
+    let mut total_sent = 0u128;
+    let mut response = Response::new();
+    for item in transfers {
+        let (fee, fee_collector) = hold_bridge_fee(deps.storage, &env, info.sender.as_str(), item.amount.u128())?;
+        let sent = Uint128::from(item.amount.u128() - fee);
+        let (transfer_id, message) = queue_send_to_evm(
+            deps.storage,
+            &env,
+            &evm_contract,
+            &info.sender,
+            item.recipient.clone(),
+            sent,
+        )?;
+        record_bridge_volume(deps.storage, transfer_id)?;
+        if fee > 0 {
+            let mut transfer = read_bridge_transfer(deps.storage, transfer_id)?;
+            transfer.fee = Uint128::from(fee);
+            transfer.fee_collector = fee_collector;
+            write_bridge_transfer(deps.storage, &transfer)?;
+        }
+        total_sent += sent.u128();
+        transfer_ids.push(transfer_id);
+        response = response.add_message(message).add_event(events::bridge_out(
+            info.sender.as_str(),
+            &item.recipient,
+            sent,
+            Uint128::from(fee),
+            transfer_id,
+        ));
+    }
+
+    let total_supply = read_total_supply(deps.storage)? - total_sent;
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+
+    let data = to_binary(&BatchSendToEvmResponse {
+        transfer_ids,
+        total_amount: total,
+        balance: Uint128::from(read_balance(deps.storage, &info.sender)?),
+    })?;
+    Ok(response.set_data(data))
+}
+
+// Records an outbound transfer already debited from the sender and builds its message
+// The transfer stays pending until the bridge caller confirms delivery on the EVM side
+fn queue_send_to_evm(
+    store: &mut dyn Storage,
+    env: &Env,
+    evm_contract: &str,
+    from: &Addr,
+    recipient: String,
+    amount: Uint128,
+) -> Result<(u64, CosmosMsg<SendToEvmMsg>), ContractError> {
+    record_history(
+        store,
+        &env.block,
+        from,
+        HistoryKind::BridgeOut,
+        Some(recipient.clone()),
+        amount.u128(),
+    )?;
+    let transfer_id = create_bridge_transfer(
+        store,
+        &env.block,
+        BridgeDirection::Out,
+        from,
+        Some(recipient.clone()),
+        amount,
+        BridgeTransferStatus::Pending,
+    )?;
+    Ok((transfer_id, send_to_evm_msg(env, evm_contract, transfer_id, recipient, amount)))
+}
+
+// Like queue_send_to_evm, but the message waits for try_execute_queued
+fn queue_timelocked_send(
+    store: &mut dyn Storage,
+    env: &Env,
+    from: &Addr,
+    recipient: String,
+    amount: Uint128,
+) -> Result<u64, ContractError> {
+    record_history(
+        store,
+        &env.block,
+        from,
+        HistoryKind::BridgeOut,
+        Some(recipient.clone()),
+        amount.u128(),
+    )?;
+    create_bridge_transfer(
+        store,
+        &env.block,
+        BridgeDirection::Out,
+        from,
+        Some(recipient),
+        amount,
+        BridgeTransferStatus::Queued,
+    )
+}
+
+fn send_to_evm_msg(
+    env: &Env,
+    evm_contract: &str,
+    id: u64,
+    recipient: String,
+    amount: Uint128,
+) -> CosmosMsg<SendToEvmMsg> {
+    CosmosMsg::Custom(SendToEvmMsg {
+        sender: env.contract.address.to_string(),
+        contract: evm_contract.to_string(),
+        recipient,
+        amount,
+        id,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn try_update_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    evm_contract: Option<String>,
+    name: Option<String>,
+    symbol: Option<String>,
+    inbound_enabled: Option<bool>,
+    outbound_enabled: Option<bool>,
+    history_limit: Option<u32>,
+    reserve_auto_pause: Option<bool>,
+    timelock_threshold: Option<Uint128>,
+    timelock_delay: Option<u64>,
+    optimistic_threshold: Option<Uint128>,
+    challenge_period: Option<u64>,
+    sender_cancel_enabled: Option<bool>,
+    claim_by_sender: Option<bool>,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+
+    // Apply the same rules as instantiate
+    if let Some(name) = &name {
+        if !is_valid_name(name) {
+            return Err(ContractError::NameWrongFormat {});
+        }
+    }
+    if let Some(symbol) = &symbol {
+        if !is_valid_symbol(symbol) {
+            return Err(ContractError::TickerWrongSymbolFormat {});
+        }
+    }
+    if let Some(evm_contract) = &evm_contract {
+        evm_address_bytes(evm_contract)?;
+    }
+
+    let mut constants = read_constants(deps.storage)?;
+    let mut directions = read_directions(deps.storage)?;
+    let mut event = events::update_config(info.sender.as_str());
+
+    if let Some(evm_contract) = evm_contract {
+        event = events::update_config_change(event, "evm_contract", &constants.contract, &evm_contract);
+        constants.contract = evm_contract;
+    }
+    if let Some(name) = name {
+        event = events::update_config_change(event, "name", &constants.name, &name);
+        constants.name = name;
+    }
+    if let Some(symbol) = symbol {
+        event = events::update_config_change(event, "symbol", &constants.symbol, &symbol);
+        constants.symbol = symbol;
+    }
+    if let Some(inbound) = inbound_enabled {
+        event = events::update_config_change(
+            event,
+            "inbound_enabled",
+            &directions.inbound.to_string(),
+            &inbound.to_string(),
+        );
+        directions.inbound = inbound;
+    }
+    if let Some(outbound) = outbound_enabled {
+        event = events::update_config_change(
+            event,
+            "outbound_enabled",
+            &directions.outbound.to_string(),
+            &outbound.to_string(),
+        );
+        directions.outbound = outbound;
+    }
+
+    if let Some(history_limit) = history_limit {
+        event = events::update_config_change(
+            event,
+            "history_limit",
+            &read_history_limit(deps.storage)?.to_string(),
+            &history_limit.to_string(),
+        );
+        let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+        config_store.set(KEY_HISTORY_LIMIT, &history_limit.to_be_bytes());
+    }
+    if let Some(auto_pause) = reserve_auto_pause {
+        event = events::update_config_change(
+            event,
+            "reserve_auto_pause",
+            &read_reserve_auto_pause(deps.storage).to_string(),
+            &auto_pause.to_string(),
+        );
+        let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+        config_store.set(KEY_RESERVE_AUTO_PAUSE, &[auto_pause as u8]);
+    }
+    if let Some(threshold) = timelock_threshold {
+        event = events::update_config_change(
+            event,
+            "timelock_threshold",
+            &read_timelock_threshold(deps.storage)?.to_string(),
+            &threshold.to_string(),
+        );
+        let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+        config_store.set(KEY_TIMELOCK_THRESHOLD, &threshold.u128().to_be_bytes());
+    }
+    if let Some(delay) = timelock_delay {
+        event = events::update_config_change(
+            event,
+            "timelock_delay",
+            &read_timelock_delay(deps.storage)?.to_string(),
+            &delay.to_string(),
+        );
+        let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+        config_store.set(KEY_TIMELOCK_DELAY, &delay.to_be_bytes());
+    }
+    if let Some(threshold) = optimistic_threshold {
+        event = events::update_config_change(
+            event,
+            "optimistic_threshold",
+            &read_optimistic_threshold(deps.storage)?.to_string(),
+            &threshold.to_string(),
+        );
+        let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+        config_store.set(KEY_OPTIMISTIC_THRESHOLD, &threshold.u128().to_be_bytes());
+    }
+    if let Some(period) = challenge_period {
+        event = events::update_config_change(
+            event,
+            "challenge_period",
+            &read_challenge_period(deps.storage)?.to_string(),
+            &period.to_string(),
+        );
+        let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+        config_store.set(KEY_CHALLENGE_PERIOD, &period.to_be_bytes());
+    }
+    if let Some(enabled) = sender_cancel_enabled {
+        event = events::update_config_change(
+            event,
+            "sender_cancel_enabled",
+            &read_sender_cancel_enabled(deps.storage).to_string(),
+            &enabled.to_string(),
+        );
+        let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+        config_store.set(KEY_SENDER_CANCEL_ENABLED, &[enabled as u8]);
+    }
+    if let Some(enabled) = claim_by_sender {
+        event = events::update_config_change(
+            event,
+            "claim_by_sender",
+            &read_claim_by_sender(deps.storage).to_string(),
+            &enabled.to_string(),
+        );
+        let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+        config_store.set(KEY_CLAIM_BY_SENDER, &[enabled as u8]);
+    }
+
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_CONSTANTS, &to_vec(&constants)?);
+    config_store.set(KEY_DIRECTIONS, &to_vec(&directions)?);
+
+    let data = to_binary(&read_bridge_config(deps.storage)?)?;
+    Ok(Response::new().add_event(event).set_data(data))
+}
+
+// Compares the EVM-side reserves with the supply plus queued and unconfirmed outbound transfers
+// plus optimistic mints that are pending or challenged
+fn try_report_reserves(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    reserves: Uint128,
+    evm_block: u64,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_owner_or_role(deps.storage, &info.sender, Role::ReserveReporter)?;
+
+    let config_store = ReadonlyPrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    if let Some(data) = config_store.get(KEY_LAST_RESERVE_REPORT) {
+        let last: ReserveReport = from_slice(&data)?;
+        if evm_block <= last.evm_block {
+            return Err(ContractError::StaleReserveReport {
+                last: last.evm_block,
+                reported: evm_block,
+            });
+        }
+    }
+
+    let total_supply = read_total_supply(deps.storage)?;
+    let outbound_in_flight = read_outbound_in_flight(deps.storage)?;
+    let pending_inbound = read_pending_inbound(deps.storage)?;
+    let expected = Uint128::from(total_supply)
+        .checked_add(Uint128::from(outbound_in_flight))
+        .and_then(|sum| sum.checked_add(Uint128::from(pending_inbound)))
+        .map_err(StdError::from)?;
+    let report = ReserveReport {
+        evm_block,
+        reserves,
+        total_supply: Uint128::from(total_supply),
+        outbound_in_flight: Uint128::from(outbound_in_flight),
+        pending_inbound: Uint128::from(pending_inbound),
+        shortfall: expected.saturating_sub(reserves),
+        surplus: reserves.saturating_sub(expected),
+        reporter: info.sender.to_string(),
+        height: env.block.height,
+        time: env.block.time,
+    };
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_LAST_RESERVE_REPORT, &to_vec(&report)?);
+
+    if !report.shortfall.is_zero() || !report.surplus.is_zero() {
+        let mut discrepancies_store =
+            PrefixedStorage::new(deps.storage, PREFIX_RESERVE_DISCREPANCIES);
+        discrepancies_store.set(&evm_block.to_be_bytes(), &to_vec(&report)?);
+    }
+
+    let mut inbound_paused = false;
+    if !report.shortfall.is_zero() && read_reserve_auto_pause(deps.storage) {
+        let mut directions = read_directions(deps.storage)?;
+        if directions.inbound {
+            directions.inbound = false;
+            let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+            config_store.set(KEY_DIRECTIONS, &to_vec(&directions)?);
+            inbound_paused = true;
+        }
+    }
+
+    let event = events::reserve_report(
+        info.sender.as_str(),
+        evm_block,
+        reserves,
+        expected,
+        report.shortfall,
+        report.surplus,
+        inbound_paused,
+    );
+    let data = to_binary(&ReportReservesResponse {
+        report,
+        inbound_paused,
+    })?;
+    Ok(Response::new().add_event(event).set_data(data))
+}
+
+fn try_grant_role(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let address = deps.api.addr_validate(&address)?;
+    grant_role(deps.storage, role, &address);
+    let data = to_binary(&RoleResponse {
+        role,
+        address: address.to_string(),
+        granted: true,
+    })?;
+    Ok(Response::new()
+        .add_event(events::grant_role(
+            info.sender.as_str(),
+            &role.to_string(),
+            address.as_str(),
+        ))
+        .set_data(data))
+}
+
+fn try_revoke_role(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let address = deps.api.addr_validate(&address)?;
+    let mut role_store = PrefixedStorage::multilevel(deps.storage, &[PREFIX_ROLES, role.key()]);
+    role_store.remove(address.as_str().as_bytes());
+    let data = to_binary(&RoleResponse {
+        role,
+        address: address.to_string(),
+        granted: false,
+    })?;
+    Ok(Response::new()
+        .add_event(events::revoke_role(
+            info.sender.as_str(),
+            &role.to_string(),
+            address.as_str(),
+        ))
+        .set_data(data))
+}
+
+fn try_freeze_account(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_owner_or_role(deps.storage, &info.sender, Role::Compliance)?;
+    let address = deps.api.addr_validate(&address)?;
+    // Every fee-bearing bridge transfer credits the collector
+    if read_fee_schedule(deps.storage)?.collector.as_ref() == Some(&address) {
+        return Err(ContractError::FeeCollectorFrozen {
+            address: address.into(),
+        });
+    }
+    let mut frozen_store = PrefixedStorage::new(deps.storage, PREFIX_FROZEN);
+    frozen_store.set(address.as_str().as_bytes(), &[1]);
+    let data = to_binary(&FreezeAccountResponse {
+        address: address.to_string(),
+        frozen: true,
+    })?;
+    Ok(Response::new()
+        .add_event(events::freeze(info.sender.as_str(), address.as_str()))
+        .set_data(data))
+}
+
+fn try_unfreeze_account(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_owner_or_role(deps.storage, &info.sender, Role::Compliance)?;
+    let address = deps.api.addr_validate(&address)?;
+    let mut frozen_store = PrefixedStorage::new(deps.storage, PREFIX_FROZEN);
+    frozen_store.remove(address.as_str().as_bytes());
+    let data = to_binary(&FreezeAccountResponse {
+        address: address.to_string(),
+        frozen: false,
+    })?;
+    Ok(Response::new()
+        .add_event(events::unfreeze(info.sender.as_str(), address.as_str()))
+        .set_data(data))
+}
+
+fn try_set_bridge_allowlist_mode(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_ALLOWLIST_ENABLED, &[enabled as u8]);
+    let data = to_binary(&BridgeAllowlistModeResponse { enabled })?;
+    Ok(Response::new()
+        .add_event(events::bridge_allowlist_mode(info.sender.as_str(), enabled))
+        .set_data(data))
+}
+
+fn try_update_bridge_allowlist(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let add = add
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<StdResult<Vec<Addr>>>()?;
+    let remove = remove
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<StdResult<Vec<Addr>>>()?;
+
+    let mut allowlist_store = PrefixedStorage::new(deps.storage, PREFIX_BRIDGE_ALLOWLIST);
+    for address in add.iter() {
+        allowlist_store.set(address.as_str().as_bytes(), &[1]);
+    }
+    for address in remove.iter() {
+        allowlist_store.remove(address.as_str().as_bytes());
+    }
+    let event = events::bridge_allowlist_update(info.sender.as_str(), add.len(), remove.len());
+    let data = to_binary(&UpdateBridgeAllowlistResponse {
+        added: add.into_iter().map(String::from).collect(),
+        removed: remove.into_iter().map(String::from).collect(),
+    })?;
+    Ok(Response::new().add_event(event).set_data(data))
+}
+
+fn try_confirm_send_to_evm(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_bridge_caller(deps.storage, &info.sender)?;
+
+    let mut transfer = read_bridge_transfer(deps.storage, id)?;
+    if transfer.direction != BridgeDirection::Out
+        || transfer.status != BridgeTransferStatus::Pending
+    {
+        return Err(ContractError::BridgeTransferNotPending { id });
+    }
+    transfer.status = BridgeTransferStatus::Completed;
+    settle_outbound(deps.storage, &transfer)?;
+    if let Some(collector) = transfer.fee_collector.as_ref().filter(|_| !transfer.fee.is_zero()) {
+        debit_balance(deps.storage, &env.contract.address, transfer.fee.u128())?;
+        pay_bridge_fee(deps.storage, &env.block, collector, &transfer.account, transfer.fee.u128())?;
+    }
+
+    let data = to_binary(&transfer)?;
+    Ok(Response::new()
+        .add_event(events::bridge_out_confirmed(info.sender.as_str(), id))
+        .set_data(data))
+}
+
+// Restores the balance and total supply taken by a SendToEvm the EVM side never confirmed
+fn try_cancel_send_to_evm(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    if !read_sender_cancel_enabled(deps.storage) {
+        return Err(ContractError::SenderCancelDisabled {});
+    }
+    let mut transfer = read_bridge_transfer(deps.storage, id)?;
+    if transfer.direction != BridgeDirection::Out
+        || transfer.status != BridgeTransferStatus::Pending
+    {
+        return Err(ContractError::BridgeTransferNotPending { id });
+    }
+    if transfer.account != info.sender.as_str() {
+        return Err(ContractError::Unauthorized {});
+    }
+    match transfer.deadline {
+        Some(deadline) if env.block.time.seconds() > deadline => {}
+        _ => return Err(ContractError::DeadlineNotPassed { id }),
+    }
+    return_outbound(deps.storage, &env, &transfer)?;
+    transfer.status = BridgeTransferStatus::Cancelled;
+    settle_outbound(deps.storage, &transfer)?;
+    remove_bridge_volume(deps.storage, &transfer)?;
+
+    let data = to_binary(&transfer)?;
+    Ok(Response::new()
+        .add_event(events::bridge_out_cancelled(info.sender.as_str(), id, transfer.amount))
+        .set_data(data))
+}
+
+fn try_execute_queued(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    let mut transfer = read_bridge_transfer(deps.storage, id)?;
+    let release_time = queued_release_time(&transfer)?;
+    if env.block.time.seconds() < release_time {
+        return Err(ContractError::TimelockNotExpired { id, release_time });
+    }
+    if !read_directions(deps.storage)?.outbound {
+        return Err(ContractError::BridgeDirectionDisabled {
+            direction: "outbound".to_string(),
+        });
+    }
+    // Compliance may still stop the transfer by freezing the sender
+    assert_not_frozen(deps.storage, &Addr::unchecked(&transfer.account))?;
+    let recipient = transfer
+        .evm_address
+        .clone()
+        .ok_or(ContractError::CorruptedDataFound {})?;
+
+    transfer.status = BridgeTransferStatus::Pending;
+    write_bridge_transfer(deps.storage, &transfer)?;
+    let mut queued_store = PrefixedStorage::new(deps.storage, PREFIX_QUEUED_OUTBOUND);
+    queued_store.remove(&id.to_be_bytes());
+    mark_pending(deps.storage, id);
+    record_bridge_volume(deps.storage, id)?;
+
+    let constants = read_constants(deps.storage)?;
+    let message = send_to_evm_msg(&env, &constants.contract, id, recipient, transfer.amount);
+    let data = to_binary(&transfer)?;
+    Ok(Response::new()
+        .add_message(message)
+        .add_event(events::bridge_out_released(info.sender.as_str(), id, transfer.amount))
+        .set_data(data))
+}
+
+fn try_cancel_queued(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_owner_or_guardian(deps.storage, &info.sender)?;
+    let mut transfer = read_bridge_transfer(deps.storage, id)?;
+    let release_time = queued_release_time(&transfer)?;
+    if env.block.time.seconds() >= release_time {
+        return Err(ContractError::TimelockExpired { id, release_time });
+    }
+
+    return_outbound(deps.storage, &env, &transfer)?;
+    transfer.status = BridgeTransferStatus::Cancelled;
+    write_bridge_transfer(deps.storage, &transfer)?;
+    let mut queued_store = PrefixedStorage::new(deps.storage, PREFIX_QUEUED_OUTBOUND);
+    queued_store.remove(&id.to_be_bytes());
+    let in_flight = read_outbound_in_flight(deps.storage)?;
+    write_outbound_in_flight(deps.storage, in_flight.saturating_sub(transfer.amount.u128()));
+
+    let data = to_binary(&transfer)?;
+    Ok(Response::new()
+        .add_event(events::bridge_out_cancelled(info.sender.as_str(), id, transfer.amount))
+        .set_data(data))
+}
+
+fn queued_release_time(transfer: &BridgeTransfer) -> Result<u64, ContractError> {
+    match (transfer.status, transfer.release_time) {
+        (BridgeTransferStatus::Queued, Some(release_time)) => Ok(release_time),
+        _ => Err(ContractError::BridgeTransferNotQueued { id: transfer.id }),
+    }
+}
+
+// Credits a cancelled outbound transfer back to its sender, along with the fee
+// the contract held for it, and restores the supply
+fn return_outbound(
+    store: &mut dyn Storage,
+    env: &Env,
+    transfer: &BridgeTransfer,
+) -> Result<(), ContractError> {
+    let sender = Addr::unchecked(&transfer.account);
+    let fee = transfer.fee.u128();
+    if transfer.fee_collector.is_some() && fee > 0 {
+        debit_balance(store, &env.contract.address, fee)?;
+    }
+    let amount = transfer.amount.checked_add(transfer.fee).map_err(StdError::from)?.u128();
+    credit_balance(store, &sender, amount)?;
+    let total_supply = Uint128::from(read_total_supply(store)?)
+        .checked_add(transfer.amount)
+        .map_err(StdError::from)?
+        .u128();
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+    remove_account_volume(store, sender.as_str(), amount, transfer.time)?;
+    record_history(
+        store,
+        &env.block,
+        &sender,
+        HistoryKind::BridgeOutCancelled,
+        transfer.evm_address.clone(),
+        amount,
+    )
+}
+
+// Stores the final status of an outbound transfer and takes it out of the in-flight total
+fn settle_outbound(store: &mut dyn Storage, transfer: &BridgeTransfer) -> Result<(), ContractError> {
+    write_bridge_transfer(store, transfer)?;
+    let mut pending_store = PrefixedStorage::new(store, PREFIX_PENDING_OUTBOUND);
+    pending_store.remove(&transfer.id.to_be_bytes());
+    let in_flight = read_outbound_in_flight(store)?;
+    write_outbound_in_flight(store, in_flight.saturating_sub(transfer.amount.u128()));
+    Ok(())
+}
+
+fn try_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: &Uint128,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    let recipient_address = deps.api.addr_validate(recipient.as_str())?;
+    perform_transfer(
+        deps.storage,
+        &env.block,
+        &info.sender,
+        &recipient_address,
+        amount.u128(),
+    )?;
+    let data = to_binary(&TransferResponse {
+        from: info.sender.to_string(),
+        to: recipient_address.to_string(),
+        amount: *amount,
+        from_balance: Uint128::from(read_balance(deps.storage, &info.sender)?),
+        to_balance: Uint128::from(read_balance(deps.storage, &recipient_address)?),
+        allowance: None,
+    })?;
+    Ok(Response::new()
+        .add_event(events::transfer(
+            info.sender.as_str(),
+            info.sender.as_str(),
+            recipient_address.as_str(),
+            *amount,
+        ))
+        .set_data(data))
+}
+
+// Debits the sender once for the whole batch, then credits every recipient
+fn try_batch_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    transfers: Vec<TransferItem>,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    if transfers.is_empty() {
+        return Err(ContractError::EmptyBatch {});
+    }
+    let recipients = transfers
+        .iter()
+        .map(|item| deps.api.addr_validate(&item.recipient))
+        .collect::<StdResult<Vec<Addr>>>()?;
+    let total = transfers
+        .iter()
+        .try_fold(Uint128::zero(), |total, item| total.checked_add(item.amount))
+        .map_err(StdError::from)?;
+
+    // Everything is checked before the first item moves, so a failure leaves no partial batch
+    assert_not_frozen(deps.storage, &info.sender)?;
+    for recipient in recipients.iter() {
+        assert_not_frozen(deps.storage, recipient)?;
+    }
+    let balance = read_balance(deps.storage, &info.sender)?;
+    if balance < total.u128() {
+        return Err(ContractError::InsufficientFunds {
+            balance,
+            required: total.u128(),
+        });
+    }
+
+    let mut response = Response::new();
+    for (recipient, item) in recipients.iter().zip(transfers.iter()) {
+        perform_transfer(deps.storage, &env.block, &info.sender, recipient, item.amount.u128())?;
+        response = response.add_event(events::transfer(
+            info.sender.as_str(),
+            info.sender.as_str(),
+            recipient.as_str(),
+            item.amount,
+        ));
+    }
+
+    let data = to_binary(&BatchTransferResponse {
+        from: info.sender.to_string(),
+        total_amount: total,
+        from_balance: Uint128::from(read_balance(deps.storage, &info.sender)?),
+    })?;
+    Ok(response.set_data(data))
+}
+
+fn try_transfer_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    recipient: String,
+    amount: &Uint128,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    let owner_address = deps.api.addr_validate(owner.as_str())?;
+    let recipient_address = deps.api.addr_validate(recipient.as_str())?;
+    let amount_raw = amount.u128();
+    // A frozen spender cannot move funds on anyone's behalf
+    assert_not_frozen(deps.storage, &info.sender)?;
+
+    let mut allowance = read_allowance(deps.storage, &owner_address, &info.sender)?;
+    if allowance < amount_raw {
+        return Err(ContractError::InsufficientAllowance {
+            allowance,
+            required: amount_raw,
+        });
+    }
+    allowance -= amount_raw;
+    write_allowance(deps.storage, &owner_address, &info.sender, allowance)?;
+    perform_transfer(
+        deps.storage,
+        &env.block,
+        &owner_address,
+        &recipient_address,
+        amount_raw,
+    )?;
+
+    let data = to_binary(&TransferResponse {
+        from: owner_address.to_string(),
+        to: recipient_address.to_string(),
+        amount: *amount,
+        from_balance: Uint128::from(read_balance(deps.storage, &owner_address)?),
+        to_balance: Uint128::from(read_balance(deps.storage, &recipient_address)?),
+        allowance: Some(Uint128::from(allowance)),
+    })?;
+    Ok(Response::new()
+        .add_event(events::transfer(
+            info.sender.as_str(),
+            owner_address.as_str(),
+            recipient_address.as_str(),
+            *amount,
+        ))
+        .set_data(data))
+}
+
+fn try_approve(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount: &Uint128,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    let spender_address = deps.api.addr_validate(spender.as_str())?;
+    assert_not_frozen(deps.storage, &info.sender)?;
+    write_allowance(deps.storage, &info.sender, &spender_address, amount.u128())?;
+    let data = to_binary(&ApproveResponse {
+        owner: info.sender.to_string(),
+        spender: spender_address.to_string(),
+        allowance: *amount,
+    })?;
+    Ok(Response::new()
+        .add_event(events::approve(
+            info.sender.as_str(),
+            spender_address.as_str(),
+            *amount,
+        ))
+        .set_data(data))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn try_permit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner_pubkey: Binary,
+    spender: String,
+    amount: Uint128,
+    expiration: u64,
+    nonce: u64,
+    signature: Binary,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    if env.block.time.seconds() > expiration {
+        return Err(ContractError::SignatureExpired { expiration });
+    }
+    let owner = signature::pubkey_to_address(&owner_pubkey)?;
+    let spender_address = deps.api.addr_validate(&spender)?;
+    let doc = SignDoc::Permit {
+        chain_id: env.block.chain_id.clone(),
+        contract: env.contract.address.to_string(),
+        owner: owner.to_string(),
+        spender: spender_address.to_string(),
+        amount,
+        expiration,
+        nonce,
+    };
+    signature::verify_sign_doc(deps.api, &doc, &owner_pubkey, &signature)?;
+    assert_not_frozen(deps.storage, &owner)?;
+    use_nonce(deps.storage, owner.as_str(), nonce)?;
+
+    write_allowance(deps.storage, &owner, &spender_address, amount.u128())?;
+    let data = to_binary(&ApproveResponse {
+        owner: owner.to_string(),
+        spender: spender_address.to_string(),
+        allowance: amount,
+    })?;
+    Ok(Response::new()
+        .add_event(events::permit(
+            info.sender.as_str(),
+            owner.as_str(),
+            spender_address.as_str(),
+            amount,
+        ))
+        .set_data(data))
+}
+
+// The action runs as if the signer had sent it, the relayer only collects the fee
+fn try_execute_signed(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    payload: SignedPayload,
+    pubkey: Binary,
+    signature: Binary,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    assert_owner_or_role(deps.storage, &info.sender, Role::Relayer)?;
+    if env.block.time.seconds() > payload.deadline {
+        return Err(ContractError::SignatureExpired {
+            expiration: payload.deadline,
+        });
+    }
+    let doc = SignDoc::Execute {
+        chain_id: env.block.chain_id.clone(),
+        contract: env.contract.address.to_string(),
+        signer: signature::pubkey_to_address(&pubkey)?.to_string(),
+        payload: payload.clone(),
+    };
+    let signer = signature::verify_sign_doc(deps.api, &doc, &pubkey, &signature)?;
+    use_nonce(deps.storage, signer.as_str(), payload.nonce)?;
+
+    let amount = match &payload.action {
+        SignedAction::Transfer { amount, .. } | SignedAction::SendToEvm { amount, .. } => *amount,
+    };
+    let required = amount.checked_add(payload.fee).map_err(StdError::from)?;
+    let balance = read_balance(deps.storage, &signer)?;
+    if balance < required.u128() {
+        return Err(ContractError::InsufficientFunds {
+            balance,
+            required: required.u128(),
+        });
+    }
+
+    let signer_info = MessageInfo {
+        sender: signer.clone(),
+        funds: vec![],
+    };
+    let mut response = match payload.action {
+        SignedAction::Transfer { recipient, amount } => {
+            try_transfer(deps.branch(), env.clone(), signer_info, recipient, &amount)?
+        }
+        SignedAction::SendToEvm { recipient, amount } => {
+            try_send_to_erc20(deps.branch(), env.clone(), signer_info, recipient, amount, None)?
+        }
+    };
+    if !payload.fee.is_zero() {
+        perform_transfer(deps.storage, &env.block, &signer, &info.sender, payload.fee.u128())?;
+        response = response.add_event(events::transfer(
+            info.sender.as_str(),
+            signer.as_str(),
+            info.sender.as_str(),
+            payload.fee,
+        ));
+    }
+    Ok(response.add_event(events::execute_signed(
+        info.sender.as_str(),
+        signer.as_str(),
+        payload.nonce,
+        payload.fee,
+    )))
+}
+
+fn try_burn(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: &Uint128,
+) -> Result<Response<SendToEvmMsg>, ContractError> {
+    let amount_raw = amount.u128();
+    assert_not_frozen(deps.storage, &info.sender)?;
+
+    let mut account_balance = read_balance(deps.storage, &info.sender)?;
+
+    if account_balance < amount_raw {
+        return Err(ContractError::InsufficientFunds {
+            balance: account_balance,
+            required: amount_raw,
+        });
+    }
+    account_balance -= amount_raw;
+    let total_supply = read_total_supply(deps.storage)? - amount_raw;
+
+    let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
+    balances_store.set(
+        info.sender.as_str().as_bytes(),
+        &account_balance.to_be_bytes(),
+    );
+
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+
+    record_history(
+        deps.storage,
+        &env.block,
+        &info.sender,
+        HistoryKind::Burn,
+        None,
+        amount_raw,
+    )?;
+
+    let data = to_binary(&BurnResponse {
+        amount: *amount,
+        balance: Uint128::from(account_balance),
+        total_supply: Uint128::from(total_supply),
+    })?;
+    Ok(Response::new()
+        .add_event(events::burn(info.sender.as_str(), *amount))
+        .set_data(data))
+}
+
+fn perform_transfer(
+    store: &mut dyn Storage,
+    block: &BlockInfo,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    assert_not_frozen(store, from)?;
+    assert_not_frozen(store, to)?;
+    debit_balance(store, from, amount)?;
+    credit_transfer(store, block, from, to, amount)
+}
+
+// Takes an amount out of an account balance without crediting it anywhere
+fn debit_balance(store: &mut dyn Storage, from: &Addr, amount: u128) -> Result<(), ContractError> {
+    let mut balances_store = PrefixedStorage::new(store, PREFIX_BALANCES);
+
+    let mut from_balance = match balances_store.get(from.as_str().as_bytes()) {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0u128),
+    }?;
+
+    if from_balance < amount {
+        return Err(ContractError::InsufficientFunds {
+            balance: from_balance,
+            required: amount,
+        });
+    }
+    from_balance -= amount;
+    balances_store.set(from.as_str().as_bytes(), &from_balance.to_be_bytes());
+    Ok(())
+}
+
+// Adds an amount to an account balance without taking it from anywhere
+fn credit_balance(store: &mut dyn Storage, to: &Addr, amount: u128) -> Result<(), ContractError> {
+    let balance = Uint128::from(read_balance(store, to)?)
+        .checked_add(Uint128::from(amount))
+        .map_err(StdError::from)?;
+    let mut balances_store = PrefixedStorage::new(store, PREFIX_BALANCES);
+    balances_store.set(to.as_str().as_bytes(), &balance.u128().to_be_bytes());
+    Ok(())
+}
+
+// Credits an amount already debited from `from` and records the transfer in both histories
+// Callers check the frozen status of both accounts before debiting
+fn credit_transfer(
+    store: &mut dyn Storage,
+    block: &BlockInfo,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let mut balances_store = PrefixedStorage::new(store, PREFIX_BALANCES);
+    let mut to_balance = match balances_store.get(to.as_str().as_bytes()) {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0u128),
+    }?;
+    to_balance += amount;
+    balances_store.set(to.as_str().as_bytes(), &to_balance.to_be_bytes());
+
+    record_history(
+        store,
+        block,
+        from,
+        HistoryKind::Send,
+        Some(to.to_string()),
+        amount,
+    )?;
+    record_history(
+        store,
+        block,
+        to,
+        HistoryKind::Receive,
+        Some(from.to_string()),
+        amount,
+    )?;
+
+    Ok(())
+}
+
+// Converts 8 bytes value into u64
+fn bytes_to_u64(data: &[u8]) -> Result<u64, ContractError> {
+    match data[..].try_into() {
+        Ok(bytes) => Ok(u64::from_be_bytes(bytes)),
+        Err(_) => Err(ContractError::CorruptedDataFound {}),
+    }
+}
+
+fn read_bridge_transfer(store: &dyn Storage, id: u64) -> Result<BridgeTransfer, ContractError> {
+    let transfers_store = ReadonlyPrefixedStorage::new(store, PREFIX_BRIDGE_TRANSFERS);
+    let data = transfers_store
+        .get(&id.to_be_bytes())
+        .ok_or_else(|| StdError::not_found("BridgeTransfer"))?;
+    Ok(from_slice(&data)?)
+}
+
+// Stores a new bridge transfer record with its indexes and returns its id
+fn write_bridge_transfer(store: &mut dyn Storage, transfer: &BridgeTransfer) -> Result<(), ContractError> {
+    let mut transfers_store = PrefixedStorage::new(store, PREFIX_BRIDGE_TRANSFERS);
+    transfers_store.set(&transfer.id.to_be_bytes(), &to_vec(transfer)?);
+    Ok(())
+}
+
+fn create_bridge_transfer(
+    store: &mut dyn Storage,
+    block: &BlockInfo,
+    direction: BridgeDirection,
+    account: &Addr,
+    evm_address: Option<String>,
+    amount: Uint128,
+    status: BridgeTransferStatus,
+) -> Result<u64, ContractError> {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    let id = match config_store.get(KEY_BRIDGE_TRANSFER_SEQ) {
+        Some(data) => bytes_to_u64(&data)?,
+        None => 0,
+    };
+    config_store.set(KEY_BRIDGE_TRANSFER_SEQ, &(id + 1).to_be_bytes());
+
+    let transfer = BridgeTransfer {
+        id,
+        direction,
+        account: account.to_string(),
+        evm_address,
+        amount,
+        status,
+        height: block.height,
+        time: block.time,
+        deadline: None,
+        release_time: None,
+        fee: Uint128::zero(),
+        fee_collector: None,
+    };
+    write_bridge_transfer(store, &transfer)?;
+
+    let mut index_store = PrefixedStorage::multilevel(
+        store,
+        &[PREFIX_BRIDGE_TRANSFERS_BY_ACCOUNT, account.as_str().as_bytes()],
+    );
+    index_store.set(&id.to_be_bytes(), direction.key());
+
+    // A queued transfer already left the supply, so it is in flight until cancelled
+    match status {
+        BridgeTransferStatus::Pending => mark_pending(store, id),
+        BridgeTransferStatus::Queued => {
+            let mut queued_store = PrefixedStorage::new(store, PREFIX_QUEUED_OUTBOUND);
+            queued_store.set(&id.to_be_bytes(), &[1]);
+        }
+        _ => return Ok(id),
+    }
+    let in_flight = read_outbound_in_flight(store)?;
+    write_outbound_in_flight(store, in_flight + amount.u128());
+    Ok(id)
+}
+
+// Adds an outbound transfer to the unconfirmed ones
+fn mark_pending(store: &mut dyn Storage, id: u64) {
+    let mut pending_store = PrefixedStorage::new(store, PREFIX_PENDING_OUTBOUND);
+    pending_store.set(&id.to_be_bytes(), &[1]);
+}
+
+// Reads the running totals, or the bucket of a given day
+fn read_bridge_volume(store: &dyn Storage, day: Option<u64>) -> Result<BridgeVolume, ContractError> {
+    let data = match day {
+        Some(day) => ReadonlyPrefixedStorage::new(store, PREFIX_DAILY_STATS).get(&day.to_be_bytes()),
+        None => ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG).get(KEY_BRIDGE_STATS),
+    };
+    match data {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Ok(BridgeVolume::default()),
+    }
+}
+
+// Counts a transfer that actually minted or left for the EVM side, in the running
+// totals and in the bucket of the day the transfer was created
+// Refunds, escrows and queued sends are counted once they settle that way, if ever
+fn record_bridge_volume(store: &mut dyn Storage, id: u64) -> Result<(), ContractError> {
+    let transfer = read_bridge_transfer(store, id)?;
+    update_bridge_volume(store, transfer.direction, transfer.amount, transfer.time, BridgeVolume::add)
+}
+
+// Takes a cancelled transfer back out of the totals and its day bucket
+fn remove_bridge_volume(store: &mut dyn Storage, transfer: &BridgeTransfer) -> Result<(), ContractError> {
+    update_bridge_volume(store, transfer.direction, transfer.amount, transfer.time, BridgeVolume::sub)
+}
+
+fn update_bridge_volume(
+    store: &mut dyn Storage,
+    direction: BridgeDirection,
+    amount: Uint128,
+    time: Timestamp,
+    update: fn(&mut BridgeVolume, BridgeDirection, Uint128) -> StdResult<()>,
+) -> Result<(), ContractError> {
+    let day = time.seconds() / SECONDS_PER_DAY;
+
+    let mut totals = read_bridge_volume(store, None)?;
+    update(&mut totals, direction, amount)?;
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_BRIDGE_STATS, &to_vec(&totals)?);
+
+    let mut daily = read_bridge_volume(store, Some(day))?;
+    update(&mut daily, direction, amount)?;
+    let mut daily_store = PrefixedStorage::new(store, PREFIX_DAILY_STATS);
+    daily_store.set(&day.to_be_bytes(), &to_vec(&daily)?);
+    Ok(())
+}
+
+// Zero disables the per-account history
+fn read_history_limit(store: &dyn Storage) -> Result<u32, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_HISTORY_LIMIT) {
+        Some(data) => match data[..].try_into() {
+            Ok(bytes) => Ok(u32::from_be_bytes(bytes)),
+            Err(_) => Err(ContractError::CorruptedDataFound {}),
+        },
+        None => Ok(0),
+    }
+}
+
+// Appends an entry to the account's history and drops entries beyond the configured limit
+fn record_history(
+    store: &mut dyn Storage,
+    block: &BlockInfo,
+    account: &Addr,
+    kind: HistoryKind,
+    counterparty: Option<String>,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let limit = read_history_limit(store)? as u64;
+    if limit == 0 {
+        return Ok(());
+    }
+
+    let mut seq_store = PrefixedStorage::new(store, PREFIX_HISTORY_SEQ);
+    let id = match seq_store.get(account.as_str().as_bytes()) {
+        Some(data) => bytes_to_u64(&data)?,
+        None => 0,
+    };
+    seq_store.set(account.as_str().as_bytes(), &(id + 1).to_be_bytes());
+
+    let entry = HistoryEntry {
+        id,
+        kind,
+        counterparty,
+        amount: Uint128::from(amount),
+        height: block.height,
+        time: block.time,
+    };
+    let mut history_store =
+        PrefixedStorage::multilevel(store, &[PREFIX_HISTORY, account.as_str().as_bytes()]);
+    history_store.set(&id.to_be_bytes(), &to_vec(&entry)?);
+
+    // Everything before the oldest kept entry goes, which also covers a lowered limit
+    if id + 1 > limit {
+        let oldest_kept = (id + 1 - limit).to_be_bytes();
+        let expired: Vec<Vec<u8>> = history_store
+            .range(None, Some(&oldest_kept), Order::Ascending)
+            .map(|(key, _)| key)
+            .collect();
+        for key in expired {
+            history_store.remove(&key);
+        }
+    }
+    Ok(())
+}
+
+// Converts 16 bytes value into u128
+// Errors if data found that is not 16 bytes
+pub fn bytes_to_u128(data: &[u8]) -> Result<u128, ContractError> {
+    match data[0..16].try_into() {
+        Ok(bytes) => Ok(u128::from_be_bytes(bytes)),
+        Err(_) => Err(ContractError::CorruptedDataFound {}),
+    }
+}
+
+// Reads 16 byte storage value into u128
+// Returns zero if key does not exist. Errors if data found that is not 16 bytes
+pub fn read_u128(store: &ReadonlyPrefixedStorage, key: &Addr) -> Result<u128, ContractError> {
+    let result = store.get(key.as_str().as_bytes());
+    match result {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0u128),
+    }
+}
+
+fn read_constants(store: &dyn Storage) -> Result<Constants, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    let data = config_store
+        .get(KEY_CONSTANTS)
+        .ok_or_else(|| StdError::not_found("Constants"))?;
+    Ok(from_slice(&data)?)
+}
+
+// Returns None for contracts instantiated before an owner was recorded
+fn read_owner(store: &dyn Storage) -> Result<Option<Addr>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_OWNER) {
+        Some(data) => match String::from_utf8(data) {
+            Ok(owner) => Ok(Some(Addr::unchecked(owner))),
+            Err(_) => Err(ContractError::CorruptedDataFound {}),
+        },
+        None => Ok(None),
+    }
+}
+
+fn assert_owner(store: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    match read_owner(store)? {
+        Some(owner) if owner == *sender => Ok(()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+// MintCW20 may come from the OKC module caller of the paired EVM contract
+// or from any account holding the bridge caller role
+fn assert_bridge_caller(store: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    if has_role(store, Role::BridgeCaller, sender) {
+        return Ok(());
+    }
+
+    //read evm contract with [u8]
+    let const_data = read_constants(store)?;
+    let evm_contract_address = evm_address_bytes(&const_data.contract)?;
+
+    //read sender address with [u8]
+    let sender_bytes = bech32::decode(sender.as_str())
+        .ok()
+        .and_then(|(_, data, _)| Vec::<u8>::from_base32(&data).ok());
+
+    //check tx sender is specified address
+    if sender_bytes != Some(evm_contract_address) {
+        return Err(ContractError::InvalidSender {
+            address: sender.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn assert_owner_or_role(store: &dyn Storage, sender: &Addr, role: Role) -> Result<(), ContractError> {
+    if has_role(store, role, sender) {
+        return Ok(());
+    }
+    assert_owner(store, sender)
+}
+
+fn assert_owner_or_guardian(store: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    if read_guardian_set(store)?.guardians.contains(sender) {
+        return Ok(());
+    }
+    assert_owner(store, sender)
+}
+
+fn assert_not_frozen(store: &dyn Storage, address: &Addr) -> Result<(), ContractError> {
+    let frozen_store = ReadonlyPrefixedStorage::new(store, PREFIX_FROZEN);
+    if frozen_store.get(address.as_str().as_bytes()).is_some() {
+        return Err(ContractError::AccountFrozen {
+            address: address.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn read_allowlist_enabled(store: &dyn Storage) -> bool {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    matches!(config_store.get(KEY_ALLOWLIST_ENABLED), Some(data) if data == [1])
+}
+
+// Only enforced while the allow-list mode is on
+fn assert_allowlisted(store: &dyn Storage, address: &Addr) -> Result<(), ContractError> {
+    if !read_allowlist_enabled(store) {
+        return Ok(());
+    }
+    let allowlist_store = ReadonlyPrefixedStorage::new(store, PREFIX_BRIDGE_ALLOWLIST);
+    if allowlist_store.get(address.as_str().as_bytes()).is_none() {
+        return Err(ContractError::NotAllowlisted {
+            address: address.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// Turns the last key of the previous page into an exclusive range start
+fn calc_range_start(start_after: Option<&[u8]>) -> Option<Vec<u8>> {
+    start_after.map(|key| {
+        let mut start = key.to_vec();
+        start.push(0);
+        start
+    })
+}
+
+// Iterates a store keyed by big-endian ids, starting right after start_after
+// Nothing can follow the largest id, so that page is empty
+fn range_after_id<'a>(
+    store: &'a dyn Storage,
+    start_after: Option<u64>,
+) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+    match start_after.map(|id| id.checked_add(1)) {
+        Some(Some(start)) => store.range(Some(&start.to_be_bytes()), None, Order::Ascending),
+        Some(None) => Box::new(std::iter::empty()),
+        None => store.range(None, None, Order::Ascending),
+    }
+}
+
+fn has_role(store: &dyn Storage, role: Role, address: &Addr) -> bool {
+    let role_store = ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_ROLES, role.key()]);
+    role_store.get(address.as_str().as_bytes()).is_some()
+}
+
+fn grant_role(store: &mut dyn Storage, role: Role, address: &Addr) {
+    let mut role_store = PrefixedStorage::multilevel(store, &[PREFIX_ROLES, role.key()]);
+    role_store.set(address.as_str().as_bytes(), &[1]);
+}
+
+fn read_total_supply(store: &dyn Storage) -> Result<u128, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    let data = config_store
+        .get(KEY_TOTAL_SUPPLY)
+        .ok_or_else(|| StdError::not_found("total supply"))?;
+    bytes_to_u128(&data)
+}
+
+// Sum of outbound transfers that are not confirmed on the EVM side yet
+fn read_outbound_in_flight(store: &dyn Storage) -> Result<u128, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_OUTBOUND_IN_FLIGHT) {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0),
+    }
+}
+
+fn write_outbound_in_flight(store: &mut dyn Storage, amount: u128) {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_OUTBOUND_IN_FLIGHT, &amount.to_be_bytes());
+}
+
+// Optimistic mints held until finalized or resolved, not yet in the total supply
+fn read_pending_inbound(store: &dyn Storage) -> Result<u128, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_PENDING_INBOUND) {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0),
+    }
+}
+
+fn write_pending_inbound(store: &mut dyn Storage, amount: u128) {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_PENDING_INBOUND, &amount.to_be_bytes());
+}
+
+fn read_reserve_auto_pause(store: &dyn Storage) -> bool {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    matches!(config_store.get(KEY_RESERVE_AUTO_PAUSE), Some(data) if data == [1])
+}
+
+// Off by default, see UpdateConfig::sender_cancel_enabled
+fn read_sender_cancel_enabled(store: &dyn Storage) -> bool {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    matches!(config_store.get(KEY_SENDER_CANCEL_ENABLED), Some(data) if data == [1])
+}
+
+// Off by default, see UpdateConfig::claim_by_sender
+fn read_claim_by_sender(store: &dyn Storage) -> bool {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    matches!(config_store.get(KEY_CLAIM_BY_SENDER), Some(data) if data == [1])
+}
+
+fn read_bridge_config(store: &dyn Storage) -> Result<BridgeConfigResponse, ContractError> {
+    let constants = read_constants(store)?;
+    let directions = read_directions(store)?;
+    Ok(BridgeConfigResponse {
+        module_caller: module_caller_address(&constants.contract)?,
+        evm_contract: constants.contract,
+        owner: read_owner(store)?.map(String::from),
+        inbound_enabled: directions.inbound,
+        outbound_enabled: directions.outbound,
+        cap: read_cap(store)?.map(Uint128::from),
+        allowlist_enabled: read_allowlist_enabled(store),
+        history_limit: read_history_limit(store)?,
+        reserve_auto_pause: read_reserve_auto_pause(store),
+        timelock_threshold: Uint128::from(read_timelock_threshold(store)?),
+        timelock_delay: read_timelock_delay(store)?,
+        optimistic_threshold: Uint128::from(read_optimistic_threshold(store)?),
+        challenge_period: read_challenge_period(store)?,
+        sender_cancel_enabled: read_sender_cancel_enabled(store),
+        claim_by_sender: read_claim_by_sender(store),
+        fee_schedule: read_fee_schedule(store)?,
+    })
+}
+
+// Zero when every mint is credited at once
+fn read_optimistic_threshold(store: &dyn Storage) -> Result<u128, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_OPTIMISTIC_THRESHOLD) {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0),
+    }
+}
+
+fn read_challenge_period(store: &dyn Storage) -> Result<u64, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_CHALLENGE_PERIOD) {
+        Some(data) => bytes_to_u64(&data),
+        None => Ok(0),
+    }
+}
+
+// Zero when no timelock is configured
+fn read_timelock_threshold(store: &dyn Storage) -> Result<u128, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_TIMELOCK_THRESHOLD) {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0),
+    }
+}
+
+fn read_timelock_delay(store: &dyn Storage) -> Result<u64, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_TIMELOCK_DELAY) {
+        Some(data) => bytes_to_u64(&data),
+        None => Ok(0),
+    }
+}
+
+fn read_cap(store: &dyn Storage) -> Result<Option<u128>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_CAP) {
+        Some(data) => Ok(Some(bytes_to_u128(&data)?)),
+        None => Ok(None),
+    }
+}
+
+// Reads the enabled bridge directions
+// Contracts instantiated before the flags existed have both directions enabled
+fn read_directions(store: &dyn Storage) -> Result<BridgeDirections, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_DIRECTIONS) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Ok(BridgeDirections {
+            inbound: true,
+            outbound: true,
+        }),
+    }
+}
+
+fn read_balance(store: &dyn Storage, owner: &Addr) -> Result<u128, ContractError> {
+    let balance_store = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES);
+    read_u128(&balance_store, owner)
+}
+
+fn read_allowance(
+    store: &dyn Storage,
+    owner: &Addr,
+    spender: &Addr,
+) -> Result<u128, ContractError> {
+    let owner_store =
+        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()]);
+    read_u128(&owner_store, spender)
+}
+
+// Keyed by the wasm address of a signing key, or the lower case EVM address of a claimant
+fn read_nonce(store: &dyn Storage, account: &str) -> Result<u64, ContractError> {
+    let nonce_store = ReadonlyPrefixedStorage::new(store, PREFIX_NONCES);
+    match nonce_store.get(account.as_bytes()) {
+        Some(data) => bytes_to_u64(&data),
+        None => Ok(0),
+    }
+}
+
+// Signed messages must use the current nonce, which is then advanced so the
+// same signature cannot be submitted twice
+fn use_nonce(store: &mut dyn Storage, account: &str, nonce: u64) -> Result<(), ContractError> {
+    let expected = read_nonce(store, account)?;
+    if nonce != expected {
+        return Err(ContractError::InvalidNonce {
+            expected,
+            got: nonce,
+        });
+    }
+    let mut nonce_store = PrefixedStorage::new(store, PREFIX_NONCES);
+    nonce_store.set(account.as_bytes(), &(expected + 1).to_be_bytes());
+    Ok(())
+}
+
+#[allow(clippy::unnecessary_wraps)]
+fn write_allowance(
+    store: &mut dyn Storage,
+    owner: &Addr,
+    spender: &Addr,
+    amount: u128,
+) -> StdResult<()> {
+    let mut owner_store =
+        PrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()]);
+    owner_store.set(spender.as_str().as_bytes(), &amount.to_be_bytes());
+    Ok(())
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    if bytes.len() < 3 || bytes.len() > 30 {
+        return false;
+    }
+    true
+}
+
+fn is_valid_symbol(symbol: &str) -> bool {
+    let bytes = symbol.as_bytes();
+    if bytes.len() < 3 || bytes.len() > 6 {
+        return false;
+    }
+    for byte in bytes.iter() {
+        if *byte < 65 || *byte > 90 {
+            return false;
+        }
+    }
+    true
+}
+
+// Decodes a "0x" prefixed EVM address into its 20 raw bytes
+fn evm_address_bytes(address: &str) -> Result<Vec<u8>, ContractError> {
+    if !is_valid_eth_address(address) {
+        return Err(ContractError::ContractERC20Err {
+            address: address.to_string(),
+        });
+    }
+    hex::decode(&address[2..]).map_err(|_| ContractError::ContractERC20Err {
+        address: address.to_string(),
+    })
+}
+
+// The OKC module calls MintCW20 from the "ex" address holding the EVM contract's bytes
+fn module_caller_address(evm_contract: &str) -> Result<String, ContractError> {
+    let bytes = evm_address_bytes(evm_contract)?;
+    bech32::encode(ADDRESS_PREFIX, bytes.to_base32(), Variant::Bech32).map_err(|_| {
+        ContractError::ContractERC20Err {
+            address: evm_contract.to_string(),
+        }
+    })
+}
+
+fn is_valid_eth_address(input: &str) -> bool {
+    
+    if input.len() != 42 {
+        return false;
+    }
+    if !input.starts_with("0x") {
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::InitialBalance;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{from_slice, Addr, Env, MessageInfo, Storage, Timestamp, Uint128};
+    use cosmwasm_storage::ReadonlyPrefixedStorage;
+    use k256::ecdsa::signature::DigestSigner;
+    use k256::ecdsa::{recoverable, Signature, SigningKey};
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    use sha3::{Digest, Keccak256};
+
+    fn mock_env_height(signer: &str, height: u64, time: u64) -> (Env, MessageInfo) {
+        let mut env = mock_env();
+        let info = mock_info(signer, &[]);
+        env.block.height = height;
+        env.block.time = Timestamp::from_seconds(time);
+        (env, info)
+    }
+
+    fn get_constants(storage: &dyn Storage) -> Constants {
+        let config_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_CONFIG);
+        let data = config_storage
+            .get(KEY_CONSTANTS)
+            .expect("no config data stored");
+        from_slice(&data).expect("invalid data")
+    }
+
+    fn get_total_supply(storage: &dyn Storage) -> u128 {
+        let config_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_CONFIG);
+        let data = config_storage
+            .get(KEY_TOTAL_SUPPLY)
+            .expect("no decimals data stored");
+        bytes_to_u128(&data).unwrap()
+    }
+
+    fn get_balance(storage: &dyn Storage, address: &Addr) -> u128 {
+        let balances_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_BALANCES);
+        read_u128(&balances_storage, address).unwrap()
+    }
+
+    fn get_allowance(storage: &dyn Storage, owner: &Addr, spender: &Addr) -> u128 {
+        let owner_storage = ReadonlyPrefixedStorage::multilevel(
+            storage,
+            &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()],
+        );
+        read_u128(&owner_storage, spender).unwrap()
+    }
+
+    fn setup_with_balances(
+        initial_balances: Vec<InitialBalance>,
+    ) -> cosmwasm_std::OwnedDeps<
+        cosmwasm_std::testing::MockStorage,
+        cosmwasm_std::testing::MockApi,
+        cosmwasm_std::testing::MockQuerier,
+    > {
+        let mut deps = mock_dependencies(&[]);
+        let instantiate_msg = InstantiateMsg {
+            name: "Cash Token".to_string(),
+            symbol: "CASH".to_string(),
+            decimals: 9,
+            evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+            initial_balances,
+            ..Default::default()
+        };
+        let (env, info) = mock_env_height("creator", 450, 550);
+        instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+        deps
+    }
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32]).unwrap()
+    }
+
+    fn uncompressed_pubkey(key: &SigningKey) -> Vec<u8> {
+        key.verifying_key().to_encoded_point(false).as_bytes().to_vec()
+    }
+
+    fn key_address(key: &SigningKey) -> Addr {
+        crate::signature::pubkey_to_address(&uncompressed_pubkey(key)).unwrap()
+    }
+
+    fn evm_address(key: &SigningKey) -> String {
+        let bytes = crate::signature::pubkey_to_evm_address(&uncompressed_pubkey(key)).unwrap();
+        format!("0x{}", hex::encode(bytes))
+    }
+
+    // Signs keccak256 of the doc, as Permit and ExecuteSigned expect
+    fn sign_doc(key: &SigningKey, doc: &crate::msg::SignDoc) -> Binary {
+        let signature: Signature = key.sign_digest(Keccak256::new().chain(to_vec(doc).unwrap()));
+        Binary::from(signature.as_ref())
+    }
+
+    // personal_sign as an EVM wallet does it, with v as 27 or 28
+    fn personal_sign(key: &SigningKey, doc: &crate::msg::SignDoc) -> Binary {
+        let message = to_vec(doc).unwrap();
+        let digest = Keccak256::new()
+            .chain(format!("\x19Ethereum Signed Message:\n{}", message.len()))
+            .chain(&message);
+        let signature: recoverable::Signature = key.sign_digest(digest);
+        let mut bytes = signature.as_ref().to_vec();
+        bytes[64] += 27;
+        Binary::from(bytes)
+    }
+
+    mod instantiate {
+        use super::*;
+        use crate::error::ContractError;
+
+        #[test]
+        fn works() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            assert_eq!(
+                get_constants(&deps.storage),
+                Constants {
+                    name: "Cash Token".to_string(),
+                    symbol: "CASH".to_string(),
+                    decimals: 9,
+                    contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                }
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                0
+            );
+            assert_eq!(get_total_supply(&deps.storage), 0);
+        }
+
+        #[test]
+        fn works_with_empty_balance() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            assert_eq!(get_total_supply(&deps.storage), 0);
+        }
+
+
+        #[test]
+        fn works_with_balance_larger_than_53_bit() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+
+            let mint_cw20_msg = ExecuteMsg::MintCW20 { recipient: "addr0000".to_string(), amount: (Uint128::from(9007199254740993u128)), event_id: None, evm_sender: None };
+
+            let (env, info) = mock_env_height("ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr", 450, 550);
+            execute(deps.as_mut(), env, info, mint_cw20_msg).unwrap();
+
+            assert_eq!(0, res.messages.len());
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                9007199254740993
+            );
+            assert_eq!(get_total_supply(&deps.storage), 9007199254740993);
+        }
+
+        #[test]
+        fn works_with_balance_larger_than_64_bit() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+
+            let mint_cw20_msg = ExecuteMsg::MintCW20 { recipient: "addr0000".to_string(), amount: (Uint128::from(100000000000000000000000000u128)), event_id: None, evm_sender: None };
+
+            let (env, info) = mock_env_height("ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr", 450, 550);
+            execute(deps.as_mut(), env, info, mint_cw20_msg).unwrap();
+
+            assert_eq!(0, res.messages.len());
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                100000000000000000000000000
+            );
+            assert_eq!(get_total_supply(&deps.storage), 100000000000000000000000000);
+        }
+
+        #[test]
+        fn fails_for_large_decimals() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 42,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::DecimalsExceeded {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_name_too_short() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "CC".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::NameWrongFormat {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_name_too_long() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash coin. Cash coin. Cash coin. Cash coin.".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::NameWrongFormat {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_symbol_too_short() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "De De".to_string(),
+                symbol: "DD".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::TickerWrongSymbolFormat {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_symbol_too_long() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Super Coin".to_string(),
+                symbol: "SUPERCOIN".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::TickerWrongSymbolFormat {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn works_with_initial_balances_cap_owner_and_callers() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(11u128),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(22u128),
+                    },
+                ],
+                cap: Some(Uint128::from(40u128)),
+                owner: Some("admin".to_string()),
+                bridge_callers: vec!["relayer".to_string()],
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 11);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 22);
+            assert_eq!(get_total_supply(&deps.storage), 33);
+            assert_eq!(read_owner(&deps.storage).unwrap(), Some(Addr::unchecked("admin")));
+            assert_eq!(read_cap(&deps.storage).unwrap(), Some(40));
+
+            // The bridge caller can mint up to the cap
+            let mint_cw20_msg = ExecuteMsg::MintCW20 { recipient: "addr0000".to_string(), amount: Uint128::from(7u128), event_id: None, evm_sender: None };
+            let (env, info) = mock_env_height("relayer", 450, 550);
+            execute(deps.as_mut(), env, info, mint_cw20_msg).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 18);
+            assert_eq!(get_total_supply(&deps.storage), 40);
+
+            let mint_cw20_msg = ExecuteMsg::MintCW20 { recipient: "addr0000".to_string(), amount: Uint128::from(1u128), event_id: None, evm_sender: None };
+            let (env, info) = mock_env_height("relayer", 450, 550);
+            let result = execute(deps.as_mut(), env, info, mint_cw20_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::CapExceeded { cap: 40, required: 41 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_total_supply(&deps.storage), 40);
+
+            // Other accounts are still rejected
+            let mint_cw20_msg = ExecuteMsg::MintCW20 { recipient: "addr0000".to_string(), amount: Uint128::from(0u128), event_id: None, evm_sender: None };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let result = execute(deps.as_mut(), env, info, mint_cw20_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidSender { .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_initial_balances_above_cap() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11u128),
+                }],
+                cap: Some(Uint128::from(10u128)),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::CapExceeded { cap: 10, required: 11 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_initial_balances_overflowing_supply() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(u128::MAX),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(1u128),
+                    },
+                ],
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Std(StdError::Overflow { .. })) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_duplicate_initial_balances() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(1u128),
+                    },
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(2u128),
+                    },
+                ],
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::DuplicateInitialBalanceAddresses {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_invalid_evm_contract() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "abc".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::ContractERC20Err { address }) => assert_eq!(address, "abc"),
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_symbol_lowercase() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CaSH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::TickerWrongSymbolFormat {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod transfer {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::attr;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn can_send_to_existing_recipient() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Initial state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                0
+            );
+            assert_eq!(get_total_supply(&deps.storage), 0);
+            // Transfer
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(0u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(transfer_result.messages.len(), 0);
+            assert_eq!(transfer_result.events.len(), 1);
+            assert_eq!(transfer_result.events[0].ty, "transfer");
+            assert_eq!(
+                transfer_result.events[0].attributes,
+                vec![
+                    attr("sender", "addr0000"),
+                    attr("from", "addr0000"),
+                    attr("to", "addr1111"),
+                    attr("amount", "0"),
+                ]
+            );
+            // New state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                0
+            ); // -1
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                0
+            ); // +1
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                0
+            );
+            assert_eq!(get_total_supply(&deps.storage), 0);
+        }
+
+        #[test]
+        fn can_send_to_non_existent_recipient() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Initial state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                0
+            );
+            assert_eq!(get_total_supply(&deps.storage), 0);
+            // Transfer
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr2323".to_string(),
+                amount: Uint128::from(0u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(transfer_result.messages.len(), 0);
+            assert_eq!(transfer_result.events.len(), 1);
+            assert_eq!(transfer_result.events[0].ty, "transfer");
+            assert_eq!(
+                transfer_result.events[0].attributes,
+                vec![
+                    attr("sender", "addr0000"),
+                    attr("from", "addr0000"),
+                    attr("to", "addr2323"),
+                    attr("amount", "0"),
+                ]
+            );
+            // New state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr2323".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                0
+            );
+            assert_eq!(get_total_supply(&deps.storage), 0);
+        }
+
+        #[test]
+        fn can_send_zero_amount() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Initial state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                0
+            );
+            assert_eq!(get_total_supply(&deps.storage), 0);
+            // Transfer
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(0u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(transfer_result.messages.len(), 0);
+            assert_eq!(transfer_result.events.len(), 1);
+            assert_eq!(transfer_result.events[0].ty, "transfer");
+            assert_eq!(
+                transfer_result.events[0].attributes,
+                vec![
+                    attr("sender", "addr0000"),
+                    attr("from", "addr0000"),
+                    attr("to", "addr1111"),
+                    attr("amount", "0"),
+                ]
+            );
+            // New state (unchanged)
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                0
+            );
+            assert_eq!(get_total_supply(&deps.storage), 0);
+        }
+
+        #[test]
+        fn can_send_to_sender() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let sender = "addr0000";
+            // Initial state
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(sender)), 0);
+            // Transfer
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: sender.to_string(),
+                amount: Uint128::from(0u128),
+            };
+            let (env, info) = mock_env_height(sender, 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(transfer_result.messages.len(), 0);
+            assert_eq!(transfer_result.events.len(), 1);
+            assert_eq!(transfer_result.events[0].ty, "transfer");
+            assert_eq!(
+                transfer_result.events[0].attributes,
+                vec![
+                    attr("sender", "addr0000"),
+                    attr("from", "addr0000"),
+                    attr("to", "addr0000"),
+                    attr("amount", "0"),
+                ]
+            );
+            // New state
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(sender)), 0);
+        }
+
+        #[test]
+        fn fails_on_insufficient_balance() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Initial state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                0
+            );
+            assert_eq!(get_total_supply(&deps.storage), 0);
+            // Transfer
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(12u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg);
+            match transfer_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientFunds {
+                    balance: 0,
+                    required: 12,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            // New state (unchanged)
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                0
+            );
+            assert_eq!(get_total_supply(&deps.storage), 0);
+        }
+    }
+
+    mod approve {
+        use super::*;
+        use cosmwasm_std::attr;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            }
+        }
+
+        fn make_spender() -> Addr {
+            Addr::unchecked("dadadadadadadada".to_string())
+        }
+
+        #[test]
+        fn has_zero_allowance_by_default() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Existing owner
+            assert_eq!(
+                get_allowance(&deps.storage, &Addr::unchecked("addr0000"), &make_spender()),
+                0
+            );
+            // Non-existing owner
+            assert_eq!(
+                get_allowance(
+                    &deps.storage,
+                    &Addr::unchecked("addr4567".to_string()),
+                    &make_spender()
+                ),
+                0
+            );
+        }
+
+        #[test]
+        fn can_set_allowance() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            assert_eq!(
+                get_allowance(
+                    &deps.storage,
+                    &Addr::unchecked("addr7654".to_string()),
+                    &make_spender()
+                ),
+                0
+            );
+            // First approval
+            let owner = Addr::unchecked("addr7654".to_string());
+            let spender = make_spender();
+            let approve_msg1 = ExecuteMsg::Approve {
+                spender: spender.clone().to_string().to_string(),
+                amount: Uint128::from(334422u128),
+            };
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
+            let approve_result1 = execute(deps.as_mut(), env, info, approve_msg1).unwrap();
+            assert_eq!(approve_result1.messages.len(), 0);
+            assert_eq!(approve_result1.events.len(), 1);
+            assert_eq!(approve_result1.events[0].ty, "approve");
+            assert_eq!(
+                approve_result1.events[0].attributes,
+                vec![
+                    attr("sender", owner.clone().to_string()),
+                    attr("owner", owner.clone().to_string()),
+                    attr("spender", spender.clone().to_string()),
+                    attr("amount", "334422"),
+                ]
+            );
+            assert_eq!(
+                get_allowance(&deps.storage, &owner, &make_spender()),
+                334422
+            );
+            // Updated approval
+            let approve_msg = ExecuteMsg::Approve {
+                spender: spender.clone().to_string().to_string(),
+                amount: Uint128::from(777888u128),
+            };
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
+            let approve_result2 = execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            assert_eq!(approve_result2.messages.len(), 0);
+            assert_eq!(approve_result2.events.len(), 1);
+            assert_eq!(approve_result2.events[0].ty, "approve");
+            assert_eq!(
+                approve_result2.events[0].attributes,
+                vec![
+                    attr("sender", owner.as_str()),
+                    attr("owner", owner.as_str()),
+                    attr("spender", spender.as_str()),
+                    attr("amount", "777888"),
+                ]
+            );
+            assert_eq!(get_allowance(&deps.storage, &owner, &spender), 777888);
+        }
+    }
+
+    mod transfer_from {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::{attr, Addr};
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            }
+        }
+
+        fn make_spender() -> Addr {
+            Addr::unchecked("dadadadadadadada".to_string())
+        }
+
+        #[test]
+        fn works() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let owner = "addr0000";
+            let spender = make_spender();
+            let recipient = Addr::unchecked("addr1212".to_string());
+            // Set approval
+            let approve_msg = ExecuteMsg::Approve {
+                spender: spender.clone().to_string().to_string(),
+                amount: Uint128::from(4u128),
+            };
+            let (env, info) = mock_env_height(owner, 450, 550);
+            let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            assert_eq!(approve_result.messages.len(), 0);
+            assert_eq!(approve_result.events.len(), 1);
+            assert_eq!(approve_result.events[0].ty, "approve");
+            assert_eq!(
+                approve_result.events[0].attributes,
+                vec![
+                    attr("sender", owner.to_string()),
+                    attr("owner", owner.to_string()),
+                    attr("spender", spender.clone().to_string()),
+                    attr("amount", "4"),
+                ]
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked(owner)),
+                0
+            );
+            assert_eq!(
+                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
+                4
+            );
+            // Transfer less than allowance but more than balance
+            let transfer_from_msg = ExecuteMsg::TransferFrom {
+                owner: owner.to_string().to_string(),
+                recipient: recipient.clone().to_string(),
+                amount: Uint128::from(0u128),
+            };
+            let (env, info) = mock_env_height(spender.as_str(), 450, 550);
+            let transfer_from_result =
+                execute(deps.as_mut(), env, info, transfer_from_msg).unwrap();
+            assert_eq!(transfer_from_result.messages.len(), 0);
+            assert_eq!(transfer_from_result.events.len(), 1);
+            assert_eq!(transfer_from_result.events[0].ty, "transfer");
+            assert_eq!(
+                transfer_from_result.events[0].attributes,
+                vec![
+                    attr("sender", spender.clone()),
+                    attr("from", owner),
+                    attr("to", recipient),
+                    attr("amount", "0"),
+                ]
+            );
+            // State changed
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(owner)), 0);
+            assert_eq!(
+                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
+                4
+            );
+        }
+
+        #[test]
+        fn fails_when_allowance_too_low() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let owner = "addr0000";
+            let spender = make_spender();
+            let recipient = Addr::unchecked("addr1212".to_string());
+            // Set approval
+            let approve_msg = ExecuteMsg::Approve {
+                spender: spender.clone().to_string(),
+                amount: Uint128::from(2u128),
+            };
+            let (env, info) = mock_env_height(owner, 450, 550);
+            let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            assert_eq!(approve_result.messages.len(), 0);
+            assert_eq!(approve_result.events.len(), 1);
+            assert_eq!(approve_result.events[0].ty, "approve");
+            assert_eq!(
+                approve_result.events[0].attributes,
+                vec![
+                    attr("sender", owner.to_string()),
+                    attr("owner", owner.to_string()),
+                    attr("spender", spender.clone().to_string()),
+                    attr("amount", "2"),
+                ]
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(owner)), 0);
+            assert_eq!(
+                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
+                2
+            );
+            // Transfer less than allowance but more than balance
+            let fransfer_from_msg = ExecuteMsg::TransferFrom {
+                owner: owner.to_string(),
+                recipient: recipient.clone().to_string(),
+                amount: Uint128::from(3u128),
+            };
+            let (env, info) = mock_env_height(spender.as_str(), 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, fransfer_from_msg);
+            match transfer_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientAllowance {
+                    allowance: 2,
+                    required: 3,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_when_allowance_is_set_but_balance_too_low() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let owner = "addr0000";
+            let spender = make_spender();
+            let recipient = Addr::unchecked("addr1212".to_string());
+            // Set approval
+            let approve_msg = ExecuteMsg::Approve {
+                spender: spender.clone().to_string(),
+                amount: Uint128::from(100u128),
+            };
+            let (env, info) = mock_env_height(owner, 450, 550);
+            let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            assert_eq!(approve_result.messages.len(), 0);
+            assert_eq!(approve_result.events.len(), 1);
+            assert_eq!(approve_result.events[0].ty, "approve");
+            assert_eq!(
+                approve_result.events[0].attributes,
+                vec![
+                    attr("sender", owner.to_string()),
+                    attr("owner", owner.to_string()),
+                    attr("spender", spender.clone().to_string()),
+                    attr("amount", "100"),
+                ]
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(owner)), 0);
+            assert_eq!(
+                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
+                100
+            );
+            // Transfer less than allowance but more than balance
+            let fransfer_from_msg = ExecuteMsg::TransferFrom {
+                owner: owner.to_string(),
+                recipient: recipient.clone().to_string(),
+                amount: Uint128::from(100u128),
+            };
+            let (env, info) = mock_env_height(spender.as_str(), 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, fransfer_from_msg);
+            match transfer_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientFunds {
+                    balance: 0,
+                    required: 100,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod burn {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::{attr, Addr};
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn can_burn_zero_amount() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Initial state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                0
+            );
+            assert_eq!(get_total_supply(&deps.storage), 0);
+            // Burn
+            let burn_msg = ExecuteMsg::Burn {
+                amount: Uint128::from(0u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let burn_result = execute(deps.as_mut(), env, info, burn_msg).unwrap();
+            assert_eq!(burn_result.messages.len(), 0);
+            assert_eq!(burn_result.events.len(), 1);
+            assert_eq!(burn_result.events[0].ty, "burn");
+            assert_eq!(
+                burn_result.events[0].attributes,
+                vec![
+                    attr("sender", "addr0000"),
+                    attr("from", "addr0000"),
+                    attr("amount", "0"),
+                ]
+            );
+            // New state (unchanged)
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                0
+            );
+            assert_eq!(get_total_supply(&deps.storage), 0);
+        }
+
+        #[test]
+        fn fails_on_insufficient_balance() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            // Initial state
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                0
+            );
+            assert_eq!(get_total_supply(&deps.storage), 0);
+            // Burn
+            let burn_msg = ExecuteMsg::Burn {
+                amount: Uint128::from(12u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let burn_result = execute(deps.as_mut(), env, info, burn_msg);
+            match burn_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientFunds {
+                    balance: 0,
+                    required: 12,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            // New state (unchanged)
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                0
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                0
+            );
+            assert_eq!(get_total_supply(&deps.storage), 0);
+        }
+    }
+
+    mod query {
+        use super::*;
+        use cosmwasm_std::{attr, Addr};
+
+        fn address(index: u8) -> Addr {
+            match index {
+                0 => Addr::unchecked("addr0000".to_string()), // contract instantiateializer
+                1 => Addr::unchecked("addr1111".to_string()),
+                2 => Addr::unchecked("addr4321".to_string()),
+                3 => Addr::unchecked("addr5432".to_string()),
+                4 => Addr::unchecked("addr6543".to_string()),
+                _ => panic!("Unsupported address index"),
+            }
+        }
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn can_query_balance_of_existing_address() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
+            let res = instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let query_msg = QueryMsg::Balance {
+                address: address(1).to_string(),
+            };
+            let query_result = query(deps.as_ref(), env, query_msg).unwrap();
+            assert_eq!(query_result.as_slice(), b"{\"balance\":\"0\"}");
+        }
+
+        #[test]
+        fn can_query_balance_of_nonexisting_address() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
+            let res = instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let query_msg = QueryMsg::Balance {
+                address: address(4).to_string(), // only indices 1, 2, 3 are instantiateialized
+            };
+            let query_result = query(deps.as_ref(), env, query_msg).unwrap();
+            assert_eq!(query_result.as_slice(), b"{\"balance\":\"0\"}");
+        }
+
+        #[test]
+        fn can_query_allowance_of_existing_addresses() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
+            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+            let owner = address(2);
+            let spender = address(1);
+            let approve_msg = ExecuteMsg::Approve {
+                spender: spender.clone().to_string(),
+                amount: Uint128::from(42u128),
+            };
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
+            let action_result = execute(deps.as_mut(), env.clone(), info, approve_msg).unwrap();
+            assert_eq!(action_result.messages.len(), 0);
+            assert_eq!(action_result.events.len(), 1);
+            assert_eq!(action_result.events[0].ty, "approve");
+            assert_eq!(
+                action_result.events[0].attributes,
+                vec![
+                    attr("sender", owner.clone().to_string()),
+                    attr("owner", owner.clone().to_string()),
+                    attr("spender", spender.clone().to_string()),
+                    attr("amount", "42"),
+                ]
+            );
+            let query_msg = QueryMsg::Allowance {
+                owner: owner.clone().to_string(),
+                spender: spender.clone().to_string(),
+            };
+            let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+            assert_eq!(query_result.as_slice(), b"{\"allowance\":\"42\"}");
+        }
+
+        #[test]
+        fn can_query_allowance_of_nonexisting_owner() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
             let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
             assert_eq!(0, res.messages.len());
-            assert_eq!(get_total_supply(&deps.storage), 0);
+            let owner = address(2);
+            let spender = address(1);
+            let bob = address(3);
+            let approve_msg = ExecuteMsg::Approve {
+                spender: spender.clone().to_string(),
+                amount: Uint128::from(42u128),
+            };
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
+            let approve_result = execute(deps.as_mut(), env.clone(), info, approve_msg).unwrap();
+            assert_eq!(approve_result.messages.len(), 0);
+            assert_eq!(approve_result.events.len(), 1);
+            assert_eq!(approve_result.events[0].ty, "approve");
+            assert_eq!(
+                approve_result.events[0].attributes,
+                vec![
+                    attr("sender", owner.clone().to_string()),
+                    attr("owner", owner.clone().to_string()),
+                    attr("spender", spender.clone().to_string()),
+                    attr("amount", "42"),
+                ]
+            );
+            // different spender
+            let query_msg = QueryMsg::Allowance {
+                owner: owner.clone().to_string(),
+                spender: bob.clone().to_string(),
+            };
+            let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+            assert_eq!(query_result.as_slice(), b"{\"allowance\":\"0\"}");
+            // differnet owner
+            let query_msg = QueryMsg::Allowance {
+                owner: bob.clone().to_string(),
+                spender: spender.clone().to_string(),
+            };
+            let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+            assert_eq!(query_result.as_slice(), b"{\"allowance\":\"0\"}");
+        }
+    }
+    
+    // mod bridge {
+    //     use super::*;
+    //     use cosmwasm_std::{attr, Addr};
+
+    //     fn address(index: u8) -> Addr {
+    //         match index {
+    //             0 => Addr::unchecked("addr0000".to_string()), // contract instantiateializer
+    //             1 => Addr::unchecked("addr1111".to_string()),
+    //             2 => Addr::unchecked("addr4321".to_string()),
+    //             3 => Addr::unchecked("addr5432".to_string()),
+    //             4 => Addr::unchecked("addr6543".to_string()),
+    //             _ => panic!("Unsupported address index"),
+    //         }
+    //     }
+
+    //     fn make_instantiate_msg() -> InstantiateMsg {
+    //         InstantiateMsg {
+    //             name: "Cash Token".to_string(),
+    //             symbol: "CASH".to_string(),
+    //             decimals: 9,
+    //             evm_contract: "0xcd38B80aee05cad65571B7564BD110fdf2990de6".to_string(),
+    //         }
+    //     }
+
+    //     #[test]
+    //     fn can_send_to_wasm() {
+    //         let mut deps = mock_dependencies(&[]);
+    //         let instantiate_msg = make_instantiate_msg();
+    //         let (env, info) = mock_env_height("0xcd38B80aee05cad65571B7564BD110fdf2990de6", 450, 550);
+    //         let res = instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+    //         assert_eq!(0, res.messages.len());
+
+    //         let mint_cw20_msg = ExecuteMsg::MintCW20 { recipient: "0xcd38B80aee05cad65571B7564BD110fdf2990de6".to_string(), amount: (Uint128::from(100u128)) };
+
+    //         let (env, info) = mock_env_height("ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr", 450, 550);
+    //         let mint_cw20_result = execute(deps.as_mut(), env, info, mint_cw20_msg).unwrap();
+    //         assert_eq!(mint_cw20_result.messages.len(), 0);
+    //         assert_eq!(
+    //             mint_cw20_result.attributes,
+    //             vec![
+    //                 attr("action", "MINT"),
+    //                 attr("account", "addr1111"),
+    //                 attr("sender", "addr0000"),
+    //                 attr("amount","100"),
+    //             ]
+    //         );
+
+    //         assert_eq!(
+    //             get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+    //             100
+    //         );
+    //         assert_eq!(get_total_supply(&deps.storage), 100);
+    //     } 
+
+    //     #[test]
+    //     fn can_send_to_evm(){
+    //         let mut deps = mock_dependencies(&[]);
+    //         let instantiate_msg = make_instantiate_msg();
+    //         let (env, info) = mock_env_height(&address(0).as_str(), 450, 550);
+    //         let res = instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+    //         assert_eq!(0, res.messages.len());
+
+    //         let mint_cw20_msg = ExecuteMsg::MintCW20 { recipient: address(1).to_string(), amount: (Uint128::from(100u128)) };
+
+    //         let (env, info) = mock_env_height("addr0000", 450, 550);
+    //         execute(deps.as_mut(), env, info, mint_cw20_msg).unwrap();
+
+    //         let send_to_evm_msg = ExecuteMsg::SendToEvm {recipient: address(1).to_string(), amount: (Uint128::from(100u128)) };
+
+    //         let (env, info) = mock_env_height("addr1111", 450, 550);
+    //         let send_to_evm_result = execute(deps.as_mut(), env, info, send_to_evm_msg).unwrap();
+
+    //         print!("这是一个打印结果{:?}",send_to_evm_result);
+    //         assert_eq!(send_to_evm_result.messages.len(), 1);
+    //         assert_eq!(send_to_evm_result.attributes, 
+    //             vec![
+    //                 attr("action","call evm"),
+    //                 attr("amount","100")
+    //             ]);
+
+    //         assert_eq!(
+    //             get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+    //             0
+    //         );
+    //     }
+    // }
+
+
+    mod deploy_test {
+        use super::*;
+        use cosmwasm_std::{attr, Addr};
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn contract_can_same() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr", 450, 550);
+            let res = instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+
+            let mint_cw20_msg = ExecuteMsg::MintCW20 { recipient: "addr111".to_string(), amount: (Uint128::from(100u128)), event_id: None, evm_sender: None };
+            
+            let (env, info) = mock_env_height("ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr", 450, 550);
+            let mint_cw20_result = execute(deps.as_mut(), env, info, mint_cw20_msg).unwrap();
+            assert_eq!(mint_cw20_result.messages.len(), 0);
+            assert_eq!(mint_cw20_result.events.len(), 1);
+            assert_eq!(mint_cw20_result.events[0].ty, "bridge_in");
+            assert_eq!(
+                mint_cw20_result.events[0].attributes,
+                vec![
+                    attr("sender", "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr"),
+                    attr("to", "addr111"),
+                    attr("amount", "100"),
+                    attr("fee", "0"),
+                    attr("transfer_id", "0"),
+                ]
+            );
+
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr111".to_string())),
+                100
+            );
+            assert_eq!(get_total_supply(&deps.storage), 100);
+        } 
+    }
+
+    mod bridge_config {
+        use super::*;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn can_query_bridge_config() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+
+            let query_result = query(deps.as_ref(), env, QueryMsg::BridgeConfig {}).unwrap();
+            let config: BridgeConfigResponse = from_slice(&query_result).unwrap();
+            assert_eq!(
+                config,
+                BridgeConfigResponse {
+                    evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                    module_caller: "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr".to_string(),
+                    owner: Some("creator".to_string()),
+                    inbound_enabled: true,
+                    outbound_enabled: true,
+                    cap: None,
+                    allowlist_enabled: false,
+                    history_limit: 0,
+                    reserve_auto_pause: false,
+                    timelock_threshold: Uint128::zero(),
+                    timelock_delay: 0,
+                    optimistic_threshold: Uint128::zero(),
+                    challenge_period: 0,
+                    sender_cancel_enabled: false,
+                    claim_by_sender: false,
+                    fee_schedule: FeeSchedule::default(),
+                }
+            );
+        }
+
+        #[test]
+        fn defaults_to_both_directions_when_flags_missing() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+            PrefixedStorage::new(&mut deps.storage, PREFIX_CONFIG).remove(KEY_DIRECTIONS);
+
+            let query_result = query(deps.as_ref(), env, QueryMsg::BridgeConfig {}).unwrap();
+            let config: BridgeConfigResponse = from_slice(&query_result).unwrap();
+            assert!(config.inbound_enabled);
+            assert!(config.outbound_enabled);
+        }
+    }
+
+    mod update_config {
+        use super::*;
+        use crate::error::ContractError;
+        use cosmwasm_std::attr;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            }
+        }
+
+        fn make_update_msg() -> ExecuteMsg {
+            ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
+            }
+        }
+
+        #[test]
+        fn owner_can_update_config() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: Some("0x1111111111111111111111111111111111111111".to_string()),
+                name: None,
+                symbol: Some("CSH".to_string()),
+                inbound_enabled: None,
+                outbound_enabled: Some(false),
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let res = execute(deps.as_mut(), env, info, update_msg).unwrap();
+            assert_eq!(res.events.len(), 1);
+            assert_eq!(res.events[0].ty, "update_config");
+            assert_eq!(
+                res.events[0].attributes,
+                vec![
+                    attr("sender", "creator"),
+                    attr("old_evm_contract", "0xcd38b80aee05cad65571b7564bd110fdf2990de6"),
+                    attr("new_evm_contract", "0x1111111111111111111111111111111111111111"),
+                    attr("old_symbol", "CASH"),
+                    attr("new_symbol", "CSH"),
+                    attr("old_outbound_enabled", "true"),
+                    attr("new_outbound_enabled", "false"),
+                ]
+            );
+
+            let constants = get_constants(&deps.storage);
+            assert_eq!(constants.contract, "0x1111111111111111111111111111111111111111");
+            assert_eq!(constants.name, "Cash Token");
+            assert_eq!(constants.symbol, "CSH");
+
+            let send_msg = ExecuteMsg::SendToEvm {
+                recipient: "0x2222222222222222222222222222222222222222".to_string(),
+                amount: Uint128::from(0u128),
+                deadline: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            match execute(deps.as_mut(), env, info, send_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::BridgeDirectionDisabled { direction }) => {
+                    assert_eq!(direction, "outbound")
+                }
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_non_owner() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            match execute(deps.as_mut(), env, info, make_update_msg()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_invalid_values() {
+            let mut deps = mock_dependencies(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: Some("abc".to_string()),
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            match execute(deps.as_mut(), env, info, update_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::ContractERC20Err { address }) => assert_eq!(address, "abc"),
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: Some("cash".to_string()),
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            match execute(deps.as_mut(), env, info, update_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::TickerWrongSymbolFormat {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_constants(&deps.storage).symbol, "CASH");
+        }
+    }
+    mod migrate {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::MigrateMsg;
+        use cosmwasm_std::testing::MockStorage;
+
+        fn setup_cw20_base(storage: &mut MockStorage) {
+            storage.set(
+                b"contract_info",
+                br#"{"contract":"crates.io:cw20-base","version":"0.9.1"}"#,
+            );
+            storage.set(
+                b"token_info",
+                br#"{"name":"Cash Token","symbol":"CASH","decimals":9,"total_supply":"300","mint":{"minter":"minter","cap":"1000"}}"#,
+            );
+            storage.set(b"marketing_info", br#"{"project":null,"description":null,"marketing":null,"logo":null}"#);
+            let mut balances = PrefixedStorage::new(storage, b"balance");
+            balances.set(b"addr0000", br#""100""#);
+            balances.set(b"addr1111", br#""200""#);
+            let mut allowances = PrefixedStorage::multilevel(storage, &[b"allowance", b"addr0000"]);
+            allowances.set(b"spender1", br#"{"allowance":"5","expires":{"never":{}}}"#);
+            allowances.set(b"spender2", br#"{"allowance":"6","expires":{"at_height":100}}"#);
+            allowances.set(b"spender3", br#"{"allowance":"7","expires":{"at_height":1000}}"#);
+            let mut spenders =
+                PrefixedStorage::multilevel(storage, &[b"allowance_spender", b"spender1"]);
+            spenders.set(b"addr0000", br#"{"allowance":"5","expires":{"never":{}}}"#);
+        }
+
+        fn make_migrate_msg() -> MigrateMsg {
+            MigrateMsg::FromCw20Base {
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                owner: None,
+                bridge_callers: vec!["relayer".to_string()],
+            }
+        }
+
+        #[test]
+        fn adopts_cw20_base_state() {
+            let mut deps = mock_dependencies(&[]);
+            setup_cw20_base(&mut deps.storage);
+
+            let (env, _) = mock_env_height("creator", 450, 550);
+            let res = migrate(deps.as_mut(), env, make_migrate_msg()).unwrap();
+            assert_eq!(0, res.messages.len());
+
+            assert_eq!(
+                get_constants(&deps.storage),
+                Constants {
+                    name: "Cash Token".to_string(),
+                    symbol: "CASH".to_string(),
+                    decimals: 9,
+                    contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                }
+            );
+            assert_eq!(get_total_supply(&deps.storage), 300);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 100);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 200);
+            let owner = Addr::unchecked("addr0000");
+            assert_eq!(get_allowance(&deps.storage, &owner, &Addr::unchecked("spender1")), 5);
+            assert_eq!(get_allowance(&deps.storage, &owner, &Addr::unchecked("spender2")), 0);
+            assert_eq!(get_allowance(&deps.storage, &owner, &Addr::unchecked("spender3")), 7);
+            assert_eq!(read_owner(&deps.storage).unwrap(), Some(Addr::unchecked("minter")));
+            assert_eq!(read_cap(&deps.storage).unwrap(), Some(1000));
+            assert!(has_role(&deps.storage, Role::BridgeCaller, &Addr::unchecked("relayer")));
+
+            // The cw20-base layout is gone
+            assert!(deps.storage.get(b"token_info").is_none());
+            assert!(deps.storage.get(b"marketing_info").is_none());
+            assert!(cw20_base::read_balances(&deps.storage).unwrap().is_empty());
+            assert!(cw20_base::read_allowances(&deps.storage).unwrap().is_empty());
+            assert!(cw20_base::read_keys(&deps.storage, b"allowance_spender").is_empty());
+
+            // Holders can keep using their tokens
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(40u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 60);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 240);
+        }
+
+        #[test]
+        fn fails_when_migrating_twice() {
+            let mut deps = mock_dependencies(&[]);
+            setup_cw20_base(&mut deps.storage);
+
+            let (env, _) = mock_env_height("creator", 450, 550);
+            migrate(deps.as_mut(), env.clone(), make_migrate_msg()).unwrap();
+            match migrate(deps.as_mut(), env, make_migrate_msg()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::UnsupportedMigration { contract }) => {
+                    assert_eq!(contract, CONTRACT_NAME)
+                }
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_without_an_owner() {
+            let mut deps = mock_dependencies(&[]);
+            setup_cw20_base(&mut deps.storage);
+            deps.storage.set(
+                b"token_info",
+                br#"{"name":"Cash Token","symbol":"CASH","decimals":9,"total_supply":"300","mint":null}"#,
+            );
+
+            let (env, _) = mock_env_height("creator", 450, 550);
+            match migrate(deps.as_mut(), env.clone(), make_migrate_msg()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MigrationOwnerRequired {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            let migrate_msg = MigrateMsg::FromCw20Base {
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                owner: Some("creator".to_string()),
+                bridge_callers: vec![],
+            };
+            migrate(deps.as_mut(), env, migrate_msg).unwrap();
+            assert_eq!(read_owner(&deps.storage).unwrap(), Some(Addr::unchecked("creator")));
+        }
+
+        #[test]
+        fn fails_for_invalid_symbol() {
+            let mut deps = mock_dependencies(&[]);
+            setup_cw20_base(&mut deps.storage);
+            deps.storage.set(
+                b"token_info",
+                br#"{"name":"Cash Token","symbol":"cash","decimals":9,"total_supply":"300","mint":{"minter":"minter","cap":null}}"#,
+            );
+
+            let (env, _) = mock_env_height("creator", 450, 550);
+            match migrate(deps.as_mut(), env, make_migrate_msg()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::TickerWrongSymbolFormat {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_invalid_name() {
+            let mut deps = mock_dependencies(&[]);
+            setup_cw20_base(&mut deps.storage);
+            deps.storage.set(
+                b"token_info",
+                br#"{"name":"CT","symbol":"CASH","decimals":9,"total_supply":"300","mint":{"minter":"minter","cap":null}}"#,
+            );
+
+            let (env, _) = mock_env_height("creator", 450, 550);
+            match migrate(deps.as_mut(), env, make_migrate_msg()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::NameWrongFormat {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn can_upgrade_after_adopting_cw20_base() {
+            let mut deps = mock_dependencies(&[]);
+            setup_cw20_base(&mut deps.storage);
+
+            let (env, _) = mock_env_height("creator", 450, 550);
+            migrate(deps.as_mut(), env.clone(), make_migrate_msg()).unwrap();
+            migrate(deps.as_mut(), env.clone(), MigrateMsg::Upgrade {}).unwrap();
+            migrate(deps.as_mut(), env, MigrateMsg::Upgrade {}).unwrap();
+            assert_eq!(
+                cw20_base::read_contract_version(&deps.storage).unwrap(),
+                Some(cw20_base::ContractVersion {
+                    contract: CONTRACT_NAME.to_string(),
+                    version: CONTRACT_VERSION.to_string(),
+                })
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 200);
+        }
+
+        #[test]
+        fn can_upgrade_instantiated_contract() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+            migrate(deps.as_mut(), env, MigrateMsg::Upgrade {}).unwrap();
+            assert_eq!(get_constants(&deps.storage).symbol, "CASH");
+        }
+
+        #[test]
+        fn upgrade_fails_for_cw20_base() {
+            let mut deps = mock_dependencies(&[]);
+            setup_cw20_base(&mut deps.storage);
+
+            let (env, _) = mock_env_height("creator", 450, 550);
+            match migrate(deps.as_mut(), env, MigrateMsg::Upgrade {}) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::UnsupportedMigration { contract }) => {
+                    assert_eq!(contract, "crates.io:cw20-base")
+                }
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_other_contracts() {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+            match migrate(deps.as_mut(), env, make_migrate_msg()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::UnsupportedMigration { contract }) => assert_eq!(contract, ""),
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+    mod freeze {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::FrozenAccountsResponse;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(100u128),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(100u128),
+                    },
+                ],
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let grant_msg = ExecuteMsg::GrantRole {
+                role: Role::Compliance,
+                address: "officer".to_string(),
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, grant_msg).unwrap();
+            deps
+        }
+
+        fn assert_frozen<T: std::fmt::Debug>(result: Result<T, ContractError>, frozen: &str) {
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::AccountFrozen { address }) => assert_eq!(address, frozen),
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn frozen_account_cannot_move_funds() {
+            let mut deps = setup();
+            let freeze_msg = ExecuteMsg::FreezeAccount {
+                address: "addr0000".to_string(),
+            };
+            let (env, info) = mock_env_height("officer", 450, 550);
+            execute(deps.as_mut(), env, info, freeze_msg).unwrap();
+
+            // Send
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(1u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            assert_frozen(execute(deps.as_mut(), env, info, transfer_msg), "addr0000");
+
+            // Receive
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(1u128),
+            };
+            let (env, info) = mock_env_height("addr1111", 450, 550);
+            assert_frozen(execute(deps.as_mut(), env, info, transfer_msg), "addr0000");
+
+            // Bridge out
+            let send_msg = ExecuteMsg::SendToEvm {
+                recipient: "0x2222222222222222222222222222222222222222".to_string(),
+                amount: Uint128::from(1u128),
+                deadline: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            assert_frozen(execute(deps.as_mut(), env, info, send_msg), "addr0000");
+
+            // Minted to
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(1u128),
+                event_id: None,
+                evm_sender: None,
+            };
+            let (env, info) =
+                mock_env_height("ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr", 450, 550);
+            assert_frozen(execute(deps.as_mut(), env, info, mint_msg), "addr0000");
+
+            // Burn
+            let burn_msg = ExecuteMsg::Burn {
+                amount: Uint128::from(1u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            assert_frozen(execute(deps.as_mut(), env, info, burn_msg), "addr0000");
+
+            // Approve
+            let approve_msg = ExecuteMsg::Approve {
+                spender: "addr1111".to_string(),
+                amount: Uint128::from(1u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            assert_frozen(execute(deps.as_mut(), env, info, approve_msg), "addr0000");
+
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 100);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 100);
+            assert_eq!(get_total_supply(&deps.storage), 200);
+
+            // Unfreezing restores access
+            let unfreeze_msg = ExecuteMsg::UnfreezeAccount {
+                address: "addr0000".to_string(),
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, unfreeze_msg).unwrap();
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(1u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 101);
+        }
+
+        #[test]
+        fn frozen_spender_cannot_use_allowance() {
+            let mut deps = setup();
+            let approve_msg = ExecuteMsg::Approve {
+                spender: "spender".to_string(),
+                amount: Uint128::from(10u128),
+            };
+            let (env, info) = mock_env_height("addr1111", 450, 550);
+            execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            let freeze_msg = ExecuteMsg::FreezeAccount {
+                address: "spender".to_string(),
+            };
+            let (env, info) = mock_env_height("officer", 450, 550);
+            execute(deps.as_mut(), env, info, freeze_msg).unwrap();
+
+            let transfer_from_msg = ExecuteMsg::TransferFrom {
+                owner: "addr1111".to_string(),
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(5u128),
+            };
+            let (env, info) = mock_env_height("spender", 450, 550);
+            assert_frozen(execute(deps.as_mut(), env, info, transfer_from_msg), "spender");
+            assert_eq!(
+                get_allowance(&deps.storage, &Addr::unchecked("addr1111"), &Addr::unchecked("spender")),
+                10
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 100);
+        }
+
+        #[test]
+        fn can_query_frozen_accounts() {
+            let mut deps = setup();
+            for address in ["addr3333", "addr1111", "addr2222"].iter() {
+                let freeze_msg = ExecuteMsg::FreezeAccount {
+                    address: address.to_string(),
+                };
+                let (env, info) = mock_env_height("officer", 450, 550);
+                execute(deps.as_mut(), env, info, freeze_msg).unwrap();
+            }
+
+            let query_msg = QueryMsg::FrozenAccounts {
+                start_after: None,
+                limit: Some(2),
+            };
+            let query_result = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+            let page: FrozenAccountsResponse = from_slice(&query_result).unwrap();
+            assert_eq!(page.accounts, vec!["addr1111", "addr2222"]);
+
+            let query_msg = QueryMsg::FrozenAccounts {
+                start_after: Some("addr2222".to_string()),
+                limit: None,
+            };
+            let query_result = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+            let page: FrozenAccountsResponse = from_slice(&query_result).unwrap();
+            assert_eq!(page.accounts, vec!["addr3333"]);
+        }
+
+        #[test]
+        fn only_owner_or_compliance_can_freeze() {
+            let mut deps = setup();
+            let freeze_msg = ExecuteMsg::FreezeAccount {
+                address: "addr1111".to_string(),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            match execute(deps.as_mut(), env, info, freeze_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            // Revoked officers lose access
+            let revoke_msg = ExecuteMsg::RevokeRole {
+                role: Role::Compliance,
+                address: "officer".to_string(),
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, revoke_msg).unwrap();
+            let freeze_msg = ExecuteMsg::FreezeAccount {
+                address: "addr1111".to_string(),
+            };
+            let (env, info) = mock_env_height("officer", 450, 550);
+            match execute(deps.as_mut(), env, info, freeze_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+    mod bridge_allowlist {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::BridgeAllowlistResponse;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "partner".to_string(),
+                        amount: Uint128::from(100u128),
+                    },
+                    InitialBalance {
+                        address: "outsider".to_string(),
+                        amount: Uint128::from(100u128),
+                    },
+                ],
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let update_msg = ExecuteMsg::UpdateBridgeAllowlist {
+                add: vec!["partner".to_string()],
+                remove: vec![],
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+            deps
+        }
+
+        fn send_to_evm_msg() -> ExecuteMsg {
+            ExecuteMsg::SendToEvm {
+                recipient: "0x2222222222222222222222222222222222222222".to_string(),
+                amount: Uint128::from(1u128),
+                deadline: None,
+            }
+        }
+
+        fn mint_msg(recipient: &str) -> ExecuteMsg {
+            ExecuteMsg::MintCW20 {
+                recipient: recipient.to_string(),
+                amount: Uint128::from(1u128),
+                event_id: None,
+                evm_sender: None,
+            }
+        }
+
+        #[test]
+        fn list_is_ignored_while_mode_is_off() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height("outsider", 450, 550);
+            execute(deps.as_mut(), env, info, send_to_evm_msg()).unwrap();
+            let (env, info) = mock_env_height(MODULE_CALLER, 450, 550);
+            execute(deps.as_mut(), env, info, mint_msg("outsider")).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("outsider")), 100);
+        }
+
+        #[test]
+        fn only_allowlisted_accounts_bridge_while_mode_is_on() {
+            let mut deps = setup();
+            let mode_msg = ExecuteMsg::SetBridgeAllowlistMode { enabled: true };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, mode_msg).unwrap();
+
+            let (env, info) = mock_env_height("partner", 450, 550);
+            execute(deps.as_mut(), env, info, send_to_evm_msg()).unwrap();
+            let (env, info) = mock_env_height(MODULE_CALLER, 450, 550);
+            execute(deps.as_mut(), env, info, mint_msg("partner")).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("partner")), 100);
+
+            let (env, info) = mock_env_height("outsider", 450, 550);
+            match execute(deps.as_mut(), env, info, send_to_evm_msg()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::NotAllowlisted { address }) => assert_eq!(address, "outsider"),
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            let (env, info) = mock_env_height(MODULE_CALLER, 450, 550);
+            match execute(deps.as_mut(), env, info, mint_msg("outsider")) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::NotAllowlisted { address }) => assert_eq!(address, "outsider"),
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            // Plain transfers are not affected
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "partner".to_string(),
+                amount: Uint128::from(1u128),
+            };
+            let (env, info) = mock_env_height("outsider", 450, 550);
+            execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+        }
+
+        #[test]
+        fn can_query_allowlist() {
+            let mut deps = setup();
+            let mode_msg = ExecuteMsg::SetBridgeAllowlistMode { enabled: true };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, mode_msg).unwrap();
+            let update_msg = ExecuteMsg::UpdateBridgeAllowlist {
+                add: vec!["addr1111".to_string()],
+                remove: vec!["partner".to_string()],
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+
+            let query_msg = QueryMsg::BridgeAllowlist {
+                start_after: None,
+                limit: None,
+            };
+            let query_result = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+            let allowlist: BridgeAllowlistResponse = from_slice(&query_result).unwrap();
+            assert_eq!(
+                allowlist,
+                BridgeAllowlistResponse {
+                    enabled: true,
+                    addresses: vec!["addr1111".to_string()],
+                }
+            );
+        }
+
+        #[test]
+        fn only_owner_manages_allowlist() {
+            let mut deps = setup();
+            let mode_msg = ExecuteMsg::SetBridgeAllowlistMode { enabled: true };
+            let (env, info) = mock_env_height("partner", 450, 550);
+            match execute(deps.as_mut(), env, info, mode_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            let update_msg = ExecuteMsg::UpdateBridgeAllowlist {
+                add: vec!["outsider".to_string()],
+                remove: vec![],
+            };
+            let (env, info) = mock_env_height("partner", 450, 550);
+            match execute(deps.as_mut(), env, info, update_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+    mod history {
+        use super::*;
+        use crate::msg::HistoryResponse;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+
+        fn setup(history_limit: u32) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: Some(history_limit),
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+            deps
+        }
+
+        fn get_history(
+            deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+            address: &str,
+            start_after: Option<u64>,
+            limit: Option<u32>,
+        ) -> Vec<HistoryEntry> {
+            let query_msg = QueryMsg::History {
+                address: address.to_string(),
+                start_after,
+                limit,
+            };
+            let query_result = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+            let history: HistoryResponse = from_slice(&query_result).unwrap();
+            history.entries
+        }
+
+        #[test]
+        fn records_every_kind() {
+            let mut deps = setup(10);
+
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(100u128),
+                event_id: None,
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps.as_mut(), env, info, mint_msg).unwrap();
+
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(30u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 452, 570);
+            execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+
+            let send_msg = ExecuteMsg::SendToEvm {
+                recipient: "0x2222222222222222222222222222222222222222".to_string(),
+                amount: Uint128::from(20u128),
+                deadline: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 453, 580);
+            execute(deps.as_mut(), env, info, send_msg).unwrap();
+
+            let burn_msg = ExecuteMsg::Burn {
+                amount: Uint128::from(10u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 454, 590);
+            execute(deps.as_mut(), env, info, burn_msg).unwrap();
+
+            let entries = get_history(&deps, "addr0000", None, None);
+            assert_eq!(
+                entries,
+                vec![
+                    HistoryEntry {
+                        id: 3,
+                        kind: HistoryKind::Burn,
+                        counterparty: None,
+                        amount: Uint128::from(10u128),
+                        height: 454,
+                        time: Timestamp::from_seconds(590),
+                    },
+                    HistoryEntry {
+                        id: 2,
+                        kind: HistoryKind::BridgeOut,
+                        counterparty: Some("0x2222222222222222222222222222222222222222".to_string()),
+                        amount: Uint128::from(20u128),
+                        height: 453,
+                        time: Timestamp::from_seconds(580),
+                    },
+                    HistoryEntry {
+                        id: 1,
+                        kind: HistoryKind::Send,
+                        counterparty: Some("addr1111".to_string()),
+                        amount: Uint128::from(30u128),
+                        height: 452,
+                        time: Timestamp::from_seconds(570),
+                    },
+                    HistoryEntry {
+                        id: 0,
+                        kind: HistoryKind::BridgeIn,
+                        counterparty: None,
+                        amount: Uint128::from(100u128),
+                        height: 451,
+                        time: Timestamp::from_seconds(560),
+                    },
+                ]
+            );
+
+            let entries = get_history(&deps, "addr1111", None, None);
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].kind, HistoryKind::Receive);
+            assert_eq!(entries[0].counterparty, Some("addr0000".to_string()));
+        }
+
+        #[test]
+        fn pages_and_caps_history() {
+            let mut deps = setup(3);
+            for amount in 1..=5u128 {
+                let mint_msg = ExecuteMsg::MintCW20 {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(amount),
+                    event_id: None,
+                    evm_sender: None,
+                };
+                let (env, info) = mock_env_height(MODULE_CALLER, 450, 550);
+                execute(deps.as_mut(), env, info, mint_msg).unwrap();
+            }
+
+            let ids: Vec<u64> = get_history(&deps, "addr0000", None, None)
+                .iter()
+                .map(|entry| entry.id)
+                .collect();
+            assert_eq!(ids, vec![4, 3, 2]);
+
+            let page = get_history(&deps, "addr0000", None, Some(2));
+            assert_eq!(page.iter().map(|entry| entry.id).collect::<Vec<u64>>(), vec![4, 3]);
+            let page = get_history(&deps, "addr0000", Some(3), Some(2));
+            assert_eq!(page.iter().map(|entry| entry.id).collect::<Vec<u64>>(), vec![2]);
+        }
+
+        #[test]
+        fn is_disabled_by_default() {
+            let mut deps = setup(0);
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(1u128),
+                event_id: None,
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 450, 550);
+            execute(deps.as_mut(), env, info, mint_msg).unwrap();
+            assert!(get_history(&deps, "addr0000", None, None).is_empty());
+        }
+    }
+
+    mod bridge_transfers {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::BridgeTransfersResponse;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+        const EVM_RECIPIENT: &str = "0x1111111111111111111111111111111111111111";
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            deps
+        }
+
+        fn mint(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, recipient: &str, amount: u128) {
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: recipient.to_string(),
+                amount: Uint128::from(amount),
+                event_id: None,
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps.as_mut(), env, info, mint_msg).unwrap();
+        }
+
+        fn send(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, sender: &str, amount: u128) {
+            let send_msg = ExecuteMsg::SendToEvm {
+                recipient: EVM_RECIPIENT.to_string(),
+                amount: Uint128::from(amount),
+                deadline: None,
+            };
+            let (env, info) = mock_env_height(sender, 452, 570);
+            execute(deps.as_mut(), env, info, send_msg).unwrap();
+        }
+
+        fn get_transfer(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, id: u64) -> BridgeTransfer {
+            let query_result = query(deps.as_ref(), mock_env(), QueryMsg::BridgeTransfer { id }).unwrap();
+            from_slice(&query_result).unwrap()
+        }
+
+        fn get_pending(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Vec<u64> {
+            let query_msg = QueryMsg::PendingOutbound {
+                start_after: None,
+                limit: None,
+            };
+            let query_result = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+            let response: BridgeTransfersResponse = from_slice(&query_result).unwrap();
+            response.transfers.iter().map(|transfer| transfer.id).collect()
+        }
+
+        #[test]
+        fn records_both_directions() {
+            let mut deps = setup();
+            mint(&mut deps, "addr0000", 100);
+            send(&mut deps, "addr0000", 40);
+
+            let inbound = get_transfer(&deps, 0);
+            assert_eq!(inbound.direction, BridgeDirection::In);
+            assert_eq!(inbound.account, "addr0000");
+            assert_eq!(inbound.evm_address, None);
+            assert_eq!(inbound.amount, Uint128::from(100u128));
+            assert_eq!(inbound.status, BridgeTransferStatus::Completed);
+            assert_eq!(inbound.height, 451);
+
+            let outbound = get_transfer(&deps, 1);
+            assert_eq!(outbound.direction, BridgeDirection::Out);
+            assert_eq!(outbound.account, "addr0000");
+            assert_eq!(outbound.evm_address, Some(EVM_RECIPIENT.to_string()));
+            assert_eq!(outbound.amount, Uint128::from(40u128));
+            assert_eq!(outbound.status, BridgeTransferStatus::Pending);
+            assert_eq!(outbound.time, Timestamp::from_seconds(570));
+
+            assert!(query(deps.as_ref(), mock_env(), QueryMsg::BridgeTransfer { id: 2 }).is_err());
+        }
+
+        #[test]
+        fn can_list_by_account_and_direction() {
+            let mut deps = setup();
+            mint(&mut deps, "addr0000", 100);
+            mint(&mut deps, "addr1111", 100);
+            send(&mut deps, "addr0000", 10);
+            send(&mut deps, "addr0000", 20);
+
+            let list = |direction: Option<BridgeDirection>, start_after: Option<u64>, limit: Option<u32>| {
+                let query_msg = QueryMsg::BridgeTransfersByAccount {
+                    address: "addr0000".to_string(),
+                    direction,
+                    start_after,
+                    limit,
+                };
+                let query_result = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+                let response: BridgeTransfersResponse = from_slice(&query_result).unwrap();
+                response.transfers.iter().map(|transfer| transfer.id).collect::<Vec<u64>>()
+            };
+            assert_eq!(list(None, None, None), vec![0, 2, 3]);
+            assert_eq!(list(Some(BridgeDirection::In), None, None), vec![0]);
+            assert_eq!(list(Some(BridgeDirection::Out), None, None), vec![2, 3]);
+            assert_eq!(list(None, Some(0), Some(1)), vec![2]);
+            assert_eq!(list(Some(BridgeDirection::Out), Some(2), None), vec![3]);
+            // Nothing comes after the largest id
+            assert_eq!(list(None, Some(u64::MAX), None), Vec::<u64>::new());
+
+            let query_msg = QueryMsg::PendingOutbound {
+                start_after: Some(u64::MAX),
+                limit: None,
+            };
+            let query_result = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+            let response: BridgeTransfersResponse = from_slice(&query_result).unwrap();
+            assert!(response.transfers.is_empty());
+        }
+
+        #[test]
+        fn bridge_caller_confirms_outbound() {
+            let mut deps = setup();
+            mint(&mut deps, "addr0000", 100);
+            send(&mut deps, "addr0000", 10);
+            send(&mut deps, "addr0000", 20);
+            assert_eq!(get_pending(&deps), vec![1, 2]);
+
+            let (env, info) = mock_env_height("addr0000", 453, 580);
+            match execute(deps.as_mut(), env, info, ExecuteMsg::ConfirmSendToEvm { id: 1 }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidSender { .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            let (env, info) = mock_env_height(MODULE_CALLER, 453, 580);
+            execute(deps.as_mut(), env, info, ExecuteMsg::ConfirmSendToEvm { id: 1 }).unwrap();
+            assert_eq!(get_transfer(&deps, 1).status, BridgeTransferStatus::Completed);
+            assert_eq!(get_pending(&deps), vec![2]);
+
+            // Neither a completed nor an inbound transfer can be confirmed
+            for id in [0u64, 1] {
+                let (env, info) = mock_env_height(MODULE_CALLER, 453, 580);
+                match execute(deps.as_mut(), env, info, ExecuteMsg::ConfirmSendToEvm { id }) {
+                    Ok(_) => panic!("expected error"),
+                    Err(ContractError::BridgeTransferNotPending { id: failed }) => assert_eq!(failed, id),
+                    Err(e) => panic!("unexpected error: {:?}", e),
+                }
+            }
+        }
+    }
+
+    mod bridge_stats {
+        use super::*;
+        use crate::msg::BridgeStatsResponse;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+        const DAY: u64 = 86_400;
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            deps
+        }
+
+        fn get_stats(
+            deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+            from_day: Option<u64>,
+            to_day: Option<u64>,
+        ) -> BridgeStatsResponse {
+            let query_msg = QueryMsg::BridgeStats { from_day, to_day };
+            let query_result = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+            from_slice(&query_result).unwrap()
+        }
+
+        #[test]
+        fn tracks_totals_and_daily_buckets() {
+            let mut deps = setup();
+            assert_eq!(get_stats(&deps, None, None).totals, BridgeVolume::default());
+
+            // Two mints on day 1, a send to EVM on day 3
+            for (amount, time) in [(100u128, DAY + 10), (50, 2 * DAY - 1)] {
+                let mint_msg = ExecuteMsg::MintCW20 {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(amount),
+                    event_id: None,
+                    evm_sender: None,
+                };
+                let (env, info) = mock_env_height(MODULE_CALLER, 451, time);
+                execute(deps.as_mut(), env, info, mint_msg).unwrap();
+            }
+            let send_msg = ExecuteMsg::SendToEvm {
+                recipient: "0x1111111111111111111111111111111111111111".to_string(),
+                amount: Uint128::from(30u128),
+                deadline: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 452, 3 * DAY);
+            execute(deps.as_mut(), env, info, send_msg).unwrap();
+
+            let stats = get_stats(&deps, None, None);
+            assert_eq!(
+                stats.totals,
+                BridgeVolume {
+                    inbound_amount: Uint128::from(150u128),
+                    inbound_count: 2,
+                    outbound_amount: Uint128::from(30u128),
+                    outbound_count: 1,
+                }
+            );
+            let days: Vec<u64> = stats.days.iter().map(|bucket| bucket.day).collect();
+            assert_eq!(days, vec![1, 3]);
+            assert_eq!(stats.days[0].volume.inbound_amount, Uint128::from(150u128));
+            assert_eq!(stats.days[0].volume.outbound_count, 0);
+            assert_eq!(stats.days[1].volume.outbound_amount, Uint128::from(30u128));
+
+            // Bounds are inclusive on both ends
+            let stats = get_stats(&deps, Some(2), Some(3));
+            assert_eq!(stats.days.len(), 1);
+            assert_eq!(stats.days[0].day, 3);
+            assert_eq!(get_stats(&deps, None, Some(1)).days.len(), 1);
+            assert_eq!(get_stats(&deps, Some(4), None).days.len(), 0);
+            assert_eq!(get_stats(&deps, Some(2), Some(u64::MAX)).days.len(), 1);
+        }
+
+        #[test]
+        fn counts_only_settled_transfers() {
+            let mut deps = setup();
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(100u128),
+                event_id: None,
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, DAY);
+            execute(deps.as_mut(), env, info, mint_msg).unwrap();
+
+            // A mint sent back to the EVM sender is neither inbound nor outbound volume
+            let refund_msg = ExecuteMsg::MintCW20 {
+                recipient: "ab".to_string(),
+                amount: Uint128::from(25u128),
+                event_id: None,
+                evm_sender: Some("0x2222222222222222222222222222222222222222".to_string()),
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 452, DAY);
+            execute(deps.as_mut(), env, info, refund_msg).unwrap();
+
+            // A cancelled send is taken back out of the day it was made
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: Some(true),
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+            let send_msg = ExecuteMsg::SendToEvm {
+                recipient: "0x1111111111111111111111111111111111111111".to_string(),
+                amount: Uint128::from(30u128),
+                deadline: Some(DAY + 100),
+            };
+            let (env, info) = mock_env_height("addr0000", 453, DAY);
+            execute(deps.as_mut(), env, info, send_msg).unwrap();
+            assert_eq!(get_stats(&deps, None, None).totals.outbound_count, 1);
+            let (env, info) = mock_env_height("addr0000", 454, 3 * DAY);
+            execute(deps.as_mut(), env, info, ExecuteMsg::CancelSendToEvm { id: 2 }).unwrap();
+
+            let stats = get_stats(&deps, None, None);
+            assert_eq!(
+                stats.totals,
+                BridgeVolume {
+                    inbound_amount: Uint128::from(100u128),
+                    inbound_count: 1,
+                    outbound_amount: Uint128::zero(),
+                    outbound_count: 0,
+                }
+            );
+            assert_eq!(stats.days.len(), 1);
+            assert_eq!(stats.days[0].volume, stats.totals);
+        }
+    }
+
+    mod reserves {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::{ReserveDiscrepanciesResponse, ReserveStatusResponse};
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::{attr, OwnedDeps};
+
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+
+        // 100 minted to addr0000, of which 30 are sent to EVM and not yet confirmed
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let grant_msg = ExecuteMsg::GrantRole {
+                role: Role::ReserveReporter,
+                address: "reporter".to_string(),
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, grant_msg).unwrap();
+
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(100u128),
+                event_id: None,
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps.as_mut(), env, info, mint_msg).unwrap();
+            let send_msg = ExecuteMsg::SendToEvm {
+                recipient: "0x1111111111111111111111111111111111111111".to_string(),
+                amount: Uint128::from(30u128),
+                deadline: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 452, 570);
+            execute(deps.as_mut(), env, info, send_msg).unwrap();
+            deps
+        }
+
+        fn report(
+            deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+            reserves: u128,
+            evm_block: u64,
+        ) -> Result<Response<SendToEvmMsg>, ContractError> {
+            let report_msg = ExecuteMsg::ReportReserves {
+                reserves: Uint128::from(reserves),
+                evm_block,
+            };
+            let (env, info) = mock_env_height("reporter", 460, 600);
+            execute(deps.as_mut(), env, info, report_msg)
+        }
+
+        fn get_status(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> ReserveStatusResponse {
+            let query_result = query(deps.as_ref(), mock_env(), QueryMsg::ReserveStatus {}).unwrap();
+            from_slice(&query_result).unwrap()
+        }
+
+        fn get_discrepancies(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Vec<ReserveReport> {
+            let query_msg = QueryMsg::ReserveDiscrepancies {
+                start_after: None,
+                limit: None,
+            };
+            let query_result = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+            let response: ReserveDiscrepanciesResponse = from_slice(&query_result).unwrap();
+            response.reports
+        }
+
+        #[test]
+        fn counts_in_flight_outbound() {
+            let mut deps = setup();
+            let status = get_status(&deps);
+            assert_eq!(status.total_supply, Uint128::from(70u128));
+            assert_eq!(status.outbound_in_flight, Uint128::from(30u128));
+            assert_eq!(status.last_report, None);
+
+            // Matching reserves leave no discrepancy
+            report(&mut deps, 100, 1000).unwrap();
+            assert!(get_discrepancies(&deps).is_empty());
+            assert_eq!(get_status(&deps).last_report.unwrap().evm_block, 1000);
+
+            // Once confirmed, the transfer no longer counts as in flight
+            let (env, info) = mock_env_height(MODULE_CALLER, 461, 610);
+            execute(deps.as_mut(), env, info, ExecuteMsg::ConfirmSendToEvm { id: 1 }).unwrap();
+            assert_eq!(get_status(&deps).outbound_in_flight, Uint128::zero());
+            report(&mut deps, 70, 1001).unwrap();
+            assert!(get_discrepancies(&deps).is_empty());
+        }
+
+        #[test]
+        fn records_discrepancies() {
+            let mut deps = setup();
+            report(&mut deps, 90, 1000).unwrap();
+            report(&mut deps, 120, 1001).unwrap();
+
+            let reports = get_discrepancies(&deps);
+            assert_eq!(reports.len(), 2);
+            assert_eq!(reports[0].evm_block, 1000);
+            assert_eq!(reports[0].shortfall, Uint128::from(10u128));
+            assert_eq!(reports[0].surplus, Uint128::zero());
+            assert_eq!(reports[0].reporter, "reporter");
+            assert_eq!(reports[1].shortfall, Uint128::zero());
+            assert_eq!(reports[1].surplus, Uint128::from(20u128));
+
+            // Auto-pause is off by default
+            assert!(read_directions(&deps.storage).unwrap().inbound);
+        }
+
+        #[test]
+        fn rejects_stale_and_unauthorized_reports() {
+            let mut deps = setup();
+            report(&mut deps, 100, 1000).unwrap();
+            match report(&mut deps, 100, 1000) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::StaleReserveReport { last, reported }) => {
+                    assert_eq!(last, 1000);
+                    assert_eq!(reported, 1000);
+                }
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            let report_msg = ExecuteMsg::ReportReserves {
+                reserves: Uint128::from(100u128),
+                evm_block: 1001,
+            };
+            let (env, info) = mock_env_height("addr0000", 460, 600);
+            match execute(deps.as_mut(), env, info, report_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized { .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn shortfall_pauses_minting_when_enabled() {
+            let mut deps = setup();
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: Some(true),
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 455, 580);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+            assert!(get_status(&deps).auto_pause);
+
+            // A surplus does not pause
+            report(&mut deps, 101, 1000).unwrap();
+            assert!(read_directions(&deps.storage).unwrap().inbound);
+
+            let res = report(&mut deps, 99, 1001).unwrap();
+            assert!(res.events[0].attributes.contains(&attr("inbound_paused", "true")));
+            assert!(!read_directions(&deps.storage).unwrap().inbound);
+
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(1u128),
+                event_id: None,
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 462, 620);
+            match execute(deps.as_mut(), env, info, mint_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::BridgeDirectionDisabled { .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn counts_pending_mints() {
+            let mut deps = setup();
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: Some(Uint128::from(50u128)),
+                challenge_period: Some(10),
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 455, 580);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(60u128),
+                event_id: None,
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 456, 590);
+            execute(deps.as_mut(), env, info, mint_msg).unwrap();
+            let status = get_status(&deps);
+            assert_eq!(status.total_supply, Uint128::from(70u128));
+            assert_eq!(status.pending_inbound, Uint128::from(60u128));
+
+            // Reserves already locked for the pending mint are not a surplus
+            let res = report(&mut deps, 160, 1000).unwrap();
+            let data: ReportReservesResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.report.pending_inbound, Uint128::from(60u128));
+            assert!(data.report.surplus.is_zero());
+            assert!(get_discrepancies(&deps).is_empty());
+
+            // Challenged mints still count until resolved
+            let (env, info) = mock_env_height("creator", 457, 600);
+            execute(deps.as_mut(), env, info, ExecuteMsg::Challenge { id: 0 }).unwrap();
+            assert_eq!(get_status(&deps).pending_inbound, Uint128::from(60u128));
+            let resolve_msg = ExecuteMsg::ResolveMint { id: 0, approve: true };
+            let (env, info) = mock_env_height("creator", 458, 610);
+            execute(deps.as_mut(), env, info, resolve_msg).unwrap();
+            let status = get_status(&deps);
+            assert_eq!(status.total_supply, Uint128::from(130u128));
+            assert_eq!(status.pending_inbound, Uint128::zero());
+            report(&mut deps, 160, 1001).unwrap();
+            assert!(get_discrepancies(&deps).is_empty());
+        }
+    }
+
+    mod response_data {
+        use super::*;
+        use crate::msg::{
+            ApproveResponse, BurnResponse, MintCW20Response, SendToEvmResponse, TransferResponse,
+        };
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            deps
+        }
+
+        fn data<T: serde::de::DeserializeOwned>(res: Response<SendToEvmMsg>) -> T {
+            from_slice(&res.data.expect("no data set")).unwrap()
+        }
+
+        #[test]
+        fn token_operations_return_balances() {
+            let mut deps = setup();
+
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(30u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            let transfer: TransferResponse = data(execute(deps.as_mut(), env, info, transfer_msg).unwrap());
+            assert_eq!(
+                transfer,
+                TransferResponse {
+                    from: "addr0000".to_string(),
+                    to: "addr1111".to_string(),
+                    amount: Uint128::from(30u128),
+                    from_balance: Uint128::from(70u128),
+                    to_balance: Uint128::from(30u128),
+                    allowance: None,
+                }
+            );
+
+            let approve_msg = ExecuteMsg::Approve {
+                spender: "addr2222".to_string(),
+                amount: Uint128::from(20u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            let approve: ApproveResponse = data(execute(deps.as_mut(), env, info, approve_msg).unwrap());
+            assert_eq!(approve.allowance, Uint128::from(20u128));
+
+            let transfer_from_msg = ExecuteMsg::TransferFrom {
+                owner: "addr0000".to_string(),
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(5u128),
+            };
+            let (env, info) = mock_env_height("addr2222", 452, 570);
+            let transfer: TransferResponse = data(execute(deps.as_mut(), env, info, transfer_from_msg).unwrap());
+            assert_eq!(transfer.from_balance, Uint128::from(65u128));
+            assert_eq!(transfer.to_balance, Uint128::from(35u128));
+            assert_eq!(transfer.allowance, Some(Uint128::from(15u128)));
+
+            let burn_msg = ExecuteMsg::Burn {
+                amount: Uint128::from(15u128),
+            };
+            let (env, info) = mock_env_height("addr0000", 453, 580);
+            let burn: BurnResponse = data(execute(deps.as_mut(), env, info, burn_msg).unwrap());
+            assert_eq!(
+                burn,
+                BurnResponse {
+                    amount: Uint128::from(15u128),
+                    balance: Uint128::from(50u128),
+                    total_supply: Uint128::from(85u128),
+                }
+            );
+        }
+
+        #[test]
+        fn bridge_operations_return_transfer_ids() {
+            let mut deps = setup();
+
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(50u128),
+                event_id: None,
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            let mint: MintOutcome = data(execute(deps.as_mut(), env, info, mint_msg).unwrap());
+            assert_eq!(
+                mint,
+                MintOutcome::Minted(MintCW20Response {
+                    transfer_id: 0,
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(50u128),
+                    fee: Uint128::zero(),
+                    balance: Uint128::from(150u128),
+                    total_supply: Uint128::from(150u128),
+                })
+            );
+
+            let send_msg = ExecuteMsg::SendToEvm {
+                recipient: "0x1111111111111111111111111111111111111111".to_string(),
+                amount: Uint128::from(40u128),
+                deadline: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 452, 570);
+            let send: SendToEvmResponse = data(execute(deps.as_mut(), env, info, send_msg).unwrap());
+            assert_eq!(
+                send,
+                SendToEvmResponse {
+                    transfer_id: 1,
+                    recipient: "0x1111111111111111111111111111111111111111".to_string(),
+                    amount: Uint128::from(40u128),
+                    fee: Uint128::zero(),
+                    balance: Uint128::from(110u128),
+                }
+            );
+
+            let (env, info) = mock_env_height(MODULE_CALLER, 453, 580);
+            let res = execute(deps.as_mut(), env, info, ExecuteMsg::ConfirmSendToEvm { id: 1 }).unwrap();
+            let confirmed: BridgeTransfer = data(res);
+            assert_eq!(confirmed.status, BridgeTransferStatus::Completed);
+        }
+    }
+
+    mod batch_transfer {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::{BatchTransferResponse, TransferItem};
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            deps
+        }
+
+        fn item(recipient: &str, amount: u128) -> TransferItem {
+            TransferItem {
+                recipient: recipient.to_string(),
+                amount: Uint128::from(amount),
+            }
+        }
+
+        #[test]
+        fn credits_every_recipient() {
+            let mut deps = setup();
+            let batch_msg = ExecuteMsg::BatchTransfer {
+                transfers: vec![item("addr1111", 10), item("addr2222", 20), item("addr1111", 5)],
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            let res = execute(deps.as_mut(), env, info, batch_msg).unwrap();
+
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 65);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 15);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr2222")), 20);
+            assert_eq!(get_total_supply(&deps.storage), 100);
+
+            assert_eq!(res.events.len(), 3);
+            assert_eq!(res.events[1], events::transfer("addr0000", "addr0000", "addr2222", Uint128::from(20u128)));
+            let data: BatchTransferResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.total_amount, Uint128::from(35u128));
+            assert_eq!(data.from_balance, Uint128::from(65u128));
+        }
+
+        #[test]
+        fn fails_as_a_whole() {
+            let mut deps = setup();
+
+            // Insufficient funds for the total even though each item fits
+            let batch_msg = ExecuteMsg::BatchTransfer {
+                transfers: vec![item("addr1111", 60), item("addr2222", 60)],
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            match execute(deps.as_mut(), env, info, batch_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientFunds { balance, required }) => {
+                    assert_eq!(balance, 100);
+                    assert_eq!(required, 120);
+                }
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            // A frozen recipient rejects the whole batch before any balance moves
+            let freeze_msg = ExecuteMsg::FreezeAccount {
+                address: "addr2222".to_string(),
+            };
+            let (env, info) = mock_env_height("creator", 451, 560);
+            execute(deps.as_mut(), env, info, freeze_msg).unwrap();
+            let batch_msg = ExecuteMsg::BatchTransfer {
+                transfers: vec![item("addr1111", 10), item("addr2222", 10)],
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            match execute(deps.as_mut(), env, info, batch_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::AccountFrozen { .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 100);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 0);
+
+            let batch_msg = ExecuteMsg::BatchTransfer { transfers: vec![] };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            match execute(deps.as_mut(), env, info, batch_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::EmptyBatch {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod batch_mint {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::{BatchMintCW20Response, MintItem, MintItemStatus};
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                cap: Some(Uint128::from(100u128)),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            deps
+        }
+
+        fn item(replay_key: &str, recipient: &str, amount: u128) -> MintItem {
+            MintItem {
+                replay_key: replay_key.to_string(),
+                recipient: recipient.to_string(),
+                amount: Uint128::from(amount),
+                evm_sender: None,
+            }
+        }
+
+        fn batch_mint(
+            deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+            mints: Vec<MintItem>,
+        ) -> Result<Response<SendToEvmMsg>, ContractError> {
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps.as_mut(), env, info, ExecuteMsg::BatchMintCW20 { mints })
+        }
+
+        #[test]
+        fn reports_each_item() {
+            let mut deps = setup();
+            let freeze_msg = ExecuteMsg::FreezeAccount {
+                address: "addr9999".to_string(),
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, freeze_msg).unwrap();
+
+            let res = batch_mint(
+                &mut deps,
+                vec![
+                    item("0xaa:0", "addr0000", 40),
+                    item("0xaa:1", "addr9999", 10),
+                    item("0xaa:2", "addr1111", 50),
+                    item("0xaa:0", "addr0000", 40),
+                    item("0xaa:3", "addr1111", 20),
+                    item("", "addr1111", 1),
+                ],
+            )
+            .unwrap();
+            let data: BatchMintCW20Response = from_slice(&res.data.unwrap()).unwrap();
+            let statuses: Vec<MintItemStatus> =
+                data.results.into_iter().map(|result| result.status).collect();
+            assert_eq!(statuses[0], MintItemStatus::Minted { transfer_id: 0, fee: Uint128::zero() });
+            assert!(matches!(statuses[1], MintItemStatus::Failed { .. }));
+            assert_eq!(statuses[2], MintItemStatus::Minted { transfer_id: 1, fee: Uint128::zero() });
+            assert_eq!(statuses[3], MintItemStatus::AlreadyProcessed { transfer_id: 0 });
+            // Would exceed the cap of 100 on top of the 90 minted so far
+            assert_eq!(
+                statuses[4],
+                MintItemStatus::Failed {
+                    error: ContractError::CapExceeded { cap: 100, required: 110 }.to_string(),
+                }
+            );
+            assert_eq!(
+                statuses[5],
+                MintItemStatus::Failed {
+                    error: ContractError::EmptyReplayKey {}.to_string(),
+                }
+            );
+            assert_eq!(data.total_minted, Uint128::from(90u128));
+
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 40);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 50);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr9999")), 0);
+            assert_eq!(get_total_supply(&deps.storage), 90);
+
+            let types: Vec<&str> = res.events.iter().map(|event| event.ty.as_str()).collect();
+            assert_eq!(
+                types,
+                vec![
+                    "bridge_in",
+                    "bridge_in_skipped",
+                    "bridge_in",
+                    "bridge_in_skipped",
+                    "bridge_in_skipped",
+                    "bridge_in_skipped",
+                ]
+            );
+        }
+
+        #[test]
+        fn replay_keys_persist_across_batches() {
+            let mut deps = setup();
+            batch_mint(&mut deps, vec![item("0xaa:0", "addr0000", 10)]).unwrap();
+            let res = batch_mint(&mut deps, vec![item("0xaa:0", "addr0000", 10)]).unwrap();
+            let data: BatchMintCW20Response = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.results[0].status, MintItemStatus::AlreadyProcessed { transfer_id: 0 });
+            assert_eq!(get_total_supply(&deps.storage), 10);
+        }
+
+        #[test]
+        fn event_is_minted_once_across_messages() {
+            let mut deps = setup();
+            let mint_msg = || ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(10u128),
+                event_id: Some("0xaa:0".to_string()),
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps.as_mut(), env, info, mint_msg()).unwrap();
+
+            // The same event again through MintCW20
+            let (env, info) = mock_env_height(MODULE_CALLER, 452, 570);
+            match execute(deps.as_mut(), env, info, mint_msg()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MintAlreadyProcessed { event_id, transfer_id }) => {
+                    assert_eq!(event_id, "0xaa:0");
+                    assert_eq!(transfer_id, 0);
+                }
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            // And through BatchMintCW20
+            let res = batch_mint(&mut deps, vec![item("0xaa:0", "addr0000", 10)]).unwrap();
+            let data: BatchMintCW20Response = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.results[0].status, MintItemStatus::AlreadyProcessed { transfer_id: 0 });
+
+            // A batch-minted event cannot come back through MintCW20 either
+            batch_mint(&mut deps, vec![item("0xaa:1", "addr0000", 10)]).unwrap();
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(10u128),
+                event_id: Some("0xaa:1".to_string()),
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 453, 580);
+            match execute(deps.as_mut(), env, info, mint_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MintAlreadyProcessed { transfer_id, .. }) => assert_eq!(transfer_id, 1),
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 20);
+            assert_eq!(get_total_supply(&deps.storage), 20);
+        }
+
+        #[test]
+        fn items_from_evm_are_escrowed_or_refunded() {
+            let mut deps = setup();
+            let freeze_msg = ExecuteMsg::FreezeAccount {
+                address: "addr9999".to_string(),
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, freeze_msg).unwrap();
+
+            let from_evm = |item: MintItem| MintItem {
+                evm_sender: Some("0x2222222222222222222222222222222222222222".to_string()),
+                ..item
+            };
+            let res = batch_mint(
+                &mut deps,
+                vec![
+                    from_evm(item("0xaa:0", "addr0000", 40)),
+                    from_evm(item("0xaa:1", "ab", 30)),
+                    from_evm(item("0xaa:2", "addr9999", 10)),
+                    from_evm(item("0xaa:3", "addr1111", 40)),
+                    item("0xaa:4", "addr1111", 40),
+                ],
+            )
+            .unwrap();
+            let data: BatchMintCW20Response = from_slice(&res.data.unwrap()).unwrap();
+            let statuses: Vec<MintItemStatus> =
+                data.results.into_iter().map(|result| result.status).collect();
+            assert_eq!(statuses[0], MintItemStatus::Minted { transfer_id: 0, fee: Uint128::zero() });
+            assert_eq!(statuses[1], MintItemStatus::Escrowed {});
+            assert_eq!(statuses[2], MintItemStatus::Refunded { transfer_id: 2 });
+            // The escrow counts towards the cap of 100
+            assert_eq!(statuses[3], MintItemStatus::Refunded { transfer_id: 3 });
+            assert!(matches!(statuses[4], MintItemStatus::Failed { .. }));
+            assert_eq!(res.messages.len(), 2);
+            assert_eq!(data.total_minted, Uint128::from(70u128));
+            assert_eq!(data.total_supply, Uint128::from(70u128));
+            assert_eq!(get_total_supply(&deps.storage), 70);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 40);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr1111")), 0);
+        }
+
+        #[test]
+        fn requires_bridge_caller() {
+            let mut deps = setup();
+            let mint_msg = ExecuteMsg::BatchMintCW20 {
+                mints: vec![item("0xaa:0", "addr0000", 10)],
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            match execute(deps.as_mut(), env, info, mint_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidSender { .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod batch_send_to_evm {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::{BatchSendToEvmResponse, TransferItem};
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        const EVM_CONTRACT: &str = "0xcd38b80aee05cad65571b7564bd110fdf2990de6";
+        const EVM_ALICE: &str = "0x1111111111111111111111111111111111111111";
+        const EVM_BOB: &str = "0x2222222222222222222222222222222222222222";
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: EVM_CONTRACT.to_string(),
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            deps
+        }
+
+        fn item(recipient: &str, amount: u128) -> TransferItem {
+            TransferItem {
+                recipient: recipient.to_string(),
+                amount: Uint128::from(amount),
+            }
+        }
+
+        #[test]
+        fn emits_one_message_per_recipient() {
+            let mut deps = setup();
+            let batch_msg = ExecuteMsg::BatchSendToEvm {
+                transfers: vec![item(EVM_ALICE, 30), item(EVM_BOB, 20)],
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            let res = execute(deps.as_mut(), env.clone(), info, batch_msg).unwrap();
+
+            assert_eq!(res.messages.len(), 2);
+            assert_eq!(
+                res.messages[1].msg,
+                CosmosMsg::Custom(SendToEvmMsg {
+                    sender: env.contract.address.to_string(),
+                    contract: EVM_CONTRACT.to_string(),
+                    recipient: EVM_BOB.to_string(),
+                    amount: Uint128::from(20u128),
+                    id: 1,
+                })
+            );
+            assert_eq!(res.events.len(), 2);
+            assert_eq!(res.events[0], events::bridge_out("addr0000", EVM_ALICE, Uint128::from(30u128), Uint128::zero(), 0));
+
+            let data: BatchSendToEvmResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.transfer_ids, vec![0, 1]);
+            assert_eq!(data.total_amount, Uint128::from(50u128));
+            assert_eq!(data.balance, Uint128::from(50u128));
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 50);
+            assert_eq!(get_total_supply(&deps.storage), 50);
+        }
+
+        #[test]
+        fn rejects_the_whole_batch() {
+            let mut deps = setup();
+            let batch_msg = ExecuteMsg::BatchSendToEvm {
+                transfers: vec![item(EVM_ALICE, 30), item("addr1111", 20)],
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            match execute(deps.as_mut(), env, info, batch_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidRecipient { address }) => assert_eq!(address, "addr1111"),
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            let batch_msg = ExecuteMsg::BatchSendToEvm {
+                transfers: vec![item(EVM_ALICE, 60), item(EVM_BOB, 60)],
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            match execute(deps.as_mut(), env, info, batch_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientFunds { balance, required }) => {
+                    assert_eq!(balance, 100);
+                    assert_eq!(required, 120);
+                }
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 100);
+            assert_eq!(get_total_supply(&deps.storage), 100);
+        }
+    }
+
+    mod permit {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::{ApproveResponse, NonceResponse, SignDoc};
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        fn owner_key() -> SigningKey {
+            signing_key(7)
+        }
+
+        fn owner_address() -> Addr {
+            key_address(&owner_key())
+        }
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            setup_with_balances(vec![InitialBalance {
+                address: owner_address().to_string(),
+                amount: Uint128::from(100u128),
+            }])
+        }
+
+        fn permit_msg(env: &Env, key: &SigningKey, amount: u128, expiration: u64, nonce: u64) -> ExecuteMsg {
+            let doc = SignDoc::Permit {
+                chain_id: env.block.chain_id.clone(),
+                contract: env.contract.address.to_string(),
+                owner: owner_address().to_string(),
+                spender: "spender".to_string(),
+                amount: Uint128::from(amount),
+                expiration,
+                nonce,
+            };
+            ExecuteMsg::Permit {
+                owner_pubkey: Binary::from(uncompressed_pubkey(&owner_key())),
+                spender: "spender".to_string(),
+                amount: Uint128::from(amount),
+                expiration,
+                nonce,
+                signature: sign_doc(key, &doc),
+            }
+        }
+
+        fn query_nonce(deps: Deps, address: &Addr) -> u64 {
+            let data = query(deps, mock_env(), QueryMsg::Nonce { address: address.to_string() }).unwrap();
+            let response: NonceResponse = from_slice(&data).unwrap();
+            response.nonce
+        }
+
+        #[test]
+        fn permit_sets_allowance_usable_by_spender() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height("relayer", 451, 560);
+            let msg = permit_msg(&env, &owner_key(), 40, 600, 0);
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+            let owner = owner_address();
+            assert_eq!(
+                res.events,
+                vec![events::permit("relayer", owner.as_str(), "spender", Uint128::from(40u128))]
+            );
+            let data: ApproveResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.owner, owner.to_string());
+            assert_eq!(data.allowance, Uint128::from(40u128));
+            assert_eq!(query_nonce(deps.as_ref(), &owner), 1);
+
+            let transfer_from_msg = ExecuteMsg::TransferFrom {
+                owner: owner.to_string(),
+                recipient: "addr0001".to_string(),
+                amount: Uint128::from(40u128),
+            };
+            let (env, info) = mock_env_height("spender", 452, 570);
+            execute(deps.as_mut(), env, info, transfer_from_msg).unwrap();
+            assert_eq!(get_balance(&deps.storage, &owner), 60);
+        }
+
+        #[test]
+        fn replayed_permit_is_rejected() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height("relayer", 451, 560);
+            execute(deps.as_mut(), env.clone(), info.clone(), permit_msg(&env, &owner_key(), 40, 600, 0)).unwrap();
+
+            match execute(deps.as_mut(), env.clone(), info, permit_msg(&env, &owner_key(), 40, 600, 0)) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidNonce { expected: 1, got: 0 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn expired_permit_is_rejected() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height("relayer", 451, 560);
+            match execute(deps.as_mut(), env.clone(), info, permit_msg(&env, &owner_key(), 40, 559, 0)) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::SignatureExpired { expiration: 559 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(query_nonce(deps.as_ref(), &owner_address()), 0);
+        }
+
+        #[test]
+        fn signature_from_another_key_is_rejected() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height("relayer", 451, 560);
+            let other_key = signing_key(9);
+            match execute(deps.as_mut(), env.clone(), info, permit_msg(&env, &other_key, 40, 600, 0)) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidSignature {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            let allowance = read_allowance(&deps.storage, &owner_address(), &Addr::unchecked("spender")).unwrap();
+            assert_eq!(allowance, 0);
+        }
+
+        #[test]
+        fn signature_for_another_chain_is_rejected() {
+            let mut deps = setup();
+            let (mut env, info) = mock_env_height("relayer", 451, 560);
+            let msg = permit_msg(&env, &owner_key(), 40, 600, 0);
+            env.block.chain_id = "exchain-66".to_string();
+            match execute(deps.as_mut(), env, info, msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidSignature {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn compressed_pubkey_is_rejected() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height("relayer", 451, 560);
+            let mut msg = permit_msg(&env, &owner_key(), 40, 600, 0);
+            if let ExecuteMsg::Permit { owner_pubkey, .. } = &mut msg {
+                *owner_pubkey = Binary::from(owner_key().verifying_key().to_encoded_point(true).as_bytes());
+            }
+            match execute(deps.as_mut(), env, info, msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidPubkey {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod execute_signed {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::{SendToEvmResponse, SignDoc, SignedAction, SignedPayload, TransferResponse};
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        const EVM_CONTRACT: &str = "0xcd38b80aee05cad65571b7564bd110fdf2990de6";
+        const EVM_ALICE: &str = "0x1111111111111111111111111111111111111111";
+
+        fn signer_key() -> SigningKey {
+            signing_key(7)
+        }
+
+        fn signer() -> Addr {
+            key_address(&signer_key())
+        }
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = setup_with_balances(vec![InitialBalance {
+                address: signer().to_string(),
+                amount: Uint128::from(100u128),
+            }]);
+
+            let grant_msg = ExecuteMsg::GrantRole {
+                role: Role::Relayer,
+                address: "relayer".to_string(),
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, grant_msg).unwrap();
+            deps
+        }
+
+        fn payload(action: SignedAction, fee: u128, nonce: u64) -> SignedPayload {
+            SignedPayload {
+                action,
+                fee: Uint128::from(fee),
+                nonce,
+                deadline: 600,
+            }
+        }
+
+        fn signed_msg(env: &Env, payload: SignedPayload) -> ExecuteMsg {
+            let doc = SignDoc::Execute {
+                chain_id: env.block.chain_id.clone(),
+                contract: env.contract.address.to_string(),
+                signer: signer().to_string(),
+                payload: payload.clone(),
+            };
+            ExecuteMsg::ExecuteSigned {
+                payload,
+                pubkey: Binary::from(uncompressed_pubkey(&signer_key())),
+                signature: sign_doc(&signer_key(), &doc),
+            }
+        }
+
+        fn transfer(amount: u128) -> SignedAction {
+            SignedAction::Transfer {
+                recipient: "addr0001".to_string(),
+                amount: Uint128::from(amount),
+            }
+        }
+
+        #[test]
+        fn relayer_submits_transfer_and_collects_fee() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height("relayer", 451, 560);
+            let msg = signed_msg(&env, payload(transfer(30), 2, 0));
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+            assert_eq!(get_balance(&deps.storage, &signer()), 68);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0001")), 30);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("relayer")), 2);
+            assert_eq!(
+                res.events,
+                vec![
+                    events::transfer(signer().as_str(), signer().as_str(), "addr0001", Uint128::from(30u128)),
+                    events::transfer("relayer", signer().as_str(), "relayer", Uint128::from(2u128)),
+                    events::execute_signed("relayer", signer().as_str(), 0, Uint128::from(2u128)),
+                ]
+            );
+            let data: TransferResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.from, signer().to_string());
+            assert_eq!(data.amount, Uint128::from(30u128));
+            assert_eq!(read_nonce(&deps.storage, signer().as_str()).unwrap(), 1);
+        }
+
+        #[test]
+        fn relayer_submits_send_to_evm() {
+            let mut deps = setup();
+            let action = SignedAction::SendToEvm {
+                recipient: EVM_ALICE.to_string(),
+                amount: Uint128::from(40u128),
+            };
+            let (env, info) = mock_env_height("relayer", 451, 560);
+            let msg = signed_msg(&env, payload(action, 0, 0));
+            let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+            assert_eq!(
+                res.messages[0].msg,
+                CosmosMsg::Custom(SendToEvmMsg {
+                    sender: env.contract.address.to_string(),
+                    contract: EVM_CONTRACT.to_string(),
+                    recipient: EVM_ALICE.to_string(),
+                    amount: Uint128::from(40u128),
+                    id: 0,
+                })
+            );
+            let data: SendToEvmResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.balance, Uint128::from(60u128));
+            assert_eq!(get_total_supply(&deps.storage), 60);
+            assert_eq!(read_bridge_transfer(&deps.storage, 0).unwrap().account, signer());
+        }
+
+        #[test]
+        fn only_relayers_may_submit() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height("addr0001", 451, 560);
+            let msg = signed_msg(&env, payload(transfer(30), 2, 0));
+            match execute(deps.as_mut(), env, info, msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
         }
 
+        #[test]
+        fn fee_counts_towards_balance() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height("relayer", 451, 560);
+            let msg = signed_msg(&env, payload(transfer(99), 2, 0));
+            match execute(deps.as_mut(), env, info, msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientFunds { balance: 100, required: 101 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_balance(&deps.storage, &signer()), 100);
+        }
 
         #[test]
-        fn works_with_balance_larger_than_53_bit() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                evm_contract: "abc".to_string(),
+        fn relayer_cannot_change_the_fee() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height("relayer", 451, 560);
+            let mut msg = signed_msg(&env, payload(transfer(30), 2, 0));
+            if let ExecuteMsg::ExecuteSigned { payload, .. } = &mut msg {
+                payload.fee = Uint128::from(20u128);
+            }
+            match execute(deps.as_mut(), env, info, msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidSignature {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn signed_payload_cannot_be_replayed() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height("relayer", 451, 560);
+            let msg = signed_msg(&env, payload(transfer(30), 0, 0));
+            execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+            let msg = signed_msg(&env, payload(transfer(30), 0, 0));
+            match execute(deps.as_mut(), env, info, msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidNonce { expected: 1, got: 0 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod claim {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::{ClaimResponse, ClaimableResponse, MintCW20Response, SignDoc};
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+
+        fn wallet_key() -> SigningKey {
+            signing_key(5)
+        }
+
+        // Mixed case, the way wallets display checksummed addresses
+        fn wallet_address() -> String {
+            format!("0x{}", evm_address(&wallet_key())[2..].to_uppercase())
+        }
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            setup_with_balances(vec![])
+        }
+
+        fn mint_to_wallet(deps: DepsMut, amount: u128) -> MintCW20Response {
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: wallet_address(),
+                amount: Uint128::from(amount),
+                event_id: None,
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            let res = execute(deps, env, info, mint_msg).unwrap();
+            match from_slice(&res.data.unwrap()).unwrap() {
+                MintOutcome::Minted(minted) => minted,
+                outcome => panic!("unexpected outcome: {:?}", outcome),
+            }
+        }
+
+        fn claim_msg(env: &Env, key: &SigningKey, recipient: &str, nonce: u64) -> ExecuteMsg {
+            let doc = SignDoc::Claim {
+                chain_id: env.block.chain_id.clone(),
+                contract: env.contract.address.to_string(),
+                evm_address: wallet_address().to_lowercase(),
+                recipient: recipient.to_string(),
+                nonce,
+            };
+            ExecuteMsg::Claim {
+                evm_address: wallet_address(),
+                recipient: recipient.to_string(),
+                signature: personal_sign(key, &doc),
+            }
+        }
+
+        fn query_claimable(deps: Deps) -> ClaimableResponse {
+            let query_msg = QueryMsg::Claimable {
+                evm_address: wallet_address(),
+            };
+            from_slice(&query(deps, mock_env(), query_msg).unwrap()).unwrap()
+        }
+
+        #[test]
+        fn evm_recipient_gets_claimable_balance() {
+            let mut deps = setup();
+            let data = mint_to_wallet(deps.as_mut(), 30);
+            let data_again = mint_to_wallet(deps.as_mut(), 12);
+
+            assert_eq!(data.recipient, wallet_address().to_lowercase());
+            assert_eq!(data_again.balance, Uint128::from(42u128));
+            assert_eq!(data_again.total_supply, Uint128::from(42u128));
+            let claimable = query_claimable(deps.as_ref());
+            assert_eq!(claimable.amount, Uint128::from(42u128));
+            assert_eq!(claimable.nonce, 0);
+
+            let transfer = read_bridge_transfer(&deps.storage, 0).unwrap();
+            assert_eq!(transfer.account, mock_env().contract.address.to_string());
+            assert_eq!(transfer.evm_address, Some(wallet_address().to_lowercase()));
+        }
+
+        #[test]
+        fn wallet_owner_claims_to_any_account() {
+            let mut deps = setup();
+            mint_to_wallet(deps.as_mut(), 30);
+
+            let (env, info) = mock_env_height("anyone", 452, 570);
+            let msg = claim_msg(&env, &wallet_key(), "addr0001", 0);
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+            assert_eq!(
+                res.events,
+                vec![events::claim(
+                    "anyone",
+                    &wallet_address().to_lowercase(),
+                    "addr0001",
+                    Uint128::from(30u128)
+                )]
+            );
+            let data: ClaimResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.balance, Uint128::from(30u128));
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0001")), 30);
+            assert_eq!(get_total_supply(&deps.storage), 30);
+            let claimable = query_claimable(deps.as_ref());
+            assert_eq!(claimable.amount, Uint128::zero());
+            assert_eq!(claimable.nonce, 1);
+        }
+
+        #[test]
+        fn sender_claims_mint_sent_to_wrong_recipient() {
+            let mut deps = setup();
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: None,
+                claim_by_sender: Some(true),
             };
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+            assert!(read_bridge_config(&deps.storage).unwrap().claim_by_sender);
+
+            // A valid address, just not the one the sender meant
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0002".to_string(),
+                amount: Uint128::from(30u128),
+                event_id: None,
+                evm_sender: Some(wallet_address()),
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            let res = execute(deps.as_mut(), env, info, mint_msg).unwrap();
+            let minted = match from_slice(&res.data.unwrap()).unwrap() {
+                MintOutcome::Minted(minted) => minted,
+                outcome => panic!("unexpected outcome: {:?}", outcome),
+            };
+            assert_eq!(minted.recipient, wallet_address().to_lowercase());
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0002")), 0);
+            assert_eq!(query_claimable(deps.as_ref()).amount, Uint128::from(30u128));
+
+            let (env, info) = mock_env_height("anyone", 452, 570);
+            let msg = claim_msg(&env, &wallet_key(), "addr0001", 0);
+            execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0001")), 30);
+            assert_eq!(get_total_supply(&deps.storage), 30);
+        }
+
+        #[test]
+        fn claim_signed_by_another_wallet_is_rejected() {
+            let mut deps = setup();
+            mint_to_wallet(deps.as_mut(), 30);
 
-            let mint_cw20_msg = ExecuteMsg::MintCW20 { recipient: "addr0000".to_string(), amount: (Uint128::from(9007199254740993u128)) };
+            let (env, info) = mock_env_height("anyone", 452, 570);
+            let other_key = signing_key(6);
+            match execute(deps.as_mut(), env.clone(), info, claim_msg(&env, &other_key, "addr0001", 0)) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidSignature {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(query_claimable(deps.as_ref()).amount, Uint128::from(30u128));
+        }
 
-            let (env, info) = mock_env_height("abc", 450, 550);
-            execute(deps.as_mut(), env, info, mint_cw20_msg).unwrap();
+        #[test]
+        fn signature_cannot_redirect_claim() {
+            let mut deps = setup();
+            mint_to_wallet(deps.as_mut(), 30);
+
+            let (env, info) = mock_env_height("anyone", 452, 570);
+            let mut msg = claim_msg(&env, &wallet_key(), "addr0001", 0);
+            if let ExecuteMsg::Claim { recipient, .. } = &mut msg {
+                *recipient = "addr0002".to_string();
+            }
+            match execute(deps.as_mut(), env, info, msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidSignature {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn claim_signature_cannot_be_replayed() {
+            let mut deps = setup();
+            mint_to_wallet(deps.as_mut(), 30);
+            let (env, info) = mock_env_height("anyone", 452, 570);
+            let msg = claim_msg(&env, &wallet_key(), "addr0001", 0);
+            execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+            mint_to_wallet(deps.as_mut(), 5);
+            match execute(deps.as_mut(), env.clone(), info, claim_msg(&env, &wallet_key(), "addr0001", 0)) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidSignature {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn nothing_to_claim() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height("anyone", 452, 570);
+            match execute(deps.as_mut(), env.clone(), info, claim_msg(&env, &wallet_key(), "addr0001", 0)) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::NothingToClaim { .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod escrow {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::{EscrowedResponse, ReleaseEscrowResponse, SignDoc};
+        use crate::state::Escrow;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        const EVM_CONTRACT: &str = "0xcd38b80aee05cad65571b7564bd110fdf2990de6";
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+
+        fn sender_key() -> SigningKey {
+            signing_key(5)
+        }
+
+        fn evm_sender() -> String {
+            evm_address(&sender_key())
+        }
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            setup_with_balances(vec![])
+        }
+
+        // "ab" is too short to be a valid address
+        fn mint_msg(event_id: Option<&str>, sender: Option<String>) -> ExecuteMsg {
+            ExecuteMsg::MintCW20 {
+                recipient: "ab".to_string(),
+                amount: Uint128::from(30u128),
+                event_id: event_id.map(|id| id.to_string()),
+                evm_sender: sender,
+            }
+        }
+
+        fn release_msg(env: &Env, key: &SigningKey, recipient: Option<&str>) -> ExecuteMsg {
+            release_msg_with_nonce(env, key, recipient, 0)
+        }
+
+        fn release_msg_with_nonce(env: &Env, key: &SigningKey, recipient: Option<&str>, nonce: u64) -> ExecuteMsg {
+            let doc = SignDoc::ReleaseEscrow {
+                chain_id: env.block.chain_id.clone(),
+                contract: env.contract.address.to_string(),
+                event_id: "0xabc-1".to_string(),
+                recipient: recipient.map(|recipient| recipient.to_string()),
+                nonce,
+            };
+            ExecuteMsg::ReleaseEscrow {
+                event_id: "0xabc-1".to_string(),
+                recipient: recipient.map(|recipient| recipient.to_string()),
+                signature: personal_sign(key, &doc),
+            }
+        }
+
+        fn escrowed(deps: Deps) -> Vec<Escrow> {
+            let query_msg = QueryMsg::Escrowed {
+                start_after: None,
+                limit: None,
+            };
+            let response: EscrowedResponse = from_slice(&query(deps, mock_env(), query_msg).unwrap()).unwrap();
+            response.escrows
+        }
+
+        #[test]
+        fn invalid_recipient_is_escrowed() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            let res = execute(deps.as_mut(), env, info, mint_msg(Some("0xabc-1"), Some(evm_sender()))).unwrap();
 
-            assert_eq!(0, res.messages.len());
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                9007199254740993
+                res.events,
+                vec![events::escrow(MODULE_CALLER, "0xabc-1", &evm_sender(), "ab", Uint128::from(30u128))]
             );
-            assert_eq!(get_total_supply(&deps.storage), 9007199254740993);
+            let escrows = escrowed(deps.as_ref());
+            assert_eq!(escrows.len(), 1);
+            assert_eq!(escrows[0].evm_sender, evm_sender());
+            assert_eq!(escrows[0].amount, Uint128::from(30u128));
+            assert_eq!(get_total_supply(&deps.storage), 30);
         }
 
         #[test]
-        fn works_with_balance_larger_than_64_bit() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                evm_contract: "abc".to_string(),
+        fn invalid_recipient_without_sender_still_fails() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            match execute(deps.as_mut(), env, info, mint_msg(Some("0xabc-1"), None)) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Std(_)) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert!(escrowed(deps.as_ref()).is_empty());
+        }
+
+        #[test]
+        fn event_is_escrowed_once() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps.as_mut(), env.clone(), info.clone(), mint_msg(Some("0xabc-1"), Some(evm_sender()))).unwrap();
+            match execute(deps.as_mut(), env, info, mint_msg(Some("0xabc-1"), Some(evm_sender()))) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MintAlreadyProcessed { .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_total_supply(&deps.storage), 30);
+        }
+
+        #[test]
+        fn released_event_cannot_be_escrowed_or_released_again() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps.as_mut(), env, info, mint_msg(Some("0xabc-1"), Some(evm_sender()))).unwrap();
+            let (env, info) = mock_env_height("anyone", 452, 570);
+            let release = release_msg(&env, &sender_key(), Some("addr0001"));
+            execute(deps.as_mut(), env.clone(), info, release).unwrap();
+
+            let (env, info) = mock_env_height(MODULE_CALLER, 453, 580);
+            match execute(deps.as_mut(), env, info, mint_msg(Some("0xabc-1"), Some(evm_sender()))) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MintAlreadyProcessed { transfer_id: 0, .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert!(escrowed(deps.as_ref()).is_empty());
+
+            // Even if the escrow came back, the old signature is for a used nonce
+            let escrow = Escrow {
+                event_id: "0xabc-1".to_string(),
+                evm_sender: evm_sender(),
+                recipient: "ab".to_string(),
+                amount: Uint128::from(30u128),
+                height: 451,
+                time: Timestamp::from_seconds(560),
             };
-            let (env, info) = mock_env_height("abc", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let mut escrow_store = PrefixedStorage::new(&mut deps.storage, PREFIX_ESCROWS);
+            escrow_store.set(b"0xabc-1", &to_vec(&escrow).unwrap());
+            let (env, info) = mock_env_height("anyone", 454, 590);
+            let replay = release_msg(&env, &sender_key(), Some("addr0001"));
+            match execute(deps.as_mut(), env.clone(), info, replay) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidSignature {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            let (env, info) = mock_env_height("anyone", 455, 600);
+            let release = release_msg_with_nonce(&env, &sender_key(), Some("addr0001"), 1);
+            execute(deps.as_mut(), env.clone(), info, release).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0001")), 60);
+        }
 
-            let mint_cw20_msg = ExecuteMsg::MintCW20 { recipient: "addr0000".to_string(), amount: (Uint128::from(100000000000000000000000000u128)) };
+        #[test]
+        fn evm_sender_releases_to_corrected_recipient() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps.as_mut(), env, info, mint_msg(Some("0xabc-1"), Some(evm_sender()))).unwrap();
+
+            let (env, info) = mock_env_height("anyone", 452, 570);
+            let res = execute(deps.as_mut(), env.clone(), info, release_msg(&env, &sender_key(), Some("addr0001"))).unwrap();
+
+            assert!(res.messages.is_empty());
+            let data: ReleaseEscrowResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.recipient, "addr0001");
+            assert_eq!(data.transfer_id, None);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0001")), 30);
+            assert_eq!(get_total_supply(&deps.storage), 30);
+            assert!(escrowed(deps.as_ref()).is_empty());
+        }
 
-            let (env, info) = mock_env_height("abc", 450, 550);
-            execute(deps.as_mut(), env, info, mint_cw20_msg).unwrap();
+        #[test]
+        fn evm_sender_bounces_escrow_back() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps.as_mut(), env, info, mint_msg(Some("0xabc-1"), Some(evm_sender()))).unwrap();
+
+            let (env, info) = mock_env_height("anyone", 452, 570);
+            let res = execute(deps.as_mut(), env.clone(), info, release_msg(&env, &sender_key(), None)).unwrap();
 
-            assert_eq!(0, res.messages.len());
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                100000000000000000000000000
+                res.messages[0].msg,
+                CosmosMsg::Custom(SendToEvmMsg {
+                    sender: env.contract.address.to_string(),
+                    contract: EVM_CONTRACT.to_string(),
+                    recipient: evm_sender(),
+                    amount: Uint128::from(30u128),
+                    id: 1,
+                })
             );
-            assert_eq!(get_total_supply(&deps.storage), 100000000000000000000000000);
+            let data: ReleaseEscrowResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.transfer_id, Some(1));
+            assert_eq!(get_total_supply(&deps.storage), 0);
+            assert_eq!(read_outbound_in_flight(&deps.storage).unwrap(), 30);
         }
 
         #[test]
-        fn fails_for_large_decimals() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 42,
-                evm_contract: "abc".to_string(),
+        fn only_evm_sender_may_release() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps.as_mut(), env, info, mint_msg(Some("0xabc-1"), Some(evm_sender()))).unwrap();
+
+            let (env, info) = mock_env_height("anyone", 452, 570);
+            let other_key = signing_key(6);
+            match execute(deps.as_mut(), env.clone(), info, release_msg(&env, &other_key, Some("addr0001"))) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InvalidSignature {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(escrowed(deps.as_ref()).len(), 1);
+        }
+
+        #[test]
+        fn release_pays_bridge_fee() {
+            let mut deps = setup();
+            let schedule_msg = ExecuteMsg::UpdateFeeSchedule {
+                collector: "treasury".to_string(),
+                base_fee_bps: 1000,
+                tiers: vec![],
+                window_days: 0,
             };
             let (env, info) = mock_env_height("creator", 450, 550);
-            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
-            match result {
+            execute(deps.as_mut(), env, info, schedule_msg).unwrap();
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps.as_mut(), env, info, mint_msg(Some("0xabc-1"), Some(evm_sender()))).unwrap();
+
+            let (env, info) = mock_env_height("anyone", 452, 570);
+            let res = execute(deps.as_mut(), env.clone(), info, release_msg(&env, &sender_key(), Some("addr0001"))).unwrap();
+
+            let data: ReleaseEscrowResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.amount, Uint128::from(30u128));
+            assert_eq!(data.fee, Uint128::from(3u128));
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0001")), 27);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("treasury")), 3);
+            assert_eq!(get_total_supply(&deps.storage), 30);
+        }
+
+        #[test]
+        fn release_checks_inbound_direction() {
+            let mut deps = setup();
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps.as_mut(), env, info, mint_msg(Some("0xabc-1"), Some(evm_sender()))).unwrap();
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: Some(false),
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 452, 570);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+
+            let (env, info) = mock_env_height("anyone", 453, 580);
+            match execute(deps.as_mut(), env.clone(), info, release_msg(&env, &sender_key(), Some("addr0001"))) {
                 Ok(_) => panic!("expected error"),
-                Err(ContractError::DecimalsExceeded {}) => {}
+                Err(ContractError::BridgeDirectionDisabled { .. }) => {}
                 Err(e) => panic!("unexpected error: {:?}", e),
             }
+            assert_eq!(escrowed(deps.as_ref()).len(), 1);
+            assert_eq!(read_nonce(&deps.storage, &evm_sender()).unwrap(), 0);
+
+            // Returning the tokens to the EVM sender is still possible
+            let (env, info) = mock_env_height("anyone", 454, 590);
+            execute(deps.as_mut(), env.clone(), info, release_msg(&env, &sender_key(), None)).unwrap();
+            assert!(escrowed(deps.as_ref()).is_empty());
         }
+    }
 
-        #[test]
-        fn fails_for_name_too_short() {
+    mod refunds {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::RefundsResponse;
+        use crate::state::RefundReason;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        const EVM_CONTRACT: &str = "0xcd38b80aee05cad65571b7564bd110fdf2990de6";
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+        const EVM_SENDER: &str = "0x1111111111111111111111111111111111111111";
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
             let mut deps = mock_dependencies(&[]);
             let instantiate_msg = InstantiateMsg {
-                name: "CC".to_string(),
+                name: "Cash Token".to_string(),
                 symbol: "CASH".to_string(),
                 decimals: 9,
-                evm_contract: "abc".to_string(),
+                evm_contract: EVM_CONTRACT.to_string(),
+                cap: Some(Uint128::from(100u128)),
+                ..Default::default()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
-            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
-            match result {
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            deps
+        }
+
+        fn mint(deps: DepsMut, sender: &str, recipient: &str, amount: u128) -> Result<Response<SendToEvmMsg>, ContractError> {
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: recipient.to_string(),
+                amount: Uint128::from(amount),
+                event_id: None,
+                evm_sender: Some(EVM_SENDER.to_string()),
+            };
+            let (env, info) = mock_env_height(sender, 451, 560);
+            execute(deps, env, info, mint_msg)
+        }
+
+        fn refund_reason(res: &Response<SendToEvmMsg>) -> RefundReason {
+            let refund = match from_slice(res.data.as_ref().unwrap()).unwrap() {
+                MintOutcome::Refunded(refund) => refund,
+                outcome => panic!("unexpected outcome: {:?}", outcome),
+            };
+            refund.reason
+        }
+
+        #[test]
+        fn frozen_recipient_is_refunded() {
+            let mut deps = setup();
+            let freeze_msg = ExecuteMsg::FreezeAccount {
+                address: "addr0000".to_string(),
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, freeze_msg).unwrap();
+
+            let res = mint(deps.as_mut(), MODULE_CALLER, "addr0000", 30).unwrap();
+            assert_eq!(
+                res.messages[0].msg,
+                CosmosMsg::Custom(SendToEvmMsg {
+                    sender: mock_env().contract.address.to_string(),
+                    contract: EVM_CONTRACT.to_string(),
+                    recipient: EVM_SENDER.to_string(),
+                    amount: Uint128::from(30u128),
+                    id: 0,
+                })
+            );
+            assert_eq!(
+                res.events,
+                vec![events::bridge_refund(
+                    MODULE_CALLER,
+                    EVM_SENDER,
+                    Uint128::from(30u128),
+                    0,
+                    "recipient_frozen"
+                )]
+            );
+            assert_eq!(refund_reason(&res), RefundReason::RecipientFrozen);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 0);
+            assert_eq!(get_total_supply(&deps.storage), 0);
+            assert_eq!(read_outbound_in_flight(&deps.storage).unwrap(), 30);
+        }
+
+        #[test]
+        fn mint_over_cap_is_refunded() {
+            let mut deps = setup();
+            let res = mint(deps.as_mut(), MODULE_CALLER, "addr0000", 101).unwrap();
+            assert_eq!(refund_reason(&res), RefundReason::CapExceeded);
+            assert_eq!(get_total_supply(&deps.storage), 0);
+        }
+
+        #[test]
+        fn mint_while_paused_is_refunded() {
+            let mut deps = setup();
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: Some(false),
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+
+            let res = mint(deps.as_mut(), MODULE_CALLER, "addr0000", 30).unwrap();
+            assert_eq!(refund_reason(&res), RefundReason::InboundPaused);
+        }
+
+        #[test]
+        fn invalid_recipient_without_event_id_is_refunded() {
+            let mut deps = setup();
+            let res = mint(deps.as_mut(), MODULE_CALLER, "ab", 30).unwrap();
+            assert_eq!(refund_reason(&res), RefundReason::InvalidRecipient);
+
+            let query_msg = QueryMsg::Refunds {
+                start_after: None,
+                limit: None,
+            };
+            let response: RefundsResponse =
+                from_slice(&query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+            assert_eq!(response.refunds.len(), 1);
+            assert_eq!(response.refunds[0].recipient, "ab");
+            assert_eq!(response.refunds[0].evm_sender, EVM_SENDER);
+        }
+
+        #[test]
+        fn unauthorized_caller_is_never_refunded() {
+            let mut deps = setup();
+            match mint(deps.as_mut(), "addr0000", "addr0000", 30) {
                 Ok(_) => panic!("expected error"),
-                Err(ContractError::NameWrongFormat {}) => {}
+                Err(ContractError::InvalidSender { .. }) => {}
                 Err(e) => panic!("unexpected error: {:?}", e),
             }
         }
+    }
 
-        #[test]
-        fn fails_for_name_too_long() {
+    mod cancel_send_to_evm {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::state::BridgeTransferStatus;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+        const EVM_RECIPIENT: &str = "0x1111111111111111111111111111111111111111";
+
+        fn setup(deadline: Option<u64>) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
             let mut deps = mock_dependencies(&[]);
             let instantiate_msg = InstantiateMsg {
-                name: "Cash coin. Cash coin. Cash coin. Cash coin.".to_string(),
+                name: "Cash Token".to_string(),
                 symbol: "CASH".to_string(),
                 decimals: 9,
-                evm_contract: "abc".to_string(),
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+                ..Default::default()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
-            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
-            match result {
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: Some(true),
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+
+            let send_msg = ExecuteMsg::SendToEvm {
+                recipient: EVM_RECIPIENT.to_string(),
+                amount: Uint128::from(40u128),
+                deadline,
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            execute(deps.as_mut(), env, info, send_msg).unwrap();
+            deps
+        }
+
+        fn cancel(deps: DepsMut, sender: &str, time: u64) -> Result<Response<SendToEvmMsg>, ContractError> {
+            let (env, info) = mock_env_height(sender, 460, time);
+            execute(deps, env, info, ExecuteMsg::CancelSendToEvm { id: 0 })
+        }
+
+        #[test]
+        fn sender_cancels_after_deadline() {
+            let mut deps = setup(Some(600));
+            assert_eq!(read_bridge_transfer(&deps.storage, 0).unwrap().deadline, Some(600));
+            assert_eq!(get_total_supply(&deps.storage), 60);
+
+            let res = cancel(deps.as_mut(), "addr0000", 601).unwrap();
+            assert_eq!(
+                res.events,
+                vec![events::bridge_out_cancelled("addr0000", 0, Uint128::from(40u128))]
+            );
+            let transfer: BridgeTransfer = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(transfer.status, BridgeTransferStatus::Cancelled);
+
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 100);
+            assert_eq!(get_total_supply(&deps.storage), 100);
+            assert_eq!(read_outbound_in_flight(&deps.storage).unwrap(), 0);
+            let pending_store = ReadonlyPrefixedStorage::new(&deps.storage, PREFIX_PENDING_OUTBOUND);
+            assert!(pending_store.get(&0u64.to_be_bytes()).is_none());
+        }
+
+        #[test]
+        fn cannot_cancel_before_deadline_or_without_one() {
+            let mut deps = setup(Some(600));
+            match cancel(deps.as_mut(), "addr0000", 600) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::DeadlineNotPassed { id: 0 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            let mut deps = setup(None);
+            match cancel(deps.as_mut(), "addr0000", 10_000) {
                 Ok(_) => panic!("expected error"),
-                Err(ContractError::NameWrongFormat {}) => {}
+                Err(ContractError::DeadlineNotPassed { id: 0 }) => {}
                 Err(e) => panic!("unexpected error: {:?}", e),
             }
         }
 
         #[test]
-        fn fails_for_symbol_too_short() {
+        fn is_disabled_by_default() {
+            let mut deps = setup(Some(600));
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: Some(false),
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 452, 570);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+            match cancel(deps.as_mut(), "addr0000", 601) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::SenderCancelDisabled {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
             let mut deps = mock_dependencies(&[]);
             let instantiate_msg = InstantiateMsg {
-                name: "De De".to_string(),
-                symbol: "DD".to_string(),
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
                 decimals: 9,
-                evm_contract: "abc".to_string(),
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
-            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
-            match result {
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            assert!(!read_bridge_config(&deps.storage).unwrap().sender_cancel_enabled);
+        }
+
+        #[test]
+        fn rejects_past_deadline() {
+            let mut deps = setup(None);
+            let send_msg = ExecuteMsg::SendToEvm {
+                recipient: EVM_RECIPIENT.to_string(),
+                amount: Uint128::from(10u128),
+                deadline: Some(570),
+            };
+            let (env, info) = mock_env_height("addr0000", 452, 570);
+            match execute(deps.as_mut(), env, info, send_msg) {
                 Ok(_) => panic!("expected error"),
-                Err(ContractError::TickerWrongSymbolFormat {}) => {}
+                Err(ContractError::InvalidDeadline { deadline: 570 }) => {}
                 Err(e) => panic!("unexpected error: {:?}", e),
             }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 60);
         }
 
         #[test]
-        fn fails_for_symbol_too_long() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
-                name: "Super Coin".to_string(),
-                symbol: "SUPERCOIN".to_string(),
-                decimals: 9,
-                evm_contract: "abc".to_string(),
-            };
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
-            match result {
+        fn only_sender_may_cancel() {
+            let mut deps = setup(Some(600));
+            match cancel(deps.as_mut(), "addr0001", 601) {
                 Ok(_) => panic!("expected error"),
-                Err(ContractError::TickerWrongSymbolFormat {}) => {}
+                Err(ContractError::Unauthorized {}) => {}
                 Err(e) => panic!("unexpected error: {:?}", e),
             }
         }
 
         #[test]
-        fn fails_for_symbol_lowercase() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CaSH".to_string(),
-                decimals: 9,
-                evm_contract: "abc".to_string(),
-            };
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
-            match result {
+        fn confirmed_and_cancelled_transfers_are_final() {
+            let mut deps = setup(Some(600));
+            let (env, info) = mock_env_height(MODULE_CALLER, 455, 580);
+            execute(deps.as_mut(), env, info, ExecuteMsg::ConfirmSendToEvm { id: 0 }).unwrap();
+            match cancel(deps.as_mut(), "addr0000", 601) {
                 Ok(_) => panic!("expected error"),
-                Err(ContractError::TickerWrongSymbolFormat {}) => {}
+                Err(ContractError::BridgeTransferNotPending { id: 0 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            let mut deps = setup(Some(600));
+            cancel(deps.as_mut(), "addr0000", 601).unwrap();
+            let (env, info) = mock_env_height(MODULE_CALLER, 461, 610);
+            match execute(deps.as_mut(), env, info, ExecuteMsg::ConfirmSendToEvm { id: 0 }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::BridgeTransferNotPending { id: 0 }) => {}
                 Err(e) => panic!("unexpected error: {:?}", e),
             }
+            assert_eq!(get_total_supply(&deps.storage), 100);
         }
     }
 
-    mod transfer {
+    mod guardians {
         use super::*;
         use crate::error::ContractError;
-        use cosmwasm_std::attr;
+        use crate::msg::{AttestMintResponse, MintCW20Response};
+        use crate::state::{GuardianSet, MintAttestation, RefundReason};
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
 
-        fn make_instantiate_msg() -> InstantiateMsg {
-            InstantiateMsg {
+        const EVM_CONTRACT: &str = "0xcd38b80aee05cad65571b7564bd110fdf2990de6";
+        const EVM_SENDER: &str = "0x2222222222222222222222222222222222222222";
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
                 name: "Cash Token".to_string(),
                 symbol: "CASH".to_string(),
                 decimals: 9,
-                evm_contract: "abc".to_string(),
-            }
-        }
+                evm_contract: EVM_CONTRACT.to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
 
-        #[test]
-        fn can_send_to_existing_recipient() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
+            let update_msg = ExecuteMsg::UpdateGuardians {
+                guardians: vec!["guard1".to_string(), "guard2".to_string(), "guard3".to_string()],
+                threshold: 2,
+            };
             let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Initial state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                0
-            );
-            assert_eq!(get_total_supply(&deps.storage), 0);
-            // Transfer
-            let transfer_msg = ExecuteMsg::Transfer {
-                recipient: "addr1111".to_string(),
-                amount: Uint128::from(0u128),
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+            deps
+        }
+
+        fn attest(
+            deps: DepsMut,
+            guardian: &str,
+            recipient: &str,
+            amount: u128,
+        ) -> Result<Response<SendToEvmMsg>, ContractError> {
+            attest_from(deps, guardian, recipient, amount, None)
+        }
+
+        fn attest_from(
+            deps: DepsMut,
+            guardian: &str,
+            recipient: &str,
+            amount: u128,
+            evm_sender: Option<&str>,
+        ) -> Result<Response<SendToEvmMsg>, ContractError> {
+            let attest_msg = ExecuteMsg::AttestMint {
+                event_id: "0xabc-1".to_string(),
+                recipient: recipient.to_string(),
+                amount: Uint128::from(amount),
+                evm_sender: evm_sender.map(|evm_sender| evm_sender.to_string()),
             };
-            let (env, info) = mock_env_height("addr0000", 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
-            assert_eq!(transfer_result.messages.len(), 0);
-            assert_eq!(
-                transfer_result.attributes,
-                vec![
-                    attr("action", "transfer"),
-                    attr("sender", "addr0000"),
-                    attr("recipient", "addr1111"),
-                ]
-            );
-            // New state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                0
-            ); // -1
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                0
-            ); // +1
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                0
-            );
-            assert_eq!(get_total_supply(&deps.storage), 0);
+            let (env, info) = mock_env_height(guardian, 451, 560);
+            execute(deps, env, info, attest_msg)
         }
 
-        #[test]
-        fn can_send_to_non_existent_recipient() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Initial state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                0
-            );
-            assert_eq!(get_total_supply(&deps.storage), 0);
-            // Transfer
-            let transfer_msg = ExecuteMsg::Transfer {
-                recipient: "addr2323".to_string(),
-                amount: Uint128::from(0u128),
+        fn freeze(deps: DepsMut, frozen: bool) {
+            let freeze_msg = if frozen {
+                ExecuteMsg::FreezeAccount {
+                    address: "addr0000".to_string(),
+                }
+            } else {
+                ExecuteMsg::UnfreezeAccount {
+                    address: "addr0000".to_string(),
+                }
             };
-            let (env, info) = mock_env_height("addr0000", 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
-            assert_eq!(transfer_result.messages.len(), 0);
-            assert_eq!(
-                transfer_result.attributes,
-                vec![
-                    attr("action", "transfer"),
-                    attr("sender", "addr0000"),
-                    attr("recipient", "addr2323"),
-                ]
-            );
-            // New state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr2323".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                0
-            );
-            assert_eq!(get_total_supply(&deps.storage), 0);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps, env, info, freeze_msg).unwrap();
         }
 
         #[test]
-        fn can_send_zero_amount() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Initial state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                0
-            );
+        fn mints_at_threshold() {
+            let mut deps = setup();
+            let res = attest(deps.as_mut(), "guard1", "addr0000", 30).unwrap();
+            let data: AttestMintResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.attestations, 1);
+            assert_eq!(data.result, None);
             assert_eq!(get_total_supply(&deps.storage), 0);
-            // Transfer
-            let transfer_msg = ExecuteMsg::Transfer {
-                recipient: "addr1111".to_string(),
-                amount: Uint128::from(0u128),
-            };
-            let (env, info) = mock_env_height("addr0000", 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
-            assert_eq!(transfer_result.messages.len(), 0);
+
+            let res = attest(deps.as_mut(), "guard2", "addr0000", 30).unwrap();
             assert_eq!(
-                transfer_result.attributes,
+                res.events,
                 vec![
-                    attr("action", "transfer"),
-                    attr("sender", "addr0000"),
-                    attr("recipient", "addr1111"),
+                    events::attest_mint("guard2", "0xabc-1", "addr0000", Uint128::from(30u128), 2),
+                    events::bridge_in("guard2", "addr0000", Uint128::from(30u128), Uint128::zero(), 0),
                 ]
             );
-            // New state (unchanged)
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                0
-            );
-            assert_eq!(get_total_supply(&deps.storage), 0);
-        }
-
-        #[test]
-        fn can_send_to_sender() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            let sender = "addr0000";
-            // Initial state
-            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(sender)), 0);
-            // Transfer
-            let transfer_msg = ExecuteMsg::Transfer {
-                recipient: sender.to_string(),
-                amount: Uint128::from(0u128),
+            let data: AttestMintResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.error, None);
+            let minted = match data.result.unwrap() {
+                MintOutcome::Minted(minted) => minted,
+                outcome => panic!("unexpected outcome: {:?}", outcome),
             };
-            let (env, info) = mock_env_height(&sender, 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
-            assert_eq!(transfer_result.messages.len(), 0);
             assert_eq!(
-                transfer_result.attributes,
-                vec![
-                    attr("action", "transfer"),
-                    attr("sender", "addr0000"),
-                    attr("recipient", "addr0000"),
-                ]
+                minted,
+                MintCW20Response {
+                    transfer_id: 0,
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(30u128),
+                    fee: Uint128::zero(),
+                    balance: Uint128::from(30u128),
+                    total_supply: Uint128::from(30u128),
+                }
             );
-            // New state
-            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(sender)), 0);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 30);
+
+            match attest(deps.as_mut(), "guard3", "addr0000", 30) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MintAlreadyProcessed { transfer_id: 0, .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_total_supply(&deps.storage), 30);
         }
 
         #[test]
-        fn fails_on_insufficient_balance() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Initial state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                0
-            );
+        fn conflicting_attestations_do_not_add_up() {
+            let mut deps = setup();
+            attest(deps.as_mut(), "guard1", "addr0000", 30).unwrap();
+            let res = attest(deps.as_mut(), "guard2", "addr0000", 31).unwrap();
+            let data: AttestMintResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.attestations, 1);
+            assert_eq!(get_total_supply(&deps.storage), 0);
+
+            let query_msg = QueryMsg::MintAttestation {
+                event_id: "0xabc-1".to_string(),
+            };
+            let attestation: MintAttestation = from_slice(&query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+            assert_eq!(attestation.votes.len(), 2);
+
+            attest(deps.as_mut(), "guard3", "addr0000", 31).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 31);
+        }
+
+        #[test]
+        fn blocked_mint_keeps_attestations() {
+            let mut deps = setup();
+            freeze(deps.as_mut(), true);
+            attest(deps.as_mut(), "guard1", "addr0000", 30).unwrap();
+            let res = attest(deps.as_mut(), "guard2", "addr0000", 30).unwrap();
+            let data: AttestMintResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.attestations, 2);
+            assert_eq!(data.result, None);
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                0
+                data.error,
+                Some(
+                    ContractError::AccountFrozen {
+                        address: "addr0000".to_string()
+                    }
+                    .to_string()
+                )
             );
+            // Only the votes were stored
             assert_eq!(get_total_supply(&deps.storage), 0);
-            // Transfer
-            let transfer_msg = ExecuteMsg::Transfer {
-                recipient: "addr1111".to_string(),
-                amount: Uint128::from(12u128),
+            assert!(read_bridge_transfer(&deps.storage, 0).is_err());
+            let query_msg = QueryMsg::MintAttestation {
+                event_id: "0xabc-1".to_string(),
             };
-            let (env, info) = mock_env_height("addr0000", 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg);
-            match transfer_result {
+            let attestation: MintAttestation = from_slice(&query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+            assert_eq!(attestation.votes.len(), 2);
+
+            freeze(deps.as_mut(), false);
+            match attest(deps.as_mut(), "guard1", "addr0000", 31) {
                 Ok(_) => panic!("expected error"),
-                Err(ContractError::InsufficientFunds {
-                    balance: 0,
-                    required: 12,
-                }) => {}
+                Err(ContractError::AlreadyAttested { .. }) => {}
                 Err(e) => panic!("unexpected error: {:?}", e),
             }
-            // New state (unchanged)
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                0
-            );
+            let res = attest(deps.as_mut(), "guard1", "addr0000", 30).unwrap();
+            let data: AttestMintResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.attestations, 2);
+            assert!(data.result.is_some());
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 30);
+        }
+
+        #[test]
+        fn attested_mint_is_refunded() {
+            let mut deps = setup();
+            freeze(deps.as_mut(), true);
+            attest_from(deps.as_mut(), "guard1", "addr0000", 30, Some(EVM_SENDER)).unwrap();
+            // The sender is part of the vote, a different one does not add up
+            let res = attest(deps.as_mut(), "guard2", "addr0000", 30).unwrap();
+            let data: AttestMintResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.attestations, 1);
+
+            let res = attest_from(deps.as_mut(), "guard3", "addr0000", 30, Some(EVM_SENDER)).unwrap();
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                0
+                res.events,
+                vec![
+                    events::attest_mint("guard3", "0xabc-1", "addr0000", Uint128::from(30u128), 2),
+                    events::bridge_refund("guard3", EVM_SENDER, Uint128::from(30u128), 0, "recipient_frozen"),
+                ]
             );
+            assert_eq!(res.messages.len(), 1);
+            let data: AttestMintResponse = from_slice(&res.data.unwrap()).unwrap();
+            let refund = match data.result.unwrap() {
+                MintOutcome::Refunded(refund) => refund,
+                outcome => panic!("unexpected outcome: {:?}", outcome),
+            };
+            assert_eq!(refund.reason, RefundReason::RecipientFrozen);
             assert_eq!(get_total_supply(&deps.storage), 0);
+
+            match attest_from(deps.as_mut(), "guard1", "addr0000", 30, Some(EVM_SENDER)) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MintAlreadyProcessed { transfer_id: 0, .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
         }
-    }
 
-    mod approve {
-        use super::*;
-        use cosmwasm_std::attr;
+        #[test]
+        fn large_attested_mint_is_held() {
+            let mut deps = setup();
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: Some(Uint128::from(100u128)),
+                challenge_period: Some(10),
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+
+            attest(deps.as_mut(), "guard1", "addr0000", 200).unwrap();
+            let res = attest(deps.as_mut(), "guard2", "addr0000", 200).unwrap();
+            let data: AttestMintResponse = from_slice(&res.data.unwrap()).unwrap();
+            let pending = match data.result.unwrap() {
+                MintOutcome::Pending(pending) => pending,
+                outcome => panic!("unexpected outcome: {:?}", outcome),
+            };
+            assert_eq!(pending.event_id, Some("0xabc-1".to_string()));
+            assert_eq!(pending.finalize_height, 461);
+            assert_eq!(get_total_supply(&deps.storage), 0);
 
-        fn make_instantiate_msg() -> InstantiateMsg {
-            InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                evm_contract: "abc".to_string(),
+            match attest(deps.as_mut(), "guard3", "addr0000", 200) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MintPending { id: 0, .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
             }
         }
 
-        fn make_spender() -> Addr {
-            Addr::unchecked("dadadadadadadada".to_string())
+        #[test]
+        fn guardian_attests_once() {
+            let mut deps = setup();
+            attest(deps.as_mut(), "guard1", "addr0000", 30).unwrap();
+            match attest(deps.as_mut(), "guard1", "addr0000", 30) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::AlreadyAttested { .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            match attest(deps.as_mut(), MODULE_CALLER, "addr0000", 30) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_total_supply(&deps.storage), 0);
         }
 
         #[test]
-        fn has_zero_allowance_by_default() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Existing owner
-            assert_eq!(
-                get_allowance(&deps.storage, &Addr::unchecked("addr0000"), &make_spender()),
-                0
-            );
-            // Non-existing owner
-            assert_eq!(
-                get_allowance(
-                    &deps.storage,
-                    &Addr::unchecked("addr4567".to_string()),
-                    &make_spender()
-                ),
-                0
-            );
+        fn direct_mints_need_attestations() {
+            let mut deps = setup();
+            let mint_msg = || ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(30u128),
+                event_id: None,
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            match execute(deps.as_mut(), env.clone(), info.clone(), mint_msg()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::GuardianAttestationRequired {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            let update_msg = ExecuteMsg::UpdateGuardians {
+                guardians: vec![],
+                threshold: 0,
+            };
+            let (owner_env, owner_info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), owner_env, owner_info, update_msg).unwrap();
+            execute(deps.as_mut(), env, info, mint_msg()).unwrap();
+            assert_eq!(get_total_supply(&deps.storage), 30);
         }
 
         #[test]
-        fn can_set_allowance() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            assert_eq!(
-                get_allowance(
-                    &deps.storage,
-                    &Addr::unchecked("addr7654".to_string()),
-                    &make_spender()
-                ),
-                0
-            );
-            // First approval
-            let owner = Addr::unchecked("addr7654".to_string());
-            let spender = make_spender();
-            let approve_msg1 = ExecuteMsg::Approve {
-                spender: spender.clone().to_string().to_string(),
-                amount: Uint128::from(334422u128),
+        fn update_resets_attestations() {
+            let mut deps = setup();
+            attest(deps.as_mut(), "guard1", "addr0000", 30).unwrap();
+
+            let update_msg = ExecuteMsg::UpdateGuardians {
+                guardians: vec!["guard1".to_string(), "guard2".to_string()],
+                threshold: 2,
             };
-            let (env, info) = mock_env_height(&owner.as_str(), 450, 550);
-            let approve_result1 = execute(deps.as_mut(), env, info, approve_msg1).unwrap();
-            assert_eq!(approve_result1.messages.len(), 0);
-            assert_eq!(
-                approve_result1.attributes,
-                vec![
-                    attr("action", "approve"),
-                    attr("owner", owner.clone().to_string()),
-                    attr("spender", spender.clone().to_string()),
-                ]
-            );
-            assert_eq!(
-                get_allowance(&deps.storage, &owner, &make_spender()),
-                334422
-            );
-            // Updated approval
-            let approve_msg = ExecuteMsg::Approve {
-                spender: spender.clone().to_string().to_string(),
-                amount: Uint128::from(777888u128),
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+
+            let res = attest(deps.as_mut(), "guard2", "addr0000", 30).unwrap();
+            let data: AttestMintResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.attestations, 1);
+            assert_eq!(get_total_supply(&deps.storage), 0);
+
+            let guardians: GuardianSet = from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::Guardians {}).unwrap()).unwrap();
+            assert_eq!(guardians.set_id, 2);
+            assert_eq!(guardians.threshold, 2);
+        }
+
+        #[test]
+        fn rejects_invalid_sets() {
+            let mut deps = setup();
+            for (guardians, threshold) in [(vec!["guard1", "guard2"], 3), (vec!["guard1", "guard2"], 0), (vec!["guard1", "guard1"], 1)] {
+                let update_msg = ExecuteMsg::UpdateGuardians {
+                    guardians: guardians.iter().map(|g| g.to_string()).collect(),
+                    threshold,
+                };
+                let (env, info) = mock_env_height("creator", 450, 550);
+                match execute(deps.as_mut(), env, info, update_msg) {
+                    Ok(_) => panic!("expected error"),
+                    Err(ContractError::InvalidGuardianSet {}) => {}
+                    Err(e) => panic!("unexpected error: {:?}", e),
+                }
+            }
+            let update_msg = ExecuteMsg::UpdateGuardians {
+                guardians: vec!["guard1".to_string()],
+                threshold: 1,
             };
-            let (env, info) = mock_env_height(&owner.as_str(), 450, 550);
-            let approve_result2 = execute(deps.as_mut(), env, info, approve_msg).unwrap();
-            assert_eq!(approve_result2.messages.len(), 0);
-            assert_eq!(
-                approve_result2.attributes,
-                vec![
-                    attr("action", "approve"),
-                    attr("owner", owner.as_str()),
-                    attr("spender", spender.as_str()),
-                ]
-            );
-            assert_eq!(get_allowance(&deps.storage, &owner, &spender), 777888);
+            let (env, info) = mock_env_height("guard1", 450, 550);
+            match execute(deps.as_mut(), env, info, update_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
         }
     }
 
-    mod transfer_from {
+    mod timelock {
         use super::*;
         use crate::error::ContractError;
-        use cosmwasm_std::{attr, Addr};
+        use crate::state::BridgeTransferStatus;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
 
-        fn make_instantiate_msg() -> InstantiateMsg {
-            InstantiateMsg {
+        const EVM_CONTRACT: &str = "0xcd38b80aee05cad65571b7564bd110fdf2990de6";
+        const EVM_RECIPIENT: &str = "0x1111111111111111111111111111111111111111";
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
                 name: "Cash Token".to_string(),
                 symbol: "CASH".to_string(),
                 decimals: 9,
-                evm_contract: "abc".to_string(),
-            }
+                evm_contract: EVM_CONTRACT.to_string(),
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(1000u128),
+                }],
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: Some(Uint128::from(100u128)),
+                timelock_delay: Some(3600),
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+
+            let guardians_msg = ExecuteMsg::UpdateGuardians {
+                guardians: vec!["guard1".to_string()],
+                threshold: 1,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, guardians_msg).unwrap();
+            deps
         }
 
-        fn make_spender() -> Addr {
-            Addr::unchecked("dadadadadadadada".to_string())
+        fn send(deps: DepsMut, amount: u128) -> Result<Response<SendToEvmMsg>, ContractError> {
+            let send_msg = ExecuteMsg::SendToEvm {
+                recipient: EVM_RECIPIENT.to_string(),
+                amount: Uint128::from(amount),
+                deadline: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            execute(deps, env, info, send_msg)
         }
 
-        #[test]
-        fn works() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            let owner = "addr0000";
-            let spender = make_spender();
-            let recipient = Addr::unchecked("addr1212".to_string());
-            // Set approval
-            let approve_msg = ExecuteMsg::Approve {
-                spender: spender.clone().to_string().to_string(),
-                amount: Uint128::from(4u128),
+        fn queued(deps: Deps) -> Vec<u64> {
+            let query_msg = QueryMsg::QueuedOutbound {
+                start_after: None,
+                limit: None,
             };
-            let (env, info) = mock_env_height(&owner.clone(), 450, 550);
-            let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
-            assert_eq!(approve_result.messages.len(), 0);
-            assert_eq!(
-                approve_result.attributes,
-                vec![
-                    attr("action", "approve"),
-                    attr("owner", owner.clone().to_string()),
-                    attr("spender", spender.clone().to_string()),
-                ]
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked(owner.clone())),
-                0
-            );
-            assert_eq!(
-                get_allowance(&deps.storage, &Addr::unchecked(owner.clone()), &spender),
-                4
-            );
-            // Transfer less than allowance but more than balance
-            let transfer_from_msg = ExecuteMsg::TransferFrom {
-                owner: owner.clone().to_string().to_string(),
-                recipient: recipient.clone().to_string(),
-                amount: Uint128::from(0u128),
+            let response: BridgeTransfersResponse = from_slice(&query(deps, mock_env(), query_msg).unwrap()).unwrap();
+            response.transfers.iter().map(|transfer| transfer.id).collect()
+        }
+
+        #[test]
+        fn small_send_is_not_queued() {
+            let mut deps = setup();
+            let res = send(deps.as_mut(), 100).unwrap();
+            assert_eq!(res.messages.len(), 1);
+            assert!(queued(deps.as_ref()).is_empty());
+            assert_eq!(read_outbound_in_flight(&deps.storage).unwrap(), 100);
+        }
+
+        #[test]
+        fn deadline_must_follow_release_time() {
+            let mut deps = setup();
+            let send_msg = |deadline: u64| ExecuteMsg::SendToEvm {
+                recipient: EVM_RECIPIENT.to_string(),
+                amount: Uint128::from(200u128),
+                deadline: Some(deadline),
             };
-            let (env, info) = mock_env_height(&spender.as_str(), 450, 550);
-            let transfer_from_result =
-                execute(deps.as_mut(), env, info, transfer_from_msg).unwrap();
-            assert_eq!(transfer_from_result.messages.len(), 0);
-            assert_eq!(
-                transfer_from_result.attributes,
-                vec![
-                    attr("action", "transfer_from"),
-                    attr("spender", spender.clone()),
-                    attr("sender", owner),
-                    attr("recipient", recipient),
-                ]
-            );
-            // State changed
-            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(owner)), 0);
-            assert_eq!(
-                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
-                4
-            );
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            match execute(deps.as_mut(), env, info, send_msg(4160)) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::DeadlineBeforeRelease { deadline, release_time }) => {
+                    assert_eq!((deadline, release_time), (4160, 4160))
+                }
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 1000);
+            assert!(queued(deps.as_ref()).is_empty());
+
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            execute(deps.as_mut(), env, info, send_msg(4161)).unwrap();
+            assert_eq!(queued(deps.as_ref()), vec![0]);
         }
 
         #[test]
-        fn fails_when_allowance_too_low() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            let owner = "addr0000";
-            let spender = make_spender();
-            let recipient = Addr::unchecked("addr1212".to_string());
-            // Set approval
-            let approve_msg = ExecuteMsg::Approve {
-                spender: spender.clone().to_string(),
-                amount: Uint128::from(2u128),
+        fn release_time_overflow_is_an_error() {
+            let mut deps = setup();
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: Some(u64::MAX),
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
             };
-            let (env, info) = mock_env_height(&owner.clone(), 450, 550);
-            let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
-            assert_eq!(approve_result.messages.len(), 0);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+
+            match send(deps.as_mut(), 200) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Std(StdError::Overflow { .. })) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 1000);
+            assert_eq!(get_total_supply(&deps.storage), 1000);
+        }
+
+        #[test]
+        fn large_send_is_released_after_delay() {
+            let mut deps = setup();
+            let res = send(deps.as_mut(), 200).unwrap();
+            assert!(res.messages.is_empty());
+            assert_eq!(
+                res.events,
+                vec![events::bridge_out_queued("addr0000", EVM_RECIPIENT, Uint128::from(200u128), Uint128::zero(), 0, 4160)]
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 800);
+            assert_eq!(get_total_supply(&deps.storage), 800);
+            assert_eq!(read_outbound_in_flight(&deps.storage).unwrap(), 200);
+            assert_eq!(queued(deps.as_ref()), vec![0]);
+
+            let (env, info) = mock_env_height("anyone", 460, 4159);
+            match execute(deps.as_mut(), env, info, ExecuteMsg::ExecuteQueued { id: 0 }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::TimelockNotExpired { release_time: 4160, .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            let (env, info) = mock_env_height("anyone", 461, 4160);
+            let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ExecuteQueued { id: 0 }).unwrap();
+            assert_eq!(
+                res.messages[0].msg,
+                CosmosMsg::Custom(SendToEvmMsg {
+                    sender: env.contract.address.to_string(),
+                    contract: EVM_CONTRACT.to_string(),
+                    recipient: EVM_RECIPIENT.to_string(),
+                    amount: Uint128::from(200u128),
+                    id: 0,
+                })
+            );
             assert_eq!(
-                approve_result.attributes,
-                vec![
-                    attr("action", "approve"),
-                    attr("owner", owner.clone().to_string()),
-                    attr("spender", spender.clone().to_string()),
-                ]
+                res.events,
+                vec![events::bridge_out_released("anyone", 0, Uint128::from(200u128))]
             );
-            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(owner)), 0);
+            let transfer: BridgeTransfer = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(transfer.status, BridgeTransferStatus::Pending);
+            assert_eq!(read_outbound_in_flight(&deps.storage).unwrap(), 200);
+            assert!(queued(deps.as_ref()).is_empty());
+        }
+
+        #[test]
+        fn guardian_cancels_before_release() {
+            let mut deps = setup();
+            send(deps.as_mut(), 200).unwrap();
+
+            let (env, info) = mock_env_height("addr0000", 460, 600);
+            match execute(deps.as_mut(), env, info, ExecuteMsg::CancelQueued { id: 0 }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+
+            let (env, info) = mock_env_height("guard1", 460, 600);
+            let res = execute(deps.as_mut(), env, info, ExecuteMsg::CancelQueued { id: 0 }).unwrap();
             assert_eq!(
-                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
-                2
+                res.events,
+                vec![events::bridge_out_cancelled("guard1", 0, Uint128::from(200u128))]
             );
-            // Transfer less than allowance but more than balance
-            let fransfer_from_msg = ExecuteMsg::TransferFrom {
-                owner: owner.clone().to_string(),
-                recipient: recipient.clone().to_string(),
-                amount: Uint128::from(3u128),
-            };
-            let (env, info) = mock_env_height(&spender.as_str(), 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, fransfer_from_msg);
-            match transfer_result {
+            let transfer: BridgeTransfer = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(transfer.status, BridgeTransferStatus::Cancelled);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 1000);
+            assert_eq!(get_total_supply(&deps.storage), 1000);
+            assert_eq!(read_outbound_in_flight(&deps.storage).unwrap(), 0);
+            assert!(queued(deps.as_ref()).is_empty());
+
+            let (env, info) = mock_env_height("anyone", 461, 4160);
+            match execute(deps.as_mut(), env, info, ExecuteMsg::ExecuteQueued { id: 0 }) {
                 Ok(_) => panic!("expected error"),
-                Err(ContractError::InsufficientAllowance {
-                    allowance: 2,
-                    required: 3,
-                }) => {}
+                Err(ContractError::BridgeTransferNotQueued { id: 0 }) => {}
                 Err(e) => panic!("unexpected error: {:?}", e),
             }
         }
 
         #[test]
-        fn fails_when_allowance_is_set_but_balance_too_low() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            let owner = "addr0000";
-            let spender = make_spender();
-            let recipient = Addr::unchecked("addr1212".to_string());
-            // Set approval
-            let approve_msg = ExecuteMsg::Approve {
-                spender: spender.clone().to_string(),
-                amount: Uint128::from(100u128),
+        fn queued_send_is_backed_by_reserves() {
+            let mut deps = setup();
+            send(deps.as_mut(), 200).unwrap();
+
+            let report_msg = ExecuteMsg::ReportReserves {
+                reserves: Uint128::from(1000u128),
+                evm_block: 1000,
             };
-            let (env, info) = mock_env_height(&owner.clone(), 450, 550);
-            let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
-            assert_eq!(approve_result.messages.len(), 0);
-            assert_eq!(
-                approve_result.attributes,
-                vec![
-                    attr("action", "approve"),
-                    attr("owner", owner.clone().to_string()),
-                    attr("spender", spender.clone().to_string()),
-                ]
-            );
-            assert_eq!(get_balance(&deps.storage, &Addr::unchecked(owner)), 0);
-            assert_eq!(
-                get_allowance(&deps.storage, &Addr::unchecked(owner), &spender),
-                100
-            );
-            // Transfer less than allowance but more than balance
-            let fransfer_from_msg = ExecuteMsg::TransferFrom {
-                owner: owner.clone().to_string(),
-                recipient: recipient.clone().to_string(),
-                amount: Uint128::from(100u128),
+            let (env, info) = mock_env_height("creator", 460, 600);
+            execute(deps.as_mut(), env, info, report_msg).unwrap();
+            let status: ReserveStatusResponse =
+                from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::ReserveStatus {}).unwrap()).unwrap();
+            let report = status.last_report.unwrap();
+            assert_eq!(report.total_supply, Uint128::from(800u128));
+            assert_eq!(report.outbound_in_flight, Uint128::from(200u128));
+            assert_eq!(report.surplus, Uint128::zero());
+            assert_eq!(report.shortfall, Uint128::zero());
+        }
+
+        #[test]
+        fn cannot_cancel_after_release_time() {
+            let mut deps = setup();
+            send(deps.as_mut(), 200).unwrap();
+            let (env, info) = mock_env_height("guard1", 460, 4160);
+            match execute(deps.as_mut(), env, info, ExecuteMsg::CancelQueued { id: 0 }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::TimelockExpired { .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn batch_cannot_skip_timelock() {
+            let mut deps = setup();
+            let batch_msg = ExecuteMsg::BatchSendToEvm {
+                transfers: vec![TransferItem {
+                    recipient: EVM_RECIPIENT.to_string(),
+                    amount: Uint128::from(200u128),
+                }],
             };
-            let (env, info) = mock_env_height(&spender.as_str(), 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, fransfer_from_msg);
-            match transfer_result {
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            match execute(deps.as_mut(), env, info, batch_msg) {
                 Ok(_) => panic!("expected error"),
-                Err(ContractError::InsufficientFunds {
-                    balance: 0,
-                    required: 100,
-                }) => {}
+                Err(ContractError::TimelockRequired { amount: 200, threshold: 100 }) => {}
                 Err(e) => panic!("unexpected error: {:?}", e),
             }
         }
     }
 
-    mod burn {
+    mod optimistic_mints {
         use super::*;
         use crate::error::ContractError;
-        use cosmwasm_std::{attr, Addr};
+        use crate::msg::{BatchMintCW20Response, MintItem, MintItemStatus, PendingMintsResponse};
+        use crate::state::{PendingMint, PendingMintStatus, RefundReason};
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
 
-        fn make_instantiate_msg() -> InstantiateMsg {
-            InstantiateMsg {
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+        const EVM_SENDER: &str = "0x2222222222222222222222222222222222222222";
+
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
                 name: "Cash Token".to_string(),
                 symbol: "CASH".to_string(),
                 decimals: 9,
-                evm_contract: "abc".to_string(),
-            }
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: Some(Uint128::from(100u128)),
+                challenge_period: Some(10),
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+
+            let grant_msg = ExecuteMsg::GrantRole {
+                role: Role::Watcher,
+                address: "watcher".to_string(),
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, grant_msg).unwrap();
+            deps
+        }
+
+        fn mint(deps: DepsMut, amount: u128) -> Response<SendToEvmMsg> {
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(amount),
+                event_id: None,
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps, env, info, mint_msg).unwrap()
+        }
+
+        fn mint_from_evm(deps: DepsMut, amount: u128) -> Response<SendToEvmMsg> {
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(amount),
+                event_id: Some("0xaa:0".to_string()),
+                evm_sender: Some(EVM_SENDER.to_string()),
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            execute(deps, env, info, mint_msg).unwrap()
+        }
+
+        fn run(deps: DepsMut, sender: &str, height: u64, msg: ExecuteMsg) -> Result<Response<SendToEvmMsg>, ContractError> {
+            let (env, info) = mock_env_height(sender, height, 600);
+            execute(deps, env, info, msg)
+        }
+
+        fn pending_mints(deps: Deps) -> Vec<PendingMint> {
+            let query_msg = QueryMsg::PendingMints {
+                start_after: None,
+                limit: None,
+            };
+            let response: PendingMintsResponse = from_slice(&query(deps, mock_env(), query_msg).unwrap()).unwrap();
+            response.mints
         }
 
         #[test]
-        fn can_burn_zero_amount() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Initial state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                0
-            );
-            assert_eq!(get_total_supply(&deps.storage), 0);
-            // Burn
-            let burn_msg = ExecuteMsg::Burn {
-                amount: Uint128::from(0u128),
+        fn small_mint_is_credited_at_once() {
+            let mut deps = setup();
+            mint(deps.as_mut(), 100);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 100);
+            assert!(pending_mints(deps.as_ref()).is_empty());
+        }
+
+        #[test]
+        fn finalize_height_overflow_is_an_error() {
+            let mut deps = setup();
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: None,
+                timelock_delay: None,
+                optimistic_threshold: None,
+                challenge_period: Some(u64::MAX),
+                sender_cancel_enabled: None,
+                claim_by_sender: None,
             };
-            let (env, info) = mock_env_height("addr0000", 450, 550);
-            let burn_result = execute(deps.as_mut(), env, info, burn_msg).unwrap();
-            assert_eq!(burn_result.messages.len(), 0);
-            assert_eq!(
-                burn_result.attributes,
-                vec![
-                    attr("action", "burn"),
-                    attr("account", "addr0000"),
-                    attr("amount", "0"),
-                ]
-            );
-            // New state (unchanged)
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                0
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                0
-            );
+            run(deps.as_mut(), "creator", 450, update_msg).unwrap();
+
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(200u128),
+                event_id: None,
+                evm_sender: None,
+            };
+            match run(deps.as_mut(), MODULE_CALLER, 451, mint_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Std(StdError::Overflow { .. })) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert!(pending_mints(deps.as_ref()).is_empty());
             assert_eq!(get_total_supply(&deps.storage), 0);
         }
 
         #[test]
-        fn fails_on_insufficient_balance() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("creator", 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            // Initial state
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                0
-            );
+        fn large_mint_finalizes_after_period() {
+            let mut deps = setup();
+            let res = mint(deps.as_mut(), 200);
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                0
+                res.events,
+                vec![events::mint_pending(MODULE_CALLER, "addr0000", Uint128::from(200u128), 0, 461)]
             );
             assert_eq!(get_total_supply(&deps.storage), 0);
-            // Burn
-            let burn_msg = ExecuteMsg::Burn {
-                amount: Uint128::from(12u128),
-            };
-            let (env, info) = mock_env_height("addr0000", 450, 550);
-            let burn_result = execute(deps.as_mut(), env, info, burn_msg);
-            match burn_result {
+            assert_eq!(pending_mints(deps.as_ref()).len(), 1);
+
+            match run(deps.as_mut(), "anyone", 460, ExecuteMsg::FinalizeMint { id: 0 }) {
                 Ok(_) => panic!("expected error"),
-                Err(ContractError::InsufficientFunds {
-                    balance: 0,
-                    required: 12,
-                }) => {}
+                Err(ContractError::ChallengePeriodActive { finalize_height: 461, .. }) => {}
                 Err(e) => panic!("unexpected error: {:?}", e),
             }
-            // New state (unchanged)
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
-                0
-            );
+
+            let res = run(deps.as_mut(), "anyone", 461, ExecuteMsg::FinalizeMint { id: 0 }).unwrap();
             assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                0
+                res.events,
+                vec![events::bridge_in("anyone", "addr0000", Uint128::from(200u128), Uint128::zero(), 0)]
             );
-            assert_eq!(get_total_supply(&deps.storage), 0);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 200);
+            assert_eq!(get_total_supply(&deps.storage), 200);
+            assert!(pending_mints(deps.as_ref()).is_empty());
         }
-    }
-
-    mod query {
-        use super::*;
-        use cosmwasm_std::{attr, Addr};
 
-        fn address(index: u8) -> Addr {
-            match index {
-                0 => Addr::unchecked("addr0000".to_string()), // contract instantiateializer
-                1 => Addr::unchecked("addr1111".to_string()),
-                2 => Addr::unchecked("addr4321".to_string()),
-                3 => Addr::unchecked("addr5432".to_string()),
-                4 => Addr::unchecked("addr6543".to_string()),
-                _ => panic!("Unsupported address index"),
+        #[test]
+        fn held_event_is_not_minted_twice() {
+            let mut deps = setup();
+            let mint_msg = || ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(200u128),
+                event_id: Some("0xaa:0".to_string()),
+                evm_sender: None,
+            };
+            run(deps.as_mut(), MODULE_CALLER, 451, mint_msg()).unwrap();
+            match run(deps.as_mut(), MODULE_CALLER, 452, mint_msg()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MintPending { id: 0, .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
             }
-        }
 
-        fn make_instantiate_msg() -> InstantiateMsg {
-            InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                evm_contract: "abc".to_string(),
+            run(deps.as_mut(), "anyone", 461, ExecuteMsg::FinalizeMint { id: 0 }).unwrap();
+            match run(deps.as_mut(), MODULE_CALLER, 462, mint_msg()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MintAlreadyProcessed { transfer_id: 0, .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
             }
+            assert_eq!(get_total_supply(&deps.storage), 200);
         }
 
         #[test]
-        fn can_query_balance_of_existing_address() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height(&address(0).as_str(), 450, 550);
-            let res = instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            let query_msg = QueryMsg::Balance {
-                address: address(1).to_string(),
-            };
-            let query_result = query(deps.as_ref(), env, query_msg).unwrap();
-            assert_eq!(query_result.as_slice(), b"{\"balance\":\"0\"}");
+        fn challenged_mint_waits_for_owner() {
+            let mut deps = setup();
+            mint(deps.as_mut(), 200);
+
+            match run(deps.as_mut(), "addr0000", 455, ExecuteMsg::Challenge { id: 0 }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            let res = run(deps.as_mut(), "watcher", 455, ExecuteMsg::Challenge { id: 0 }).unwrap();
+            assert_eq!(res.events, vec![events::mint_challenged("watcher", 0)]);
+            let pending: PendingMint = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(pending.status, PendingMintStatus::Challenged);
+            assert_eq!(pending.challenger, Some(Addr::unchecked("watcher")));
+
+            match run(deps.as_mut(), "anyone", 470, ExecuteMsg::FinalizeMint { id: 0 }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MintChallenged { id: 0 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            let resolve_msg = || ExecuteMsg::ResolveMint { id: 0, approve: true };
+            match run(deps.as_mut(), "watcher", 470, resolve_msg()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            run(deps.as_mut(), "creator", 470, resolve_msg()).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 200);
+            assert!(pending_mints(deps.as_ref()).is_empty());
         }
 
         #[test]
-        fn can_query_balance_of_nonexisting_address() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height(&address(0).as_str(), 450, 550);
-            let res = instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            let query_msg = QueryMsg::Balance {
-                address: address(4).to_string(), // only indices 1, 2, 3 are instantiateialized
-            };
-            let query_result = query(deps.as_ref(), env, query_msg).unwrap();
-            assert_eq!(query_result.as_slice(), b"{\"balance\":\"0\"}");
+        fn rejected_mint_is_dropped() {
+            let mut deps = setup();
+            mint(deps.as_mut(), 200);
+            run(deps.as_mut(), "watcher", 455, ExecuteMsg::Challenge { id: 0 }).unwrap();
+
+            let res = run(deps.as_mut(), "creator", 470, ExecuteMsg::ResolveMint { id: 0, approve: false }).unwrap();
+            assert_eq!(
+                res.events,
+                vec![events::mint_rejected("creator", 0, Uint128::from(200u128))]
+            );
+            assert!(res.messages.is_empty());
+            match from_slice(&res.data.unwrap()).unwrap() {
+                MintOutcome::Rejected(pending) => assert_eq!(pending.status, PendingMintStatus::Challenged),
+                outcome => panic!("unexpected outcome: {:?}", outcome),
+            }
+            assert_eq!(get_total_supply(&deps.storage), 0);
+            assert!(pending_mints(deps.as_ref()).is_empty());
+            match run(deps.as_mut(), "anyone", 470, ExecuteMsg::FinalizeMint { id: 0 }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Std(StdError::NotFound { .. })) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
         }
 
         #[test]
-        fn can_query_allowance_of_existing_addresses() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height(&address(0).as_str(), 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            let owner = address(2);
-            let spender = address(1);
-            let approve_msg = ExecuteMsg::Approve {
-                spender: spender.clone().to_string(),
-                amount: Uint128::from(42u128),
+        fn frozen_during_hold_is_refunded() {
+            let mut deps = setup();
+            mint(deps.as_mut(), 200);
+            mint_from_evm(deps.as_mut(), 300);
+            let freeze_msg = || ExecuteMsg::FreezeAccount {
+                address: "addr0000".to_string(),
             };
-            let (env, info) = mock_env_height(&owner.as_str(), 450, 550);
-            let action_result = execute(deps.as_mut(), env.clone(), info, approve_msg).unwrap();
-            assert_eq!(action_result.messages.len(), 0);
+            run(deps.as_mut(), "creator", 455, freeze_msg()).unwrap();
+
+            // Without an EVM sender the mint waits for the recipient to be unfrozen
+            match run(deps.as_mut(), "anyone", 461, ExecuteMsg::FinalizeMint { id: 0 }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::AccountFrozen { .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(pending_mints(deps.as_ref()).len(), 2);
+
+            let res = run(deps.as_mut(), "anyone", 461, ExecuteMsg::FinalizeMint { id: 1 }).unwrap();
             assert_eq!(
-                action_result.attributes,
-                vec![
-                    attr("action", "approve"),
-                    attr("owner", owner.clone().to_string()),
-                    attr("spender", spender.clone().to_string()),
-                ]
+                res.events,
+                vec![events::bridge_refund("anyone", EVM_SENDER, Uint128::from(300u128), 0, "recipient_frozen")]
             );
-            let query_msg = QueryMsg::Allowance {
-                owner: owner.clone().to_string(),
-                spender: spender.clone().to_string(),
+            assert_eq!(res.messages.len(), 1);
+            let refund = match from_slice(&res.data.unwrap()).unwrap() {
+                MintOutcome::Refunded(refund) => refund,
+                outcome => panic!("unexpected outcome: {:?}", outcome),
             };
-            let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-            assert_eq!(query_result.as_slice(), b"{\"allowance\":\"42\"}");
+            assert_eq!(refund.reason, RefundReason::RecipientFrozen);
+            assert_eq!(refund.event_id, Some("0xaa:0".to_string()));
+            assert_eq!(get_total_supply(&deps.storage), 0);
+            assert_eq!(pending_mints(deps.as_ref()).len(), 1);
         }
 
         #[test]
-        fn can_query_allowance_of_nonexisting_owner() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height(&address(0).as_str(), 450, 550);
-            let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
-            let owner = address(2);
-            let spender = address(1);
-            let bob = address(3);
-            let approve_msg = ExecuteMsg::Approve {
-                spender: spender.clone().to_string(),
-                amount: Uint128::from(42u128),
-            };
-            let (env, info) = mock_env_height(&owner.as_str(), 450, 550);
-            let approve_result = execute(deps.as_mut(), env.clone(), info, approve_msg).unwrap();
-            assert_eq!(approve_result.messages.len(), 0);
+        fn rejected_mint_is_refunded_to_evm_sender() {
+            let mut deps = setup();
+            mint_from_evm(deps.as_mut(), 200);
+            run(deps.as_mut(), "watcher", 455, ExecuteMsg::Challenge { id: 0 }).unwrap();
+
+            let res = run(deps.as_mut(), "creator", 470, ExecuteMsg::ResolveMint { id: 0, approve: false }).unwrap();
             assert_eq!(
-                approve_result.attributes,
+                res.events,
                 vec![
-                    attr("action", "approve"),
-                    attr("owner", owner.clone().to_string()),
-                    attr("spender", spender.clone().to_string()),
+                    events::mint_rejected("creator", 0, Uint128::from(200u128)),
+                    events::bridge_refund("creator", EVM_SENDER, Uint128::from(200u128), 0, "rejected"),
                 ]
             );
-            // different spender
-            let query_msg = QueryMsg::Allowance {
-                owner: owner.clone().to_string(),
-                spender: bob.clone().to_string(),
+            assert_eq!(res.messages.len(), 1);
+            let refund = match from_slice(&res.data.unwrap()).unwrap() {
+                MintOutcome::Refunded(refund) => refund,
+                outcome => panic!("unexpected outcome: {:?}", outcome),
             };
-            let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-            assert_eq!(query_result.as_slice(), b"{\"allowance\":\"0\"}");
-            // differnet owner
-            let query_msg = QueryMsg::Allowance {
-                owner: bob.clone().to_string(),
-                spender: spender.clone().to_string(),
+            assert_eq!(refund.reason, RefundReason::Rejected);
+            assert_eq!(get_total_supply(&deps.storage), 0);
+            assert!(pending_mints(deps.as_ref()).is_empty());
+
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(200u128),
+                event_id: Some("0xaa:0".to_string()),
+                evm_sender: Some(EVM_SENDER.to_string()),
             };
-            let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-            assert_eq!(query_result.as_slice(), b"{\"allowance\":\"0\"}");
+            match run(deps.as_mut(), MODULE_CALLER, 471, mint_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MintAlreadyProcessed { transfer_id: 0, .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
         }
-    }
-    
-    // mod bridge {
-    //     use super::*;
-    //     use cosmwasm_std::{attr, Addr};
 
-    //     fn address(index: u8) -> Addr {
-    //         match index {
-    //             0 => Addr::unchecked("addr0000".to_string()), // contract instantiateializer
-    //             1 => Addr::unchecked("addr1111".to_string()),
-    //             2 => Addr::unchecked("addr4321".to_string()),
-    //             3 => Addr::unchecked("addr5432".to_string()),
-    //             4 => Addr::unchecked("addr6543".to_string()),
-    //             _ => panic!("Unsupported address index"),
-    //         }
-    //     }
+        #[test]
+        fn cannot_challenge_after_period() {
+            let mut deps = setup();
+            mint(deps.as_mut(), 200);
+            match run(deps.as_mut(), "watcher", 461, ExecuteMsg::Challenge { id: 0 }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::ChallengePeriodEnded { .. }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            match run(deps.as_mut(), "creator", 461, ExecuteMsg::ResolveMint { id: 0, approve: false }) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::MintNotChallenged { id: 0 }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
 
-    //     fn make_instantiate_msg() -> InstantiateMsg {
-    //         InstantiateMsg {
-    //             name: "Cash Token".to_string(),
-    //             symbol: "CASH".to_string(),
-    //             decimals: 9,
-    //             evm_contract: "0xcd38B80aee05cad65571B7564BD110fdf2990de6".to_string(),
-    //         }
-    //     }
+        #[test]
+        fn large_batch_item_is_held() {
+            let mut deps = setup();
+            let batch_msg = ExecuteMsg::BatchMintCW20 {
+                mints: vec![MintItem {
+                    replay_key: "0xabc-1".to_string(),
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(200u128),
+                    evm_sender: None,
+                }],
+            };
+            let res = run(deps.as_mut(), MODULE_CALLER, 451, batch_msg).unwrap();
+            let data: BatchMintCW20Response = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.results[0].status, MintItemStatus::Pending { id: 0 });
+            assert_eq!(data.total_minted, Uint128::zero());
+            assert_eq!(get_total_supply(&deps.storage), 0);
 
-    //     #[test]
-    //     fn can_send_to_wasm() {
-    //         let mut deps = mock_dependencies(&[]);
-    //         let instantiate_msg = make_instantiate_msg();
-    //         let (env, info) = mock_env_height("0xcd38B80aee05cad65571B7564BD110fdf2990de6", 450, 550);
-    //         let res = instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
-    //         assert_eq!(0, res.messages.len());
+            run(deps.as_mut(), "anyone", 461, ExecuteMsg::FinalizeMint { id: 0 }).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 200);
+            assert_eq!(get_total_supply(&deps.storage), 200);
+        }
+    }
 
-    //         let mint_cw20_msg = ExecuteMsg::MintCW20 { recipient: "0xcd38B80aee05cad65571B7564BD110fdf2990de6".to_string(), amount: (Uint128::from(100u128)) };
+    mod bridge_fees {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::msg::{
+            BatchMintCW20Response, FeeTierResponse, MintItem, MintItemStatus, SendToEvmResponse,
+        };
+        use crate::state::FeeTier;
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::OwnedDeps;
+
+        const MODULE_CALLER: &str = "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr";
+        const EVM_RECIPIENT: &str = "0x1111111111111111111111111111111111111111";
+
+        fn schedule_msg(base_fee_bps: u16, tiers: &[(u128, u16)], window_days: u32) -> ExecuteMsg {
+            ExecuteMsg::UpdateFeeSchedule {
+                collector: "treasury".to_string(),
+                base_fee_bps,
+                tiers: tiers
+                    .iter()
+                    .map(|&(min_volume, fee_bps)| FeeTier {
+                        min_volume: Uint128::from(min_volume),
+                        fee_bps,
+                    })
+                    .collect(),
+                window_days,
+            }
+        }
 
-    //         let (env, info) = mock_env_height("ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr", 450, 550);
-    //         let mint_cw20_result = execute(deps.as_mut(), env, info, mint_cw20_msg).unwrap();
-    //         assert_eq!(mint_cw20_result.messages.len(), 0);
-    //         assert_eq!(
-    //             mint_cw20_result.attributes,
-    //             vec![
-    //                 attr("action", "MINT"),
-    //                 attr("account", "addr1111"),
-    //                 attr("sender", "addr0000"),
-    //                 attr("amount","100"),
-    //             ]
-    //         );
+        fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+            let mut deps = mock_dependencies(&[]);
+            let instantiate_msg = InstantiateMsg {
+                name: "Cash Token".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 9,
+                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100_000u128),
+                }],
+                ..Default::default()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
 
-    //         assert_eq!(
-    //             get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-    //             100
-    //         );
-    //         assert_eq!(get_total_supply(&deps.storage), 100);
-    //     } 
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, schedule_msg(100, &[(1000, 50), (5000, 10)], 7)).unwrap();
+            deps
+        }
 
-    //     #[test]
-    //     fn can_send_to_evm(){
-    //         let mut deps = mock_dependencies(&[]);
-    //         let instantiate_msg = make_instantiate_msg();
-    //         let (env, info) = mock_env_height(&address(0).as_str(), 450, 550);
-    //         let res = instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
-    //         assert_eq!(0, res.messages.len());
+        fn send(deps: DepsMut, amount: u128) -> Response<SendToEvmMsg> {
+            let send_msg = ExecuteMsg::SendToEvm {
+                recipient: EVM_RECIPIENT.to_string(),
+                amount: Uint128::from(amount),
+                deadline: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            execute(deps, env, info, send_msg).unwrap()
+        }
 
-    //         let mint_cw20_msg = ExecuteMsg::MintCW20 { recipient: address(1).to_string(), amount: (Uint128::from(100u128)) };
+        fn sent_amount(res: &Response<SendToEvmMsg>) -> Uint128 {
+            match &res.messages[0].msg {
+                CosmosMsg::Custom(msg) => msg.amount,
+                msg => panic!("unexpected message: {:?}", msg),
+            }
+        }
 
-    //         let (env, info) = mock_env_height("addr0000", 450, 550);
-    //         execute(deps.as_mut(), env, info, mint_cw20_msg).unwrap();
+        fn fee_tier(deps: Deps, address: &str, time: u64) -> FeeTierResponse {
+            let (env, _) = mock_env_height("anyone", 460, time);
+            let query_msg = QueryMsg::FeeTier {
+                address: address.to_string(),
+            };
+            from_slice(&query(deps, env, query_msg).unwrap()).unwrap()
+        }
 
-    //         let send_to_evm_msg = ExecuteMsg::SendToEvm {recipient: address(1).to_string(), amount: (Uint128::from(100u128)) };
+        #[test]
+        fn send_pays_base_fee() {
+            let mut deps = setup();
+            let res = send(deps.as_mut(), 1000);
+            assert_eq!(sent_amount(&res), Uint128::from(990u128));
+            assert_eq!(
+                res.events,
+                vec![events::bridge_out("addr0000", EVM_RECIPIENT, Uint128::from(990u128), Uint128::from(10u128), 0)]
+            );
+            let data: SendToEvmResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.amount, Uint128::from(1000u128));
+            assert_eq!(data.fee, Uint128::from(10u128));
+            assert_eq!(data.balance, Uint128::from(99_000u128));
+            // Held by the contract until the transfer is confirmed
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("treasury")), 0);
+            assert_eq!(get_balance(&deps.storage, &mock_env().contract.address), 10);
+            assert_eq!(get_total_supply(&deps.storage), 99_010);
+            assert_eq!(read_outbound_in_flight(&deps.storage).unwrap(), 990);
+
+            let (env, info) = mock_env_height(MODULE_CALLER, 452, 570);
+            execute(deps.as_mut(), env, info, ExecuteMsg::ConfirmSendToEvm { id: 0 }).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("treasury")), 10);
+            assert_eq!(get_balance(&deps.storage, &mock_env().contract.address), 0);
+            assert_eq!(get_total_supply(&deps.storage), 99_010);
+        }
 
-    //         let (env, info) = mock_env_height("addr1111", 450, 550);
-    //         let send_to_evm_result = execute(deps.as_mut(), env, info, send_to_evm_msg).unwrap();
+        #[test]
+        fn volume_lowers_rate() {
+            let mut deps = setup();
+            assert_eq!(fee_tier(deps.as_ref(), "addr0000", 560).fee_bps, 100);
+            send(deps.as_mut(), 1000);
+            assert_eq!(
+                fee_tier(deps.as_ref(), "addr0000", 560),
+                FeeTierResponse {
+                    address: "addr0000".to_string(),
+                    exempt: false,
+                    volume: Uint128::from(1000u128),
+                    tier: Some(0),
+                    fee_bps: 50,
+                }
+            );
+            let res = send(deps.as_mut(), 4000);
+            assert_eq!(sent_amount(&res), Uint128::from(3980u128));
+            assert_eq!(fee_tier(deps.as_ref(), "addr0000", 560).tier, Some(1));
+        }
 
-    //         print!("这是一个打印结果{:?}",send_to_evm_result);
-    //         assert_eq!(send_to_evm_result.messages.len(), 1);
-    //         assert_eq!(send_to_evm_result.attributes, 
-    //             vec![
-    //                 attr("action","call evm"),
-    //                 attr("amount","100")
-    //             ]);
+        #[test]
+        fn volume_leaves_window() {
+            let mut deps = setup();
+            send(deps.as_mut(), 1000);
+            assert_eq!(fee_tier(deps.as_ref(), "addr0000", 560 + 6 * 86_400).tier, Some(0));
+            let tier = fee_tier(deps.as_ref(), "addr0000", 560 + 7 * 86_400);
+            assert_eq!(tier.volume, Uint128::zero());
+            assert_eq!(tier.tier, None);
+            assert_eq!(tier.fee_bps, 100);
+        }
 
-    //         assert_eq!(
-    //             get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-    //             0
-    //         );
-    //     }
-    // }
+        #[test]
+        fn exempt_account_pays_nothing() {
+            let mut deps = setup();
+            let exempt_msg = || ExecuteMsg::UpdateFeeExemptions {
+                add: vec!["addr0000".to_string()],
+                remove: vec![],
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            match execute(deps.as_mut(), env, info, exempt_msg()) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::Unauthorized {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, exempt_msg()).unwrap();
+
+            let res = send(deps.as_mut(), 1000);
+            assert_eq!(sent_amount(&res), Uint128::from(1000u128));
+            let tier = fee_tier(deps.as_ref(), "addr0000", 560);
+            assert!(tier.exempt);
+            assert_eq!(tier.fee_bps, 0);
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("treasury")), 0);
+        }
 
+        #[test]
+        fn mint_pays_fee() {
+            let mut deps = setup();
+            let mint_msg = ExecuteMsg::MintCW20 {
+                recipient: "addr0001".to_string(),
+                amount: Uint128::from(1000u128),
+                event_id: None,
+                evm_sender: None,
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            let res = execute(deps.as_mut(), env, info, mint_msg).unwrap();
+            assert_eq!(
+                res.events,
+                vec![events::bridge_in(MODULE_CALLER, "addr0001", Uint128::from(990u128), Uint128::from(10u128), 0)]
+            );
+            let data = match from_slice(&res.data.unwrap()).unwrap() {
+                MintOutcome::Minted(data) => data,
+                outcome => panic!("unexpected outcome: {:?}", outcome),
+            };
+            assert_eq!(data.fee, Uint128::from(10u128));
+            assert_eq!(data.balance, Uint128::from(990u128));
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("treasury")), 10);
+            assert_eq!(get_total_supply(&deps.storage), 101_000);
+            assert_eq!(fee_tier(deps.as_ref(), "addr0001", 560).volume, Uint128::from(1000u128));
+        }
 
-    mod deploy_test {
-        use super::*;
-        use cosmwasm_std::{attr, Addr};
+        #[test]
+        fn batch_mint_reports_net_amount() {
+            let mut deps = setup();
+            let batch_msg = ExecuteMsg::BatchMintCW20 {
+                mints: vec![MintItem {
+                    replay_key: "0xabc-1".to_string(),
+                    recipient: "addr0001".to_string(),
+                    amount: Uint128::from(1000u128),
+                    evm_sender: None,
+                }],
+            };
+            let (env, info) = mock_env_height(MODULE_CALLER, 451, 560);
+            let res = execute(deps.as_mut(), env, info, batch_msg).unwrap();
+            assert_eq!(
+                res.events,
+                vec![events::bridge_in(MODULE_CALLER, "addr0001", Uint128::from(990u128), Uint128::from(10u128), 0)]
+            );
+            let data: BatchMintCW20Response = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(
+                data.results[0].status,
+                MintItemStatus::Minted {
+                    transfer_id: 0,
+                    fee: Uint128::from(10u128),
+                }
+            );
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0001")), 990);
+        }
 
-        fn address(index: u8) -> Addr {
-            match index {
-                0 => Addr::unchecked("addr0000".to_string()), // contract instantiateializer
-                1 => Addr::unchecked("addr1111".to_string()),
-                2 => Addr::unchecked("addr4321".to_string()),
-                3 => Addr::unchecked("addr5432".to_string()),
-                4 => Addr::unchecked("addr6543".to_string()),
-                _ => panic!("Unsupported address index"),
-            }
+        #[test]
+        fn cancelled_sends_return_fee() {
+            let mut deps = setup();
+            let update_msg = ExecuteMsg::UpdateConfig {
+                evm_contract: None,
+                name: None,
+                symbol: None,
+                inbound_enabled: None,
+                outbound_enabled: None,
+                history_limit: None,
+                reserve_auto_pause: None,
+                timelock_threshold: Some(Uint128::from(2000u128)),
+                timelock_delay: Some(3600),
+                optimistic_threshold: None,
+                challenge_period: None,
+                sender_cancel_enabled: Some(true),
+                claim_by_sender: None,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, update_msg).unwrap();
+
+            let send_msg = ExecuteMsg::SendToEvm {
+                recipient: EVM_RECIPIENT.to_string(),
+                amount: Uint128::from(1000u128),
+                deadline: Some(600),
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 560);
+            execute(deps.as_mut(), env, info, send_msg).unwrap();
+            let res = send(deps.as_mut(), 3000);
+            let data: SendToEvmResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.fee, Uint128::from(15u128));
+            assert_eq!(get_balance(&deps.storage, &mock_env().contract.address), 25);
+            assert_eq!(fee_tier(deps.as_ref(), "addr0000", 560).volume, Uint128::from(4000u128));
+
+            let (env, info) = mock_env_height("creator", 452, 570);
+            execute(deps.as_mut(), env, info, ExecuteMsg::CancelQueued { id: 1 }).unwrap();
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 99_000);
+            assert_eq!(get_balance(&deps.storage, &mock_env().contract.address), 10);
+            assert_eq!(fee_tier(deps.as_ref(), "addr0000", 570).volume, Uint128::from(1000u128));
+
+            let (env, info) = mock_env_height("addr0000", 453, 601);
+            let res = execute(deps.as_mut(), env, info, ExecuteMsg::CancelSendToEvm { id: 0 }).unwrap();
+            let transfer: BridgeTransfer = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(transfer.amount, Uint128::from(990u128));
+            assert_eq!(transfer.fee, Uint128::from(10u128));
+            assert_eq!(get_balance(&deps.storage, &Addr::unchecked("addr0000")), 100_000);
+            assert_eq!(get_balance(&deps.storage, &mock_env().contract.address), 0);
+            assert_eq!(get_total_supply(&deps.storage), 100_000);
+            assert_eq!(read_outbound_in_flight(&deps.storage).unwrap(), 0);
+            let tier = fee_tier(deps.as_ref(), "addr0000", 601);
+            assert_eq!(tier.volume, Uint128::zero());
+            assert_eq!(tier.fee_bps, 100);
         }
 
-        fn make_instantiate_msg() -> InstantiateMsg {
-            InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                evm_contract: "0xcd38b80aee05cad65571b7564bd110fdf2990de6".to_string(),
+        #[test]
+        fn collector_cannot_be_frozen() {
+            let mut deps = setup();
+            let freeze_msg = |address: &str| ExecuteMsg::FreezeAccount {
+                address: address.to_string(),
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            match execute(deps.as_mut(), env, info, freeze_msg("treasury")) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::FeeCollectorFrozen { address }) => assert_eq!(address, "treasury"),
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            send(deps.as_mut(), 1000);
+            assert_eq!(get_balance(&deps.storage, &mock_env().contract.address), 10);
+
+            // Nor can a frozen account become the collector
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, freeze_msg("vault")).unwrap();
+            let schedule_msg = ExecuteMsg::UpdateFeeSchedule {
+                collector: "vault".to_string(),
+                base_fee_bps: 100,
+                tiers: vec![],
+                window_days: 0,
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            match execute(deps.as_mut(), env, info, schedule_msg) {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::AccountFrozen { address }) => assert_eq!(address, "vault"),
+                Err(e) => panic!("unexpected error: {:?}", e),
             }
         }
 
         #[test]
-        fn contract_can_same() {
-            let mut deps = mock_dependencies(&[]);
-            let instantiate_msg = make_instantiate_msg();
-            let (env, info) = mock_env_height("ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr", 450, 550);
-            let res = instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
-            assert_eq!(0, res.messages.len());
+        fn fee_on_large_amount_does_not_overflow() {
+            let mut deps = setup_with_balances(vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::MAX,
+            }]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, schedule_msg(100, &[], 0)).unwrap();
 
-            let mint_cw20_msg = ExecuteMsg::MintCW20 { recipient: "addr111".to_string(), amount: (Uint128::from(100u128)) };
-            
-            let (env, info) = mock_env_height("ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr", 450, 550);
-            let mint_cw20_result = execute(deps.as_mut(), env, info, mint_cw20_msg).unwrap();
-            assert_eq!(mint_cw20_result.messages.len(), 0);
-            assert_eq!(
-                mint_cw20_result.attributes,
-                vec![
-                    attr("action", "MINT"),
-                    attr("account", "addr111"),
-                    attr("sender", "ex1e5utszhwqh9dv4t3katyh5gslhefjr0xmlcyyr"),
-                    attr("amount","100"),
-                ]
-            );
+            let res = send(deps.as_mut(), u128::MAX);
+            assert_eq!(sent_amount(&res), Uint128::from(u128::MAX - u128::MAX / 100));
+            let data: SendToEvmResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.fee, Uint128::from(u128::MAX / 100));
+        }
 
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                100
-            );
-            assert_eq!(get_total_supply(&deps.storage), 100);
-        } 
+        #[test]
+        fn rejects_invalid_schedules() {
+            let mut deps = setup();
+            for msg in [
+                schedule_msg(10_001, &[], 0),
+                schedule_msg(100, &[(5000, 10), (1000, 50)], 7),
+                schedule_msg(100, &[(1000, 50)], 0),
+                schedule_msg(100, &[(1000, 150)], 7),
+                schedule_msg(100, &[(1000, 10), (5000, 50)], 7),
+            ] {
+                let (env, info) = mock_env_height("creator", 450, 550);
+                match execute(deps.as_mut(), env, info, msg) {
+                    Ok(_) => panic!("expected error"),
+                    Err(ContractError::InvalidFeeSchedule {}) => {}
+                    Err(e) => panic!("unexpected error: {:?}", e),
+                }
+            }
+        }
     }
-
-}
\ No newline at end of file
+}
@@ -0,0 +1,128 @@
+//! Read-only view of the cw20-base storage layout, used to adopt existing
+//! cw20-base deployments through `migrate`.
+//!
+//! cw20-base stores its data with cw-storage-plus. Items live under their raw
+//! key, maps under a length-prefixed namespace, which is the same encoding
+//! cosmwasm-storage uses for prefixed storage.
+
+use cosmwasm_std::{from_slice, Addr, BlockInfo, Order, Storage, Timestamp, Uint128};
+use cosmwasm_storage::ReadonlyPrefixedStorage;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+
+use crate::error::ContractError;
+
+pub const CW20_BASE_CONTRACT: &str = "crates.io:cw20-base";
+
+pub const KEY_CONTRACT_INFO: &[u8] = b"contract_info";
+pub const KEY_TOKEN_INFO: &[u8] = b"token_info";
+pub const KEY_MARKETING_INFO: &[u8] = b"marketing_info";
+pub const KEY_LOGO: &[u8] = b"logo";
+pub const NAMESPACE_BALANCES: &[u8] = b"balance";
+pub const NAMESPACE_ALLOWANCES: &[u8] = b"allowance";
+pub const NAMESPACE_ALLOWANCES_SPENDER: &[u8] = b"allowance_spender";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ContractVersion {
+    pub contract: String,
+    pub version: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokenInfo {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: Uint128,
+    pub mint: Option<MinterData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MinterData {
+    pub minter: Addr,
+    pub cap: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Expiration {
+    AtHeight(u64),
+    AtTime(Timestamp),
+    Never {},
+}
+
+impl Expiration {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        match self {
+            Expiration::AtHeight(height) => block.height >= *height,
+            Expiration::AtTime(time) => block.time >= *time,
+            Expiration::Never {} => false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Allowance {
+    pub allowance: Uint128,
+    pub expires: Expiration,
+}
+
+pub fn read_contract_version(store: &dyn Storage) -> Result<Option<ContractVersion>, ContractError> {
+    match store.get(KEY_CONTRACT_INFO) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn read_token_info(store: &dyn Storage) -> Result<Option<TokenInfo>, ContractError> {
+    match store.get(KEY_TOKEN_INFO) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+// Returns every (holder, balance) pair, keyed by the raw storage key
+pub fn read_balances(store: &dyn Storage) -> Result<Vec<(Vec<u8>, Uint128)>, ContractError> {
+    let balances_store = ReadonlyPrefixedStorage::new(store, NAMESPACE_BALANCES);
+    balances_store
+        .range(None, None, Order::Ascending)
+        .map(|(key, value)| Ok((key, from_slice(&value)?)))
+        .collect()
+}
+
+// Returns every raw key stored under a map namespace
+pub fn read_keys(store: &dyn Storage, namespace: &[u8]) -> Vec<Vec<u8>> {
+    let map_store = ReadonlyPrefixedStorage::new(store, namespace);
+    map_store
+        .range(None, None, Order::Ascending)
+        .map(|(key, _)| key)
+        .collect()
+}
+
+// Raw owner key, raw spender key and the stored allowance
+pub type AllowanceEntry = (Vec<u8>, Vec<u8>, Allowance);
+
+// Returns every allowance entry
+// The storage key is the length-prefixed owner followed by the spender
+pub fn read_allowances(store: &dyn Storage) -> Result<Vec<AllowanceEntry>, ContractError> {
+    let allowances_store = ReadonlyPrefixedStorage::new(store, NAMESPACE_ALLOWANCES);
+    allowances_store
+        .range(None, None, Order::Ascending)
+        .map(|(key, value)| {
+            let (owner, spender) = split_length_prefixed(&key)?;
+            Ok((owner.to_vec(), spender.to_vec(), from_slice(&value)?))
+        })
+        .collect()
+}
+
+fn split_length_prefixed(key: &[u8]) -> Result<(&[u8], &[u8]), ContractError> {
+    if key.len() < 2 {
+        return Err(ContractError::CorruptedDataFound {});
+    }
+    let len = u16::from_be_bytes(key[0..2].try_into().unwrap()) as usize;
+    if key.len() < 2 + len {
+        return Err(ContractError::CorruptedDataFound {});
+    }
+    Ok((&key[2..2 + len], &key[2 + len..]))
+}
@@ -34,5 +34,116 @@ pub enum ContractError {
     InvalidRecipient{address:String},
 
     #[error("The sender addr {address} is not expect)")]
-    InvalidSender{address:String}
+    InvalidSender{address:String},
+
+    #[error("Minting cannot exceed the cap (cap {cap}, required={required})")]
+    CapExceeded { cap: u128, required: u128 },
+
+    #[error("Duplicate initial balance addresses")]
+    DuplicateInitialBalanceAddresses {},
+
+    #[error("Cannot migrate from contract {contract:?}")]
+    UnsupportedMigration { contract: String },
+
+    #[error("Migration needs an owner when the cw20-base token has no minter")]
+    MigrationOwnerRequired {},
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Account {address} is frozen")]
+    AccountFrozen { address: String },
+
+    #[error("Account {address} collects bridge fees and cannot be frozen")]
+    FeeCollectorFrozen { address: String },
+
+    #[error("Account {address} is not on the bridge allow-list")]
+    NotAllowlisted { address: String },
+
+    #[error("Bridge transfer {id} is not a pending outbound transfer")]
+    BridgeTransferNotPending { id: u64 },
+
+    #[error("Pending mint {id} can be finalized from block {finalize_height}")]
+    ChallengePeriodActive { id: u64, finalize_height: u64 },
+
+    #[error("Challenge period of pending mint {id} ended at block {finalize_height}")]
+    ChallengePeriodEnded { id: u64, finalize_height: u64 },
+
+    #[error("Pending mint {id} is challenged")]
+    MintChallenged { id: u64 },
+
+    #[error("Pending mint {id} is not challenged")]
+    MintNotChallenged { id: u64 },
+
+    #[error("Bridge transfer {id} is not a queued outbound transfer")]
+    BridgeTransferNotQueued { id: u64 },
+
+    #[error("Bridge transfer {id} is queued until {release_time}")]
+    TimelockNotExpired { id: u64, release_time: u64 },
+
+    #[error("Bridge transfer {id} was released at {release_time}")]
+    TimelockExpired { id: u64, release_time: u64 },
+
+    #[error("Amount {amount} is above the timelock threshold {threshold}, use SendToEvm")]
+    TimelockRequired { amount: u128, threshold: u128 },
+
+    #[error("Bridge transfer {id} has no deadline or it has not passed")]
+    DeadlineNotPassed { id: u64 },
+
+    #[error("Deadline {deadline} is not in the future")]
+    InvalidDeadline { deadline: u64 },
+
+    #[error("Deadline {deadline} must be after the release time {release_time}")]
+    DeadlineBeforeRelease { deadline: u64, release_time: u64 },
+
+    #[error("Senders cannot cancel bridge transfers until ConfirmSendToEvm is relied on")]
+    SenderCancelDisabled {},
+
+    #[error("Reserve report for EVM block {reported} is not newer than block {last}")]
+    StaleReserveReport { last: u64, reported: u64 },
+
+    #[error("Batch must contain at least one item")]
+    EmptyBatch {},
+
+    #[error("Replay key must not be empty")]
+    EmptyReplayKey {},
+
+    #[error("Bridging is disabled in the {direction} direction")]
+    BridgeDirectionDisabled { direction: String },
+
+    #[error("Public key must be a 65 byte uncompressed secp256k1 key")]
+    InvalidPubkey {},
+
+    #[error("Signature does not match the signed message")]
+    InvalidSignature {},
+
+    #[error("Invalid nonce (expected {expected}, got {got})")]
+    InvalidNonce { expected: u64, got: u64 },
+
+    #[error("Signature expired at {expiration}")]
+    SignatureExpired { expiration: u64 },
+
+    #[error("Nothing to claim for {evm_address}")]
+    NothingToClaim { evm_address: String },
+
+    #[error("Fee rates must be at most 10000 bps and tiers must have increasing volumes and non-increasing rates within a window")]
+    InvalidFeeSchedule {},
+
+    #[error("Guardian set must have unique guardians and a threshold between 1 and their number")]
+    InvalidGuardianSet {},
+
+    #[error("Mints must be attested by guardians")]
+    GuardianAttestationRequired {},
+
+    #[error("Guardian already attested event {event_id}")]
+    AlreadyAttested { event_id: String },
+
+    #[error("Event {event_id} was already processed as bridge transfer {transfer_id}")]
+    MintAlreadyProcessed { event_id: String, transfer_id: u64 },
+
+    #[error("Event {event_id} is held as pending mint {id}")]
+    MintPending { event_id: String, id: u64 },
+
+    #[error("Event {event_id} is already escrowed")]
+    EscrowExists { event_id: String },
 }
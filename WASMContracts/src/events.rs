@@ -0,0 +1,527 @@
+//! Event types and attribute keys emitted by the execute entry point.
+//!
+//! Every event is built here, so each event type always carries the same
+//! attribute keys. Most execute paths emit a single event. The batches emit
+//! one per item, and paths that hand over to another one also emit its
+//! events: AttestMint and ResolveMint those of the mint or refund they
+//! dispatch, ExecuteSigned those of the signed action. The chain prefixes
+//! custom event types with `wasm-`, so `bridge_in` is indexed as
+//! `wasm-bridge_in`.
+
+use cosmwasm_std::{Event, Uint128};
+
+pub const TRANSFER: &str = "transfer";
+pub const APPROVE: &str = "approve";
+pub const PERMIT: &str = "permit";
+pub const EXECUTE_SIGNED: &str = "execute_signed";
+pub const BURN: &str = "burn";
+pub const BRIDGE_IN: &str = "bridge_in";
+pub const BRIDGE_IN_SKIPPED: &str = "bridge_in_skipped";
+pub const MINT_PENDING: &str = "mint_pending";
+pub const MINT_CHALLENGED: &str = "mint_challenged";
+pub const MINT_REJECTED: &str = "mint_rejected";
+pub const CLAIM: &str = "claim";
+pub const ESCROW: &str = "escrow";
+pub const ESCROW_RELEASE: &str = "escrow_release";
+pub const BRIDGE_REFUND: &str = "bridge_refund";
+pub const BRIDGE_OUT: &str = "bridge_out";
+pub const BRIDGE_OUT_CONFIRMED: &str = "bridge_out_confirmed";
+pub const BRIDGE_OUT_CANCELLED: &str = "bridge_out_cancelled";
+pub const BRIDGE_OUT_QUEUED: &str = "bridge_out_queued";
+pub const BRIDGE_OUT_RELEASED: &str = "bridge_out_released";
+pub const UPDATE_CONFIG: &str = "update_config";
+pub const GRANT_ROLE: &str = "grant_role";
+pub const REVOKE_ROLE: &str = "revoke_role";
+pub const FREEZE: &str = "freeze";
+pub const UNFREEZE: &str = "unfreeze";
+pub const BRIDGE_ALLOWLIST_MODE: &str = "bridge_allowlist_mode";
+pub const BRIDGE_ALLOWLIST_UPDATE: &str = "bridge_allowlist_update";
+pub const RESERVE_REPORT: &str = "reserve_report";
+pub const UPDATE_GUARDIANS: &str = "update_guardians";
+pub const UPDATE_FEE_SCHEDULE: &str = "update_fee_schedule";
+pub const FEE_EXEMPTION_UPDATE: &str = "fee_exemption_update";
+pub const ATTEST_MINT: &str = "attest_mint";
+
+/// The message sender, present on every event
+pub const ATTR_SENDER: &str = "sender";
+pub const ATTR_FROM: &str = "from";
+/// A wasm address, or an EVM address on bridge_out and claimable bridge_in
+pub const ATTR_TO: &str = "to";
+pub const ATTR_AMOUNT: &str = "amount";
+pub const ATTR_OWNER: &str = "owner";
+pub const ATTR_SPENDER: &str = "spender";
+pub const ATTR_SIGNER: &str = "signer";
+pub const ATTR_NONCE: &str = "nonce";
+pub const ATTR_FEE: &str = "fee";
+pub const ATTR_TRANSFER_ID: &str = "transfer_id";
+pub const ATTR_REPLAY_KEY: &str = "replay_key";
+pub const ATTR_REASON: &str = "reason";
+pub const ATTR_EVM_ADDRESS: &str = "evm_address";
+pub const ATTR_EVENT_ID: &str = "event_id";
+pub const ATTR_RELEASE_TIME: &str = "release_time";
+pub const ATTR_PENDING_MINT_ID: &str = "pending_mint_id";
+pub const ATTR_FINALIZE_HEIGHT: &str = "finalize_height";
+pub const ATTR_GUARDIANS: &str = "guardians";
+pub const ATTR_THRESHOLD: &str = "threshold";
+pub const ATTR_BASE_FEE_BPS: &str = "base_fee_bps";
+pub const ATTR_TIERS: &str = "tiers";
+pub const ATTR_ATTESTATIONS: &str = "attestations";
+pub const ATTR_ACCOUNT: &str = "account";
+pub const ATTR_ROLE: &str = "role";
+pub const ATTR_ENABLED: &str = "enabled";
+pub const ATTR_ADDED: &str = "added";
+pub const ATTR_REMOVED: &str = "removed";
+pub const ATTR_EVM_BLOCK: &str = "evm_block";
+pub const ATTR_RESERVES: &str = "reserves";
+pub const ATTR_EXPECTED: &str = "expected";
+pub const ATTR_SHORTFALL: &str = "shortfall";
+pub const ATTR_SURPLUS: &str = "surplus";
+pub const ATTR_INBOUND_PAUSED: &str = "inbound_paused";
+
+/// Prefixes of the attribute pairs update_config adds for each changed field
+pub const ATTR_OLD_PREFIX: &str = "old_";
+pub const ATTR_NEW_PREFIX: &str = "new_";
+
+// Used by Transfer and TransferFrom, where the sender is the spender
+pub fn transfer(sender: &str, from: &str, to: &str, amount: Uint128) -> Event {
+    Event::new(TRANSFER)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_FROM, from)
+        .add_attribute(ATTR_TO, to)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+}
+
+pub fn approve(sender: &str, spender: &str, amount: Uint128) -> Event {
+    Event::new(APPROVE)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_OWNER, sender)
+        .add_attribute(ATTR_SPENDER, spender)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+}
+
+// The sender only relays the signed approval, the owner is the signer
+pub fn permit(sender: &str, owner: &str, spender: &str, amount: Uint128) -> Event {
+    Event::new(PERMIT)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_OWNER, owner)
+        .add_attribute(ATTR_SPENDER, spender)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+}
+
+// Follows the events of the signed action, which carry the signer as sender
+pub fn execute_signed(sender: &str, signer: &str, nonce: u64, fee: Uint128) -> Event {
+    Event::new(EXECUTE_SIGNED)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_SIGNER, signer)
+        .add_attribute(ATTR_NONCE, nonce.to_string())
+        .add_attribute(ATTR_FEE, fee.to_string())
+}
+
+pub fn burn(sender: &str, amount: Uint128) -> Event {
+    Event::new(BURN)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_FROM, sender)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+}
+
+pub fn bridge_in(sender: &str, to: &str, amount: Uint128, fee: Uint128, transfer_id: u64) -> Event {
+    Event::new(BRIDGE_IN)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_TO, to)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+        .add_attribute(ATTR_FEE, fee.to_string())
+        .add_attribute(ATTR_TRANSFER_ID, transfer_id.to_string())
+}
+
+// A MintCW20 held for the challenge period, finalizing it emits bridge_in
+pub fn mint_pending(sender: &str, to: &str, amount: Uint128, id: u64, finalize_height: u64) -> Event {
+    Event::new(MINT_PENDING)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_TO, to)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+        .add_attribute(ATTR_PENDING_MINT_ID, id.to_string())
+        .add_attribute(ATTR_FINALIZE_HEIGHT, finalize_height.to_string())
+}
+
+pub fn mint_challenged(sender: &str, id: u64) -> Event {
+    Event::new(MINT_CHALLENGED)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_PENDING_MINT_ID, id.to_string())
+}
+
+pub fn mint_rejected(sender: &str, id: u64, amount: Uint128) -> Event {
+    Event::new(MINT_REJECTED)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_PENDING_MINT_ID, id.to_string())
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+}
+
+// A BatchMintCW20 item that was not minted
+pub fn bridge_in_skipped(sender: &str, replay_key: &str, to: &str, amount: Uint128, reason: &str) -> Event {
+    Event::new(BRIDGE_IN_SKIPPED)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_REPLAY_KEY, replay_key)
+        .add_attribute(ATTR_TO, to)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+        .add_attribute(ATTR_REASON, reason)
+}
+
+pub fn claim(sender: &str, evm_address: &str, to: &str, amount: Uint128) -> Event {
+    Event::new(CLAIM)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_EVM_ADDRESS, evm_address)
+        .add_attribute(ATTR_TO, to)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+}
+
+// A MintCW20 held back because the recipient is not a valid address
+pub fn escrow(sender: &str, event_id: &str, evm_address: &str, to: &str, amount: Uint128) -> Event {
+    Event::new(ESCROW)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_EVENT_ID, event_id)
+        .add_attribute(ATTR_EVM_ADDRESS, evm_address)
+        .add_attribute(ATTR_TO, to)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+}
+
+// The recipient is the EVM sender when the escrow is returned
+pub fn escrow_release(sender: &str, event_id: &str, to: &str, amount: Uint128, fee: Uint128) -> Event {
+    Event::new(ESCROW_RELEASE)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_EVENT_ID, event_id)
+        .add_attribute(ATTR_TO, to)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+        .add_attribute(ATTR_FEE, fee.to_string())
+}
+
+// A MintCW20 returned to the EVM sender, to is the EVM sender
+pub fn bridge_refund(sender: &str, to: &str, amount: Uint128, transfer_id: u64, reason: &str) -> Event {
+    Event::new(BRIDGE_REFUND)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_TO, to)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+        .add_attribute(ATTR_TRANSFER_ID, transfer_id.to_string())
+        .add_attribute(ATTR_REASON, reason)
+}
+
+pub fn bridge_out(sender: &str, to: &str, amount: Uint128, fee: Uint128, transfer_id: u64) -> Event {
+    Event::new(BRIDGE_OUT)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_FROM, sender)
+        .add_attribute(ATTR_TO, to)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+        .add_attribute(ATTR_FEE, fee.to_string())
+        .add_attribute(ATTR_TRANSFER_ID, transfer_id.to_string())
+}
+
+pub fn bridge_out_confirmed(sender: &str, transfer_id: u64) -> Event {
+    Event::new(BRIDGE_OUT_CONFIRMED)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_TRANSFER_ID, transfer_id.to_string())
+}
+
+// A bridge_out held until the release time, no SendToEvm message is emitted yet
+pub fn bridge_out_queued(
+    sender: &str,
+    to: &str,
+    amount: Uint128,
+    fee: Uint128,
+    transfer_id: u64,
+    release_time: u64,
+) -> Event {
+    Event::new(BRIDGE_OUT_QUEUED)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_FROM, sender)
+        .add_attribute(ATTR_TO, to)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+        .add_attribute(ATTR_FEE, fee.to_string())
+        .add_attribute(ATTR_TRANSFER_ID, transfer_id.to_string())
+        .add_attribute(ATTR_RELEASE_TIME, release_time.to_string())
+}
+
+pub fn bridge_out_released(sender: &str, transfer_id: u64, amount: Uint128) -> Event {
+    Event::new(BRIDGE_OUT_RELEASED)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_TRANSFER_ID, transfer_id.to_string())
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+}
+
+pub fn bridge_out_cancelled(sender: &str, transfer_id: u64, amount: Uint128) -> Event {
+    Event::new(BRIDGE_OUT_CANCELLED)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_TRANSFER_ID, transfer_id.to_string())
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+}
+
+// Only the sender is fixed, see update_config_change for the rest
+pub fn update_config(sender: &str) -> Event {
+    Event::new(UPDATE_CONFIG).add_attribute(ATTR_SENDER, sender)
+}
+
+pub fn update_config_change(event: Event, field: &str, old: &str, new: &str) -> Event {
+    event
+        .add_attribute(format!("{}{}", ATTR_OLD_PREFIX, field), old)
+        .add_attribute(format!("{}{}", ATTR_NEW_PREFIX, field), new)
+}
+
+pub fn grant_role(sender: &str, role: &str, account: &str) -> Event {
+    Event::new(GRANT_ROLE)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_ROLE, role)
+        .add_attribute(ATTR_ACCOUNT, account)
+}
+
+pub fn revoke_role(sender: &str, role: &str, account: &str) -> Event {
+    Event::new(REVOKE_ROLE)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_ROLE, role)
+        .add_attribute(ATTR_ACCOUNT, account)
+}
+
+pub fn freeze(sender: &str, account: &str) -> Event {
+    Event::new(FREEZE)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_ACCOUNT, account)
+}
+
+pub fn unfreeze(sender: &str, account: &str) -> Event {
+    Event::new(UNFREEZE)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_ACCOUNT, account)
+}
+
+pub fn bridge_allowlist_mode(sender: &str, enabled: bool) -> Event {
+    Event::new(BRIDGE_ALLOWLIST_MODE)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_ENABLED, enabled.to_string())
+}
+
+pub fn bridge_allowlist_update(sender: &str, added: usize, removed: usize) -> Event {
+    Event::new(BRIDGE_ALLOWLIST_UPDATE)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_ADDED, added.to_string())
+        .add_attribute(ATTR_REMOVED, removed.to_string())
+}
+
+pub fn update_fee_schedule(sender: &str, base_fee_bps: u16, tiers: usize) -> Event {
+    Event::new(UPDATE_FEE_SCHEDULE)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_BASE_FEE_BPS, base_fee_bps.to_string())
+        .add_attribute(ATTR_TIERS, tiers.to_string())
+}
+
+pub fn fee_exemption_update(sender: &str, added: usize, removed: usize) -> Event {
+    Event::new(FEE_EXEMPTION_UPDATE)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_ADDED, added.to_string())
+        .add_attribute(ATTR_REMOVED, removed.to_string())
+}
+
+pub fn update_guardians(sender: &str, guardians: usize, threshold: u32) -> Event {
+    Event::new(UPDATE_GUARDIANS)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_GUARDIANS, guardians.to_string())
+        .add_attribute(ATTR_THRESHOLD, threshold.to_string())
+}
+
+// Followed by bridge_in when the attestation reaches the threshold
+pub fn attest_mint(sender: &str, event_id: &str, to: &str, amount: Uint128, attestations: u32) -> Event {
+    Event::new(ATTEST_MINT)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_EVENT_ID, event_id)
+        .add_attribute(ATTR_TO, to)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+        .add_attribute(ATTR_ATTESTATIONS, attestations.to_string())
+}
+
+pub fn reserve_report(
+    sender: &str,
+    evm_block: u64,
+    reserves: Uint128,
+    expected: Uint128,
+    shortfall: Uint128,
+    surplus: Uint128,
+    inbound_paused: bool,
+) -> Event {
+    Event::new(RESERVE_REPORT)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_EVM_BLOCK, evm_block.to_string())
+        .add_attribute(ATTR_RESERVES, reserves.to_string())
+        .add_attribute(ATTR_EXPECTED, expected.to_string())
+        .add_attribute(ATTR_SHORTFALL, shortfall.to_string())
+        .add_attribute(ATTR_SURPLUS, surplus.to_string())
+        .add_attribute(ATTR_INBOUND_PAUSED, inbound_paused.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(event: &Event) -> Vec<&str> {
+        event.attributes.iter().map(|attr| attr.key.as_str()).collect()
+    }
+
+    // Indexers depend on these names, changing any of them is a breaking change
+    #[test]
+    fn event_types_are_stable() {
+        assert_eq!(TRANSFER, "transfer");
+        assert_eq!(APPROVE, "approve");
+        assert_eq!(PERMIT, "permit");
+        assert_eq!(EXECUTE_SIGNED, "execute_signed");
+        assert_eq!(BURN, "burn");
+        assert_eq!(BRIDGE_IN, "bridge_in");
+        assert_eq!(BRIDGE_IN_SKIPPED, "bridge_in_skipped");
+        assert_eq!(CLAIM, "claim");
+        assert_eq!(ESCROW, "escrow");
+        assert_eq!(ESCROW_RELEASE, "escrow_release");
+        assert_eq!(BRIDGE_REFUND, "bridge_refund");
+        assert_eq!(BRIDGE_OUT, "bridge_out");
+        assert_eq!(BRIDGE_OUT_CONFIRMED, "bridge_out_confirmed");
+        assert_eq!(BRIDGE_OUT_CANCELLED, "bridge_out_cancelled");
+        assert_eq!(BRIDGE_OUT_QUEUED, "bridge_out_queued");
+        assert_eq!(MINT_PENDING, "mint_pending");
+        assert_eq!(MINT_CHALLENGED, "mint_challenged");
+        assert_eq!(MINT_REJECTED, "mint_rejected");
+        assert_eq!(BRIDGE_OUT_RELEASED, "bridge_out_released");
+        assert_eq!(UPDATE_CONFIG, "update_config");
+        assert_eq!(GRANT_ROLE, "grant_role");
+        assert_eq!(REVOKE_ROLE, "revoke_role");
+        assert_eq!(FREEZE, "freeze");
+        assert_eq!(UNFREEZE, "unfreeze");
+        assert_eq!(BRIDGE_ALLOWLIST_MODE, "bridge_allowlist_mode");
+        assert_eq!(BRIDGE_ALLOWLIST_UPDATE, "bridge_allowlist_update");
+        assert_eq!(RESERVE_REPORT, "reserve_report");
+        assert_eq!(UPDATE_GUARDIANS, "update_guardians");
+        assert_eq!(UPDATE_FEE_SCHEDULE, "update_fee_schedule");
+        assert_eq!(FEE_EXEMPTION_UPDATE, "fee_exemption_update");
+        assert_eq!(ATTEST_MINT, "attest_mint");
+    }
+
+    #[test]
+    fn attribute_keys_are_stable() {
+        let amount = Uint128::from(5u128);
+        assert_eq!(
+            keys(&transfer("a", "b", "c", amount)),
+            vec!["sender", "from", "to", "amount"]
+        );
+        assert_eq!(
+            keys(&approve("a", "b", amount)),
+            vec!["sender", "owner", "spender", "amount"]
+        );
+        assert_eq!(
+            keys(&permit("a", "b", "c", amount)),
+            vec!["sender", "owner", "spender", "amount"]
+        );
+        assert_eq!(
+            keys(&execute_signed("a", "b", 0, amount)),
+            vec!["sender", "signer", "nonce", "fee"]
+        );
+        assert_eq!(keys(&burn("a", amount)), vec!["sender", "from", "amount"]);
+        assert_eq!(
+            keys(&bridge_in("a", "b", amount, amount, 1)),
+            vec!["sender", "to", "amount", "fee", "transfer_id"]
+        );
+        assert_eq!(
+            keys(&bridge_in_skipped("a", "k", "b", amount, "r")),
+            vec!["sender", "replay_key", "to", "amount", "reason"]
+        );
+        assert_eq!(
+            keys(&claim("a", "0x1", "b", amount)),
+            vec!["sender", "evm_address", "to", "amount"]
+        );
+        assert_eq!(
+            keys(&escrow("a", "e", "0x1", "b", amount)),
+            vec!["sender", "event_id", "evm_address", "to", "amount"]
+        );
+        assert_eq!(
+            keys(&escrow_release("a", "e", "b", amount, amount)),
+            vec!["sender", "event_id", "to", "amount", "fee"]
+        );
+        assert_eq!(
+            keys(&bridge_refund("a", "0x1", amount, 1, "cap_exceeded")),
+            vec!["sender", "to", "amount", "transfer_id", "reason"]
+        );
+        assert_eq!(
+            keys(&bridge_out("a", "0x1", amount, amount, 1)),
+            vec!["sender", "from", "to", "amount", "fee", "transfer_id"]
+        );
+        assert_eq!(
+            keys(&bridge_out_confirmed("a", 1)),
+            vec!["sender", "transfer_id"]
+        );
+        assert_eq!(
+            keys(&bridge_out_cancelled("a", 1, amount)),
+            vec!["sender", "transfer_id", "amount"]
+        );
+        assert_eq!(
+            keys(&bridge_out_queued("a", "0x", amount, amount, 1, 2)),
+            vec!["sender", "from", "to", "amount", "fee", "transfer_id", "release_time"]
+        );
+        assert_eq!(
+            keys(&bridge_out_released("a", 1, amount)),
+            vec!["sender", "transfer_id", "amount"]
+        );
+        assert_eq!(
+            keys(&mint_pending("a", "b", amount, 1, 2)),
+            vec!["sender", "to", "amount", "pending_mint_id", "finalize_height"]
+        );
+        assert_eq!(
+            keys(&mint_challenged("a", 1)),
+            vec!["sender", "pending_mint_id"]
+        );
+        assert_eq!(
+            keys(&mint_rejected("a", 1, amount)),
+            vec!["sender", "pending_mint_id", "amount"]
+        );
+        assert_eq!(
+            keys(&update_config_change(update_config("a"), "name", "x", "y")),
+            vec!["sender", "old_name", "new_name"]
+        );
+        assert_eq!(
+            keys(&grant_role("a", "compliance", "b")),
+            vec!["sender", "role", "account"]
+        );
+        assert_eq!(
+            keys(&revoke_role("a", "compliance", "b")),
+            vec!["sender", "role", "account"]
+        );
+        assert_eq!(keys(&freeze("a", "b")), vec!["sender", "account"]);
+        assert_eq!(keys(&unfreeze("a", "b")), vec!["sender", "account"]);
+        assert_eq!(
+            keys(&bridge_allowlist_mode("a", true)),
+            vec!["sender", "enabled"]
+        );
+        assert_eq!(
+            keys(&bridge_allowlist_update("a", 1, 0)),
+            vec!["sender", "added", "removed"]
+        );
+        assert_eq!(
+            keys(&update_fee_schedule("a", 30, 2)),
+            vec!["sender", "base_fee_bps", "tiers"]
+        );
+        assert_eq!(
+            keys(&fee_exemption_update("a", 1, 0)),
+            vec!["sender", "added", "removed"]
+        );
+        assert_eq!(
+            keys(&update_guardians("a", 3, 2)),
+            vec!["sender", "guardians", "threshold"]
+        );
+        assert_eq!(
+            keys(&attest_mint("a", "e", "b", amount, 1)),
+            vec!["sender", "event_id", "to", "amount", "attestations"]
+        );
+        assert_eq!(
+            keys(&reserve_report("a", 1, amount, amount, amount, amount, false)),
+            vec![
+                "sender",
+                "evm_block",
+                "reserves",
+                "expected",
+                "shortfall",
+                "surplus",
+                "inbound_paused"
+            ]
+        );
+    }
+}
@@ -1,9 +1,25 @@
 pub mod contract;
+mod cw20_base;
 mod error;
+pub mod events;
 mod msg;
+mod signature;
 mod state;
 
 pub use msg::{
-    AllowanceResponse, BalanceResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
+    AllowanceResponse, ApproveResponse, AttestMintResponse, BalanceResponse, BatchMintCW20Response,
+    BatchSendToEvmResponse, BatchTransferResponse, BridgeAllowlistModeResponse,
+    BridgeAllowlistResponse, BridgeConfigResponse, BridgeStatsResponse, BridgeTransfersResponse,
+    BurnResponse, ClaimResponse, ClaimableResponse, DailyBridgeVolume, EscrowedResponse, ExecuteMsg,
+    FeeExemptionsResponse, FeeTierResponse, FreezeAccountResponse, FrozenAccountsResponse,
+    HistoryResponse, InitialBalance, InstantiateMsg, MigrateMsg, MintCW20Response, MintItem,
+    MintItemResult, MintItemStatus, MintOutcome, NonceResponse, PendingMintsResponse, QueryMsg, RefundsResponse,
+    ReleaseEscrowResponse, ReportReservesResponse, ReserveDiscrepanciesResponse,
+    ReserveStatusResponse, RoleResponse, SendToEvmResponse, SignDoc, SignedAction, SignedPayload,
+    TransferItem, TransferResponse, UpdateBridgeAllowlistResponse, UpdateFeeExemptionsResponse,
+};
+pub use state::{
+    BridgeDirection, BridgeTransfer, BridgeTransferStatus, BridgeVolume, Constants, Escrow,
+    FeeSchedule, FeeTier, GuardianSet, GuardianVote, HistoryEntry, HistoryKind, MintAttestation,
+    PendingMint, PendingMintStatus, Refund, RefundReason, ReserveReport, Role,
 };
-pub use state::Constants;
@@ -1,14 +1,66 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Binary, Uint128};
 use cosmwasm_std::{CosmosMsg,CustomMsg};
 
-#[derive(Serialize, Deserialize, JsonSchema)]
+use crate::state::{
+    BridgeDirection, BridgeTransfer, BridgeVolume, Escrow, FeeSchedule, FeeTier, HistoryEntry,
+    PendingMint, Refund, ReserveReport, Role,
+};
+
+#[derive(Serialize, Deserialize, JsonSchema, Default)]
 pub struct InstantiateMsg {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
-    pub evm_contract: String
+    pub evm_contract: String,
+    #[serde(default)]
+    pub initial_balances: Vec<InitialBalance>,
+    /// Upper bound on the total supply, enforced by MintCW20
+    pub cap: Option<Uint128>,
+    /// Defaults to the instantiating account
+    pub owner: Option<String>,
+    /// Accounts allowed to call MintCW20 besides the OKC module caller
+    #[serde(default)]
+    pub bridge_callers: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrateMsg {
+    /// Adopts a cw20-base deployment, keeping its address, balances and allowances
+    FromCw20Base {
+        evm_contract: String,
+        /// Defaults to the cw20-base minter
+        owner: Option<String>,
+        #[serde(default)]
+        bridge_callers: Vec<String>,
+    },
+    /// Moves an existing deployment of this contract to new code, keeping its state
+    Upgrade {},
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct InitialBalance {
+    pub address: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TransferItem {
+    pub recipient: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MintItem {
+    /// Unique per EVM-side event, for example the transaction hash and log index
+    pub replay_key: String,
+    pub recipient: String,
+    pub amount: Uint128,
+    /// As in MintCW20, lets a failed item be escrowed or refunded
+    #[serde(default)]
+    pub evm_sender: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, JsonSchema)]
@@ -22,6 +74,10 @@ pub enum ExecuteMsg {
         recipient: String,
         amount: Uint128,
     },
+    /// All transfers succeed or fail together
+    BatchTransfer {
+        transfers: Vec<TransferItem>,
+    },
     TransferFrom {
         owner: String,
         recipient: String,
@@ -30,14 +86,265 @@ pub enum ExecuteMsg {
     Burn {
         amount: Uint128,
     },
+    /// An EVM recipient is credited a claimable balance instead, see Claim.
+    /// A recipient that fails address validation is escrowed when both
+    /// event_id and evm_sender are given, see ReleaseEscrow. Any other mint
+    /// that cannot complete is returned to evm_sender when given, see Refunds
     MintCW20 {
         recipient: String,
         amount: Uint128,
+        /// Unique per EVM-side event, keys the escrow
+        event_id: Option<String>,
+        /// The account that locked or burned the tokens on the EVM side
+        evm_sender: Option<String>,
+    },
+    /// Same callers as MintCW20. Each item is minted, held, escrowed or
+    /// refunded like a MintCW20. Items that fail or were already processed are
+    /// reported in the response data without reverting the rest
+    BatchMintCW20 {
+        mints: Vec<MintItem>,
     },
     SendToEvm {
         recipient: String,
         amount: Uint128,
-    }
+        /// Block time in seconds after which the sender may cancel the
+        /// transfer if it is still unconfirmed, must be in the future and,
+        /// for a timelocked transfer, after its release time
+        deadline: Option<u64>,
+    },
+    /// Recipients are EVM addresses. All transfers succeed or fail together
+    BatchSendToEvm {
+        transfers: Vec<TransferItem>,
+    },
+    /// Owner only. Fields left empty keep their current value
+    UpdateConfig {
+        evm_contract: Option<String>,
+        name: Option<String>,
+        symbol: Option<String>,
+        inbound_enabled: Option<bool>,
+        outbound_enabled: Option<bool>,
+        /// Number of entries kept per account, zero disables the history
+        history_limit: Option<u32>,
+        /// Disable MintCW20 when a reserve report shows a shortfall
+        reserve_auto_pause: Option<bool>,
+        /// SendToEvm amounts above this are queued, zero disables the timelock
+        timelock_threshold: Option<Uint128>,
+        /// Seconds a queued SendToEvm waits before it can be sent
+        timelock_delay: Option<u64>,
+        /// MintCW20 amounts above this wait for the challenge period, zero
+        /// mints everything at once
+        optimistic_threshold: Option<Uint128>,
+        /// Blocks a pending mint can be challenged for
+        challenge_period: Option<u64>,
+        /// Allows CancelSendToEvm. Only enable it once the OKC module sends
+        /// ConfirmSendToEvm for every delivered transfer, otherwise a transfer
+        /// that was delivered can still be cancelled and paid out twice
+        sender_cancel_enabled: Option<bool>,
+        /// Credits every MintCW20 with an evm_sender to the claimable balance
+        /// of that sender instead of the recipient, to be collected with Claim
+        claim_by_sender: Option<bool>,
+    },
+    /// Owner only
+    GrantRole {
+        role: Role,
+        address: String,
+    },
+    /// Owner only
+    RevokeRole {
+        role: Role,
+        address: String,
+    },
+    /// Owner or compliance role. A frozen account cannot send, receive, bridge out or be minted to
+    FreezeAccount {
+        address: String,
+    },
+    /// Owner or compliance role
+    UnfreezeAccount {
+        address: String,
+    },
+    /// Owner only. While enabled, only allow-listed accounts may call SendToEvm
+    /// or receive MintCW20
+    SetBridgeAllowlistMode {
+        enabled: bool,
+    },
+    /// Owner only
+    UpdateBridgeAllowlist {
+        #[serde(default)]
+        add: Vec<String>,
+        #[serde(default)]
+        remove: Vec<String>,
+    },
+    /// Owner only. Rates are in basis points of the bridged amount, no tier
+    /// may charge more than the base rate or the tier below it
+    UpdateFeeSchedule {
+        collector: String,
+        base_fee_bps: u16,
+        #[serde(default)]
+        tiers: Vec<FeeTier>,
+        window_days: u32,
+    },
+    /// Owner only. Exempt accounts pay no bridge fee in either direction
+    UpdateFeeExemptions {
+        #[serde(default)]
+        add: Vec<String>,
+        #[serde(default)]
+        remove: Vec<String>,
+    },
+    /// Owner only. Replaces the guardian set, an empty list lets the bridge
+    /// caller mint directly again
+    UpdateGuardians {
+        guardians: Vec<String>,
+        threshold: u32,
+    },
+    /// Guardians only. Mints like MintCW20 once `threshold` guardians attested
+    /// the same recipient, amount and evm_sender for the event. A mint that
+    /// fails keeps its attestations, and a guardian that voted for it can
+    /// attest again to retry. MintCW20 and BatchMintCW20 are disabled while
+    /// guardians are set
+    AttestMint {
+        event_id: String,
+        recipient: String,
+        amount: Uint128,
+        #[serde(default)]
+        evm_sender: Option<String>,
+    },
+    /// Sender only, while UpdateConfig::sender_cancel_enabled is set. Returns
+    /// an unconfirmed SendToEvm whose deadline has passed
+    CancelSendToEvm {
+        id: u64,
+    },
+    /// Anyone. Credits a pending mint once its challenge period has passed. A
+    /// mint that no longer passes the MintCW20 checks is escrowed or refunded
+    /// to its evm_sender like a MintCW20
+    FinalizeMint {
+        id: u64,
+    },
+    /// Owner or watcher role. Holds a pending mint during its challenge
+    /// period until the owner resolves it
+    Challenge {
+        id: u64,
+    },
+    /// Owner only. Mints a challenged mint when approved. Otherwise refunds it
+    /// to its evm_sender, or drops it when there is none
+    ResolveMint {
+        id: u64,
+        approve: bool,
+    },
+    /// Anyone. Sends a queued SendToEvm once its release time has passed
+    ExecuteQueued {
+        id: u64,
+    },
+    /// Owner or guardians. Returns a queued SendToEvm to the sender before
+    /// its release time
+    CancelQueued {
+        id: u64,
+    },
+    /// Bridge caller only. Marks a pending SendToEvm transfer as delivered on the EVM side
+    ConfirmSendToEvm {
+        id: u64,
+    },
+    /// Owner or reserve reporter. Reports must come with increasing EVM block numbers
+    ReportReserves {
+        reserves: Uint128,
+        evm_block: u64,
+    },
+    /// Sets an allowance on behalf of the owner of the key, who signed
+    /// `SignDoc::Permit` off-chain. Anyone may submit it
+    Permit {
+        /// 65 byte uncompressed secp256k1 key
+        owner_pubkey: Binary,
+        spender: String,
+        amount: Uint128,
+        /// Block time in seconds after which the signature is rejected
+        expiration: u64,
+        /// Must match the owner's current nonce, see QueryMsg::Nonce
+        nonce: u64,
+        /// 64 byte compact signature over the keccak256 hash of the JSON sign doc
+        signature: Binary,
+    },
+    /// Moves the whole claimable balance of an EVM address to a wasm account.
+    /// The EVM address must personal_sign `SignDoc::Claim`. Anyone may submit it
+    Claim {
+        evm_address: String,
+        recipient: String,
+        /// 65 byte r, s, v signature
+        signature: Binary,
+    },
+    /// Credits an escrowed mint to a corrected recipient, less the bridge fee
+    /// and subject to the MintCW20 checks, or returns it to the EVM sender
+    /// when recipient is empty. The EVM sender must personal_sign
+    /// `SignDoc::ReleaseEscrow`. Anyone may submit it
+    ReleaseEscrow {
+        event_id: String,
+        recipient: Option<String>,
+        /// 65 byte r, s, v signature
+        signature: Binary,
+    },
+    /// Owner or relayer. Performs the payload on behalf of the owner of the
+    /// key, who signed `SignDoc::Execute` off-chain
+    ExecuteSigned {
+        payload: SignedPayload,
+        /// 65 byte uncompressed secp256k1 key
+        pubkey: Binary,
+        signature: Binary,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SignedAction {
+    Transfer { recipient: String, amount: Uint128 },
+    SendToEvm { recipient: String, amount: Uint128 },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SignedPayload {
+    pub action: SignedAction,
+    /// Paid by the signer to the relayer on top of the action's amount
+    #[serde(default)]
+    pub fee: Uint128,
+    /// Shared with Permit, see QueryMsg::Nonce
+    pub nonce: u64,
+    /// Block time in seconds after which the signature is rejected
+    pub deadline: u64,
+}
+
+/// Messages signed off-chain. The chain id and contract address keep a
+/// signature from being replayed on another chain or deployment
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SignDoc {
+    Permit {
+        chain_id: String,
+        contract: String,
+        owner: String,
+        spender: String,
+        amount: Uint128,
+        expiration: u64,
+        nonce: u64,
+    },
+    Execute {
+        chain_id: String,
+        contract: String,
+        signer: String,
+        payload: SignedPayload,
+    },
+    /// nonce is the EVM sender's, as returned by QueryMsg::Claimable
+    ReleaseEscrow {
+        chain_id: String,
+        contract: String,
+        event_id: String,
+        recipient: Option<String>,
+        nonce: u64,
+    },
+    /// evm_address is lower case, nonce comes from QueryMsg::Claimable
+    Claim {
+        chain_id: String,
+        contract: String,
+        evm_address: String,
+        recipient: String,
+        nonce: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -47,12 +354,15 @@ pub struct SendToEvmMsg {
     pub contract: String,
     pub recipient: String,
     pub amount: Uint128,
-
+    /// Bridge transfer id, the module sends it back in ConfirmSendToEvm and
+    /// uses it as the nonce on the EVM side. Added after the first release:
+    /// the module must accept the field before this contract is migrated
+    pub id: u64,
 }
 
-impl Into<CosmosMsg<SendToEvmMsg>> for SendToEvmMsg {
-    fn into(self) -> CosmosMsg<SendToEvmMsg> {
-        CosmosMsg::Custom(self)
+impl From<SendToEvmMsg> for CosmosMsg<SendToEvmMsg> {
+    fn from(msg: SendToEvmMsg) -> Self {
+        CosmosMsg::Custom(msg)
     }
 }
 impl CustomMsg for SendToEvmMsg {}
@@ -62,6 +372,83 @@ impl CustomMsg for SendToEvmMsg {}
 pub enum QueryMsg {
     Balance { address: String },
     Allowance { owner: String, spender: String },
+    BridgeConfig {},
+    FrozenAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    BridgeAllowlist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Newest entries first, start_after is the id of the last entry seen
+    History {
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    BridgeTransfer {
+        id: u64,
+    },
+    /// Oldest first. All directions are returned when direction is empty
+    BridgeTransfersByAccount {
+        address: String,
+        direction: Option<BridgeDirection>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Running totals plus the daily buckets between both days, inclusive.
+    /// A day is the block time in seconds divided by 86400
+    BridgeStats {
+        from_day: Option<u64>,
+        to_day: Option<u64>,
+    },
+    ReserveStatus {},
+    /// Reports that did not match, ordered by EVM block
+    ReserveDiscrepancies {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Outbound transfers not yet confirmed, oldest first
+    PendingOutbound {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Optimistic mints not finalized or resolved yet, oldest first
+    PendingMints {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Outbound transfers waiting for their release time, oldest first
+    QueuedOutbound {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Nonce the next signed message from this account must use
+    Nonce { address: String },
+    /// Balance minted to an EVM address and not claimed yet, with the nonce its
+    /// next Claim or ReleaseEscrow signature must use
+    Claimable { evm_address: String },
+    /// Escrowed mints ordered by event id
+    Escrowed {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Guardians {},
+    FeeSchedule {},
+    FeeExemptions {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Volume over the fee window and the rate the account pays now
+    FeeTier { address: String },
+    /// Attestations collected so far for an event that is not minted yet
+    MintAttestation { event_id: String },
+    /// Mints returned to the EVM sender, ordered by transfer id
+    Refunds {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -72,4 +459,294 @@ pub struct BalanceResponse {
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct AllowanceResponse {
     pub allowance: Uint128,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct NonceResponse {
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct EscrowedResponse {
+    pub escrows: Vec<Escrow>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RefundsResponse {
+    pub refunds: Vec<Refund>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClaimableResponse {
+    pub evm_address: String,
+    pub amount: Uint128,
+    /// Nonce the next claim signature must use
+    pub nonce: u64,
+}
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BridgeConfigResponse {
+    pub evm_contract: String,
+    pub module_caller: String,
+    pub owner: Option<String>,
+    pub inbound_enabled: bool,
+    pub outbound_enabled: bool,
+    pub cap: Option<Uint128>,
+    pub allowlist_enabled: bool,
+    pub history_limit: u32,
+    pub reserve_auto_pause: bool,
+    pub timelock_threshold: Uint128,
+    pub timelock_delay: u64,
+    pub optimistic_threshold: Uint128,
+    pub challenge_period: u64,
+    pub sender_cancel_enabled: bool,
+    pub claim_by_sender: bool,
+    pub fee_schedule: FeeSchedule,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct FrozenAccountsResponse {
+    pub accounts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BridgeAllowlistResponse {
+    pub enabled: bool,
+    pub addresses: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct HistoryResponse {
+    pub entries: Vec<HistoryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BridgeTransfersResponse {
+    pub transfers: Vec<BridgeTransfer>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DailyBridgeVolume {
+    pub day: u64,
+    pub volume: BridgeVolume,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BridgeStatsResponse {
+    pub totals: BridgeVolume,
+    /// Only days with bridge activity are listed
+    pub days: Vec<DailyBridgeVolume>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ReserveStatusResponse {
+    pub total_supply: Uint128,
+    pub outbound_in_flight: Uint128,
+    pub pending_inbound: Uint128,
+    pub last_report: Option<ReserveReport>,
+    pub auto_pause: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ReserveDiscrepanciesResponse {
+    pub reports: Vec<ReserveReport>,
+}
+
+/// Returned in the data of Transfer and TransferFrom
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TransferResponse {
+    pub from: String,
+    pub to: String,
+    pub amount: Uint128,
+    pub from_balance: Uint128,
+    pub to_balance: Uint128,
+    /// Allowance left to the spender, TransferFrom only
+    pub allowance: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ApproveResponse {
+    pub owner: String,
+    pub spender: String,
+    pub allowance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BurnResponse {
+    pub amount: Uint128,
+    pub balance: Uint128,
+    pub total_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MintCW20Response {
+    pub transfer_id: u64,
+    pub recipient: String,
+    /// The minted amount, the recipient is credited the amount less the fee
+    pub amount: Uint128,
+    pub fee: Uint128,
+    /// The claimable balance when the recipient is an EVM address
+    pub balance: Uint128,
+    pub total_supply: Uint128,
+}
+
+/// Returned in the data of MintCW20, FinalizeMint and ResolveMint, and as the
+/// result of an AttestMint that reached the threshold
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MintOutcome {
+    Minted(MintCW20Response),
+    /// Held for its challenge period, see FinalizeMint
+    Pending(PendingMint),
+    /// The recipient is not a valid address, see ReleaseEscrow
+    Escrowed(Escrow),
+    Refunded(Refund),
+    /// A rejected pending mint without an EVM sender to refund
+    Rejected(PendingMint),
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ReleaseEscrowResponse {
+    pub event_id: String,
+    /// The wasm recipient, or the EVM sender when returned
+    pub recipient: String,
+    pub amount: Uint128,
+    /// Bridge fee taken from the amount when released to a wasm recipient
+    pub fee: Uint128,
+    /// The outbound bridge transfer when returned to the EVM sender
+    pub transfer_id: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PendingMintsResponse {
+    pub mints: Vec<PendingMint>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AttestMintResponse {
+    pub event_id: String,
+    /// Attestations matching this one, including it
+    pub attestations: u32,
+    pub threshold: u32,
+    /// Outcome of the dispatched mint
+    pub result: Option<MintOutcome>,
+    /// Why the dispatched mint failed, the attestations are kept
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClaimResponse {
+    pub evm_address: String,
+    pub recipient: String,
+    pub amount: Uint128,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SendToEvmResponse {
+    /// Bridge transfer id, also the id of the SendToEvmMsg
+    pub transfer_id: u64,
+    pub recipient: String,
+    /// The debited amount, the recipient receives the amount less the fee
+    pub amount: Uint128,
+    pub fee: Uint128,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ReportReservesResponse {
+    pub report: ReserveReport,
+    pub inbound_paused: bool,
+}
+
+/// Returned by GrantRole and RevokeRole
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RoleResponse {
+    pub role: Role,
+    pub address: String,
+    pub granted: bool,
+}
+
+/// Returned by FreezeAccount and UnfreezeAccount
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct FreezeAccountResponse {
+    pub address: String,
+    pub frozen: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BridgeAllowlistModeResponse {
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct UpdateBridgeAllowlistResponse {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct UpdateFeeExemptionsResponse {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct FeeExemptionsResponse {
+    pub accounts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct FeeTierResponse {
+    pub address: String,
+    pub exempt: bool,
+    pub volume: Uint128,
+    /// Index of the tier reached, none while below every tier
+    pub tier: Option<u32>,
+    pub fee_bps: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BatchTransferResponse {
+    pub from: String,
+    pub total_amount: Uint128,
+    pub from_balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MintItemStatus {
+    /// `fee` was kept from the item amount, the recipient got the rest
+    Minted { transfer_id: u64, fee: Uint128 },
+    /// Held for its challenge period as the given pending mint
+    Pending { id: u64 },
+    /// The recipient is not a valid address, see ReleaseEscrow
+    Escrowed {},
+    /// Sent back to the EVM sender under the given transfer
+    Refunded { transfer_id: u64 },
+    /// The replay key was minted before, under the given transfer
+    AlreadyProcessed { transfer_id: u64 },
+    Failed { error: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MintItemResult {
+    pub replay_key: String,
+    pub recipient: String,
+    pub amount: Uint128,
+    pub status: MintItemStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BatchMintCW20Response {
+    pub results: Vec<MintItemResult>,
+    pub total_minted: Uint128,
+    pub total_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BatchSendToEvmResponse {
+    /// One per item, in order, each used as the nonce of its EVM transfer
+    pub transfer_ids: Vec<u64>,
+    pub total_amount: Uint128,
+    pub balance: Uint128,
+}
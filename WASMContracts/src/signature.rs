@@ -0,0 +1,87 @@
+//! Verification of messages signed off-chain with OKC account keys.
+//!
+//! OKC accounts use Ethereum keys: the account address is the bech32 `ex`
+//! encoding of the last 20 bytes of the keccak256 hash of the uncompressed
+//! public key. Signed payloads are the JSON encoding of a `SignDoc`, hashed
+//! with keccak256.
+//!
+//! Claims of bridged tokens are signed from EVM wallets instead, with
+//! `personal_sign`, and checked by recovering the signer's EVM address.
+
+use bech32::{ToBase32, Variant};
+use cosmwasm_std::{to_vec, Addr, Api, StdError};
+use sha3::{Digest, Keccak256};
+
+use crate::contract::ADDRESS_PREFIX;
+use crate::error::ContractError;
+use crate::msg::SignDoc;
+
+const UNCOMPRESSED_PUBKEY_LEN: usize = 65;
+const RECOVERABLE_SIGNATURE_LEN: usize = 65;
+const PERSONAL_SIGN_PREFIX: &str = "\x19Ethereum Signed Message:\n";
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+// Last 20 bytes of the keccak256 hash of the key, without the 0x04 prefix
+pub fn pubkey_to_evm_address(pubkey: &[u8]) -> Result<Vec<u8>, ContractError> {
+    if pubkey.len() != UNCOMPRESSED_PUBKEY_LEN || pubkey[0] != 0x04 {
+        return Err(ContractError::InvalidPubkey {});
+    }
+    Ok(keccak256(&pubkey[1..])[12..].to_vec())
+}
+
+pub fn pubkey_to_address(pubkey: &[u8]) -> Result<Addr, ContractError> {
+    let evm_address = pubkey_to_evm_address(pubkey)?;
+    let address = bech32::encode(ADDRESS_PREFIX, evm_address.to_base32(), Variant::Bech32)
+        .map_err(|_| ContractError::InvalidPubkey {})?;
+    Ok(Addr::unchecked(address))
+}
+
+// The hash EVM wallets sign for personal_sign
+pub fn personal_sign_hash(message: &[u8]) -> [u8; 32] {
+    let mut data = format!("{}{}", PERSONAL_SIGN_PREFIX, message.len()).into_bytes();
+    data.extend_from_slice(message);
+    keccak256(&data)
+}
+
+// Returns the EVM address that personal-signed the sign doc
+// The signature is r, s and v, where v is 0/1 or 27/28
+pub fn recover_personal_signer(
+    api: &dyn Api,
+    doc: &SignDoc,
+    signature: &[u8],
+) -> Result<Vec<u8>, ContractError> {
+    if signature.len() != RECOVERABLE_SIGNATURE_LEN {
+        return Err(ContractError::InvalidSignature {});
+    }
+    let recovery_param = match signature[64] {
+        0 | 27 => 0,
+        1 | 28 => 1,
+        _ => return Err(ContractError::InvalidSignature {}),
+    };
+    let message_hash = personal_sign_hash(&to_vec(doc)?);
+    let pubkey = api
+        .secp256k1_recover_pubkey(&message_hash, &signature[..64], recovery_param)
+        .map_err(|_| ContractError::InvalidSignature {})?;
+    pubkey_to_evm_address(&pubkey)
+}
+
+// Checks the signature over the sign doc and returns the signer's address
+pub fn verify_sign_doc(
+    api: &dyn Api,
+    doc: &SignDoc,
+    pubkey: &[u8],
+    signature: &[u8],
+) -> Result<Addr, ContractError> {
+    let signer = pubkey_to_address(pubkey)?;
+    let message_hash = keccak256(&to_vec(doc)?);
+    let valid = api
+        .secp256k1_verify(&message_hash, signature, pubkey)
+        .map_err(StdError::from)?;
+    if !valid {
+        return Err(ContractError::InvalidSignature {});
+    }
+    Ok(signer)
+}
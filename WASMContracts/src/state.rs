@@ -1,5 +1,7 @@
+use cosmwasm_std::{Addr, StdResult, Timestamp, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct Constants {
@@ -8,3 +10,311 @@ pub struct Constants {
     pub decimals: u8,
     pub contract: String
 }
+
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct BridgeDirections {
+    pub inbound: bool,
+    pub outbound: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// May call MintCW20 in addition to the OKC module caller
+    BridgeCaller,
+    /// May freeze and unfreeze accounts alongside the owner
+    Compliance,
+    /// May post EVM-side reserve figures alongside the owner
+    ReserveReporter,
+    /// May submit ExecuteSigned on behalf of signers alongside the owner
+    Relayer,
+    /// May challenge pending optimistic mints alongside the owner
+    Watcher,
+}
+
+impl Role {
+    pub fn key(&self) -> &'static [u8] {
+        match self {
+            Role::BridgeCaller => b"bridge_caller",
+            Role::Compliance => b"compliance",
+            Role::ReserveReporter => b"reserve_reporter",
+            Role::Relayer => b"relayer",
+            Role::Watcher => b"watcher",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Storage keys double as the display name
+        f.write_str(std::str::from_utf8(self.key()).unwrap())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryKind {
+    Send,
+    Receive,
+    BridgeIn,
+    BridgeOut,
+    Burn,
+    /// A cancelled SendToEvm returned to the sender, fee included
+    BridgeOutCancelled,
+    /// A bridge fee credited to the fee collector
+    BridgeFee,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct HistoryEntry {
+    /// Per-account sequence number
+    pub id: u64,
+    pub kind: HistoryKind,
+    /// The other account of a transfer, or the EVM recipient of a bridge-out
+    pub counterparty: Option<String>,
+    pub amount: Uint128,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BridgeDirection {
+    /// EVM to wasm, through MintCW20
+    In,
+    /// Wasm to EVM, through SendToEvm
+    Out,
+}
+
+impl BridgeDirection {
+    pub fn key(&self) -> &'static [u8] {
+        match self {
+            BridgeDirection::In => b"in",
+            BridgeDirection::Out => b"out",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BridgeTransferStatus {
+    Pending,
+    Completed,
+    /// Cancelled by the sender after the deadline passed, or by a guardian
+    /// while it was queued
+    Cancelled,
+    /// Above the timelock threshold, sent once the release time has passed
+    Queued,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct BridgeTransfer {
+    pub id: u64,
+    pub direction: BridgeDirection,
+    /// The wasm account that was credited or debited. The contract itself for
+    /// a claimable mint
+    pub account: String,
+    /// The EVM recipient of an outbound transfer, or the EVM address a
+    /// claimable mint is held for
+    pub evm_address: Option<String>,
+    pub amount: Uint128,
+    pub status: BridgeTransferStatus,
+    pub height: u64,
+    pub time: Timestamp,
+    /// Block time in seconds after which an unconfirmed outbound transfer may be cancelled
+    #[serde(default)]
+    pub deadline: Option<u64>,
+    /// Block time in seconds after which a queued transfer may be sent
+    #[serde(default)]
+    pub release_time: Option<u64>,
+    /// Bridge fee taken on top of `amount` by an outbound transfer
+    #[serde(default)]
+    pub fee: Uint128,
+    /// Account the fee is paid to once the transfer is confirmed, the contract
+    /// holds it until then and returns it to the sender on cancellation
+    #[serde(default)]
+    pub fee_collector: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingMintStatus {
+    /// Anyone may finalize it once the challenge period has passed
+    Pending,
+    /// Held until the owner approves or rejects it
+    Challenged,
+}
+
+/// A MintCW20 above the optimistic threshold, not credited yet
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct PendingMint {
+    pub id: u64,
+    pub recipient: String,
+    pub amount: Uint128,
+    pub event_id: Option<String>,
+    pub evm_sender: Option<String>,
+    pub height: u64,
+    pub time: Timestamp,
+    /// First block at which the mint may be finalized
+    pub finalize_height: u64,
+    pub status: PendingMintStatus,
+    pub challenger: Option<Addr>,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct FeeTier {
+    /// Volume over the fee window from which the rate applies
+    pub min_volume: Uint128,
+    pub fee_bps: u16,
+}
+
+/// Fees taken from SendToEvm and MintCW20 amounts, in basis points
+#[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct FeeSchedule {
+    pub collector: Option<Addr>,
+    /// Rate for accounts below every tier
+    pub base_fee_bps: u16,
+    /// Ordered by min_volume, the highest tier reached applies
+    pub tiers: Vec<FeeTier>,
+    /// Days of bridged volume counted towards the tiers, the current one included
+    pub window_days: u32,
+}
+
+/// Accounts that must attest each mint while the list is not empty
+#[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct GuardianSet {
+    /// Increases with every update, attestations from older sets are dropped
+    pub set_id: u64,
+    pub guardians: Vec<Addr>,
+    /// Matching attestations needed to mint
+    pub threshold: u32,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct GuardianVote {
+    pub guardian: Addr,
+    pub recipient: String,
+    pub amount: Uint128,
+    #[serde(default)]
+    pub evm_sender: Option<String>,
+}
+
+/// Attestations collected for an EVM-side event that has not been minted yet
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct MintAttestation {
+    pub event_id: String,
+    pub set_id: u64,
+    pub votes: Vec<GuardianVote>,
+}
+
+/// A MintCW20 whose recipient failed address validation
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct Escrow {
+    pub event_id: String,
+    /// Lower case EVM address, the only one able to release the escrow
+    pub evm_sender: String,
+    /// The recipient as given, which is not a valid address
+    pub recipient: String,
+    pub amount: Uint128,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RefundReason {
+    InvalidRecipient,
+    RecipientFrozen,
+    NotAllowlisted,
+    CapExceeded,
+    InboundPaused,
+    /// A challenged optimistic mint the owner did not approve
+    Rejected,
+}
+
+impl RefundReason {
+    pub fn code(&self) -> &'static str {
+        match self {
+            RefundReason::InvalidRecipient => "invalid_recipient",
+            RefundReason::RecipientFrozen => "recipient_frozen",
+            RefundReason::NotAllowlisted => "not_allowlisted",
+            RefundReason::CapExceeded => "cap_exceeded",
+            RefundReason::InboundPaused => "inbound_paused",
+            RefundReason::Rejected => "rejected",
+        }
+    }
+}
+
+/// A MintCW20 returned to the EVM sender instead of failing
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct Refund {
+    /// Id of the outbound bridge transfer carrying the refund
+    pub transfer_id: u64,
+    pub event_id: Option<String>,
+    /// Lower case EVM address the amount is returned to
+    pub evm_sender: String,
+    pub recipient: String,
+    pub amount: Uint128,
+    pub reason: RefundReason,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+/// Bridged amounts and transfer counts, either running totals or a single day
+#[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct BridgeVolume {
+    pub inbound_amount: Uint128,
+    pub inbound_count: u64,
+    pub outbound_amount: Uint128,
+    pub outbound_count: u64,
+}
+
+impl BridgeVolume {
+    pub fn add(&mut self, direction: BridgeDirection, amount: Uint128) -> StdResult<()> {
+        match direction {
+            BridgeDirection::In => {
+                self.inbound_amount = self.inbound_amount.checked_add(amount)?;
+                self.inbound_count += 1;
+            }
+            BridgeDirection::Out => {
+                self.outbound_amount = self.outbound_amount.checked_add(amount)?;
+                self.outbound_count += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes back a transfer that was counted and later cancelled
+    pub fn sub(&mut self, direction: BridgeDirection, amount: Uint128) -> StdResult<()> {
+        match direction {
+            BridgeDirection::In => {
+                self.inbound_amount = self.inbound_amount.checked_sub(amount)?;
+                self.inbound_count = self.inbound_count.saturating_sub(1);
+            }
+            BridgeDirection::Out => {
+                self.outbound_amount = self.outbound_amount.checked_sub(amount)?;
+                self.outbound_count = self.outbound_count.saturating_sub(1);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct ReserveReport {
+    pub evm_block: u64,
+    /// Locked in Bridge.sol or burned in BridgeERC20.sol, as reported
+    pub reserves: Uint128,
+    pub total_supply: Uint128,
+    pub outbound_in_flight: Uint128,
+    /// Optimistic mints still pending or challenged
+    #[serde(default)]
+    pub pending_inbound: Uint128,
+    /// Amount by which reserves fall below supply plus in-flight transfers and pending mints
+    pub shortfall: Uint128,
+    /// Amount by which reserves exceed supply plus in-flight transfers and pending mints
+    pub surplus: Uint128,
+    pub reporter: String,
+    pub height: u64,
+    pub time: Timestamp,
+}